@@ -0,0 +1,110 @@
+//! Timings behind the polynomial multiplication thresholds in `dense_polynomial.rs`: run with
+//! `cargo run --release --example mul_thresholds` and look for where each column overtakes the
+//! one before it. The last column is the automatic choice of `*`.
+
+use mathlib::{
+    DensePolynomial, El, Int, Mod, MulAlgorithm, SDivSmall, SIntegerResidues, Structure,
+};
+use rug::Integer;
+use std::time::Instant;
+
+fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// A random integer of `bits` bits, reduced modulo `modulus` unless it is zero
+fn random(state: &mut u64, bits: u32, modulus: &Integer) -> Integer {
+    let mut c = Integer::from(xorshift(state));
+    while c.significant_bits() < bits {
+        c = (c << 64) + xorshift(state);
+    }
+    let excess = c.significant_bits() - bits;
+    let c = c >> excess;
+    if *modulus == 0 { c } else { c % modulus }
+}
+
+/// Microseconds per product, the best of several batches
+fn time<S>(
+    a: &El<'_, DensePolynomial<S>>,
+    b: &El<'_, DensePolynomial<S>>,
+    algorithm: Option<MulAlgorithm>,
+) -> f64
+where
+    S: SIntegerResidues + SDivSmall,
+{
+    let poly = a.s;
+    let len = a.v.len();
+    let reps = usize::max(400_000 / (len * len), 3);
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..reps {
+                match algorithm {
+                    Some(algorithm) => std::hint::black_box(poly.mul_with(a, b, algorithm)),
+                    None => std::hint::black_box(a.copy() * b.copy()),
+                };
+            }
+            start.elapsed().as_secs_f64() * 1e6 / reps as f64
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+fn table<S>(s: &S, bits: u32, lengths: &[usize], algorithms: &[MulAlgorithm], state: &mut u64)
+where
+    S: SIntegerResidues<V = Integer> + SDivSmall,
+{
+    let poly = DensePolynomial::new_symb("x", s);
+    let modulus = s.integer_modulus();
+    print!("{:>6}", "len");
+    for algorithm in algorithms {
+        print!(" {:>12}", format!("{algorithm:?}").to_lowercase());
+    }
+    println!(" {:>12}", "auto");
+    for &len in lengths {
+        let mut random_poly = || {
+            poly.el((0..len)
+                .map(|_| random(state, bits, &modulus))
+                .collect::<Vec<_>>())
+        };
+        let (a, b) = (random_poly(), random_poly());
+        print!("{len:>6}");
+        for &algorithm in algorithms {
+            print!(" {:>12.1}", time(&a, &b, Some(algorithm)));
+        }
+        println!(" {:>12.1}", time(&a, &b, None));
+    }
+}
+
+const LENGTHS: [usize; 13] = [16, 24, 32, 48, 64, 96, 128, 192, 256, 384, 512, 768, 1024];
+
+fn main() {
+    let mut state = 0x9e37_79b9_7f4a_7c15;
+    for bits in [64, 256, 1024] {
+        println!("{bits}-bit integer coefficients, microseconds per product");
+        let algorithms = [
+            MulAlgorithm::Schoolbook,
+            MulAlgorithm::Karatsuba,
+            MulAlgorithm::Toom3,
+            MulAlgorithm::Kronecker,
+            MulAlgorithm::Ntt,
+        ];
+        table(&Int, bits, &LENGTHS, &algorithms, &mut state);
+    }
+    // Products with 49 to 61-bit coefficients, which a transform modulo one prime recovers
+    println!("long products of 20-bit integer coefficients, microseconds per product");
+    let algorithms = [MulAlgorithm::Kronecker, MulAlgorithm::Ntt];
+    table(&Int, 20, &[2048, 4096, 8192, 16384, 32768], &algorithms, &mut state);
+    // A prime with a single transform of every length here
+    let prime = Mod::new(Int.el(0x3fff_ffee_0000_0001u64));
+    println!("coefficients modulo a 62-bit transform prime, microseconds per product");
+    let algorithms = [
+        MulAlgorithm::Schoolbook,
+        MulAlgorithm::Karatsuba,
+        MulAlgorithm::Kronecker,
+        MulAlgorithm::Ntt,
+    ];
+    table(&prime, 62, &LENGTHS, &algorithms, &mut state);
+}
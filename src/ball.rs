@@ -0,0 +1,515 @@
+use crate::{El, Int, SAdd, SDiv, SField, SFusedMulAdd, SMul, SSub, Structure, SuperStructure};
+use rug::{
+    Float, Rational,
+    float::{Constant, Round, Special, prec_min},
+    ops::{AssignRound, NegAssign},
+};
+use std::{borrow::Cow, cmp::Ordering, fmt};
+
+/// Precision of the radii, which are rounded up
+const RAD_PREC: u32 = 64;
+
+/// Real balls `[m +/- r]` with midpoints of `prec` bits and radii rounded up, as in Arb. Every
+/// operation returns a ball containing the exact result for all points of its operands, with
+/// the rounding error of the midpoint added to the radius.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ball {
+    prec: u32,
+}
+/// A midpoint and radius, in which midpoints that are NaN equal each other. The default is the
+/// exact zero.
+#[derive(Clone, Debug)]
+pub struct BallValue {
+    mid: Float,
+    rad: Float,
+}
+impl Default for BallValue {
+    fn default() -> Self {
+        Self {
+            mid: Float::new(prec_min()),
+            rad: Float::new(RAD_PREC),
+        }
+    }
+}
+impl PartialEq for BallValue {
+    fn eq(&self, other: &Self) -> bool {
+        (self.mid == other.mid || self.mid.is_nan() && other.mid.is_nan()) && self.rad == other.rad
+    }
+}
+impl Eq for BallValue {}
+/// The exact ball
+impl From<Float> for BallValue {
+    fn from(mid: Float) -> Self {
+        Self {
+            mid,
+            rad: Float::new(RAD_PREC),
+        }
+    }
+}
+impl Structure for Ball {
+    type V = BallValue;
+    fn fmt_v(&self, v: &Self::V, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if v.rad.is_zero() {
+            return write!(f, "{}", v.mid);
+        }
+        write!(f, "[{} +/- {:.3}]", v.mid, v.rad)
+    }
+    /// Rounds the midpoint to the precision, widening the radius by the error
+    fn el<'a>(&'a self, v: impl Into<Self::V>) -> El<'a, Self> {
+        let BallValue { mid, rad } = v.into();
+        let v = if mid.prec() == self.prec && rad.prec() == RAD_PREC {
+            BallValue { mid, rad }
+        } else {
+            let mut v = self.rounded(&mid);
+            v.rad = up(&v.rad + &rad);
+            v
+        };
+        El {
+            v: Cow::Owned(v),
+            s: self,
+        }
+    }
+}
+impl std::fmt::Display for Ball {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if f.alternate() {
+            write!(f, ", ")?;
+        }
+        write!(f, "ball {}", self.prec)
+    }
+}
+/// Encloses integers, exactly when they fit in the precision
+impl SuperStructure for Ball {
+    type Inner = Int;
+    fn inner(&self) -> &Self::Inner {
+        &Int
+    }
+    fn lifted_from<'a>(&'a self, inner: El<'a, Self::Inner>) -> El<'a, Self> {
+        El {
+            v: Cow::Owned(self.rounded(&*inner.v)),
+            s: self,
+        }
+    }
+}
+
+impl Ball {
+    pub fn new(prec: u32) -> Self {
+        assert!(prec >= prec_min(), "precision below the mpfr minimum");
+        Self { prec }
+    }
+    pub fn prec(&self) -> u32 {
+        self.prec
+    }
+    /// `[mid +/- rad]`, widened by the rounding of `mid` to the precision
+    pub fn ball(&self, mid: Float, rad: Float) -> El<'_, Self> {
+        assert!(rad >= 0, "ball with a negative radius");
+        self.el(BallValue { mid, rad })
+    }
+    /// The smallest ball at the precision around `q`
+    pub fn from_rational(&self, q: &Rational) -> El<'_, Self> {
+        El {
+            v: Cow::Owned(self.rounded(q)),
+            s: self,
+        }
+    }
+    pub fn pi(&self) -> El<'_, Self> {
+        El {
+            v: Cow::Owned(self.rounded(Constant::Pi)),
+            s: self,
+        }
+    }
+    /// The ball of all reals, for results without a finite enclosure
+    pub fn indeterminate(&self) -> El<'_, Self> {
+        El {
+            v: Cow::Owned(BallValue {
+                mid: Float::new(self.prec),
+                rad: Float::with_val(RAD_PREC, Special::Infinity),
+            }),
+            s: self,
+        }
+    }
+    pub fn mid(&self, a: &El<'_, Self>) -> Float {
+        a.v.mid.clone()
+    }
+    pub fn rad(&self, a: &El<'_, Self>) -> Float {
+        a.v.rad.clone()
+    }
+    /// Whether the ball `a` contains `x`
+    pub fn contains(&self, a: &El<'_, Self>, x: &Rational) -> bool {
+        match endpoints(&a.v) {
+            Some((lo, hi)) => lo <= *x && *x <= hi,
+            None => !a.v.mid.is_nan(),
+        }
+    }
+    /// Whether `a < b` holds for all points of the balls, for none, or is unknown
+    pub fn lt(&self, a: &El<'_, Self>, b: &El<'_, Self>) -> Option<bool> {
+        let ((a_lo, a_hi), (b_lo, b_hi)) = (endpoints(&a.v)?, endpoints(&b.v)?);
+        if a_hi < b_lo {
+            Some(true)
+        } else if a_lo >= b_hi {
+            Some(false)
+        } else {
+            None
+        }
+    }
+    /// Whether `a <= b` holds for all points of the balls, for none, or is unknown
+    pub fn le(&self, a: &El<'_, Self>, b: &El<'_, Self>) -> Option<bool> {
+        let ((a_lo, a_hi), (b_lo, b_hi)) = (endpoints(&a.v)?, endpoints(&b.v)?);
+        if a_hi <= b_lo {
+            Some(true)
+        } else if a_lo > b_hi {
+            Some(false)
+        } else {
+            None
+        }
+    }
+    pub fn gt(&self, a: &El<'_, Self>, b: &El<'_, Self>) -> Option<bool> {
+        self.lt(b, a)
+    }
+    pub fn ge(&self, a: &El<'_, Self>, b: &El<'_, Self>) -> Option<bool> {
+        self.le(b, a)
+    }
+    /// Whether `a = b`: true only for equal exact balls, false for disjoint ones
+    pub fn equal(&self, a: &El<'_, Self>, b: &El<'_, Self>) -> Option<bool> {
+        let ((a_lo, a_hi), (b_lo, b_hi)) = (endpoints(&a.v)?, endpoints(&b.v)?);
+        if a_hi < b_lo || b_hi < a_lo {
+            Some(false)
+        } else if a_lo == a_hi && a_lo == b_lo && b_lo == b_hi {
+            Some(true)
+        } else {
+            None
+        }
+    }
+    pub fn exp<'a>(&'a self, a: &El<'_, Self>) -> El<'a, Self> {
+        let (lo, hi) = self.bounds(&a.v);
+        self.increasing(lo, hi, Float::exp_round)
+    }
+    /// The natural logarithm, indeterminate unless the ball is positive
+    pub fn log<'a>(&'a self, a: &El<'_, Self>) -> El<'a, Self> {
+        let (lo, hi) = self.bounds(&a.v);
+        if lo.is_nan() || lo <= 0 {
+            return self.indeterminate();
+        }
+        self.increasing(lo, hi, Float::ln_round)
+    }
+    /// The square root, indeterminate unless the ball is nonnegative
+    pub fn sqrt<'a>(&'a self, a: &El<'_, Self>) -> El<'a, Self> {
+        let (lo, hi) = self.bounds(&a.v);
+        if lo.is_nan() || lo < 0 {
+            return self.indeterminate();
+        }
+        self.increasing(lo, hi, Float::sqrt_round)
+    }
+    pub fn atan<'a>(&'a self, a: &El<'_, Self>) -> El<'a, Self> {
+        let (lo, hi) = self.bounds(&a.v);
+        self.increasing(lo, hi, Float::atan_round)
+    }
+    /// The sine, with the radius widened by at most the input radius as `sin` is 1-Lipschitz
+    pub fn sin<'a>(&'a self, a: &El<'_, Self>) -> El<'a, Self> {
+        self.lipschitz(&a.v, self.rounded(a.v.mid.sin_ref()))
+    }
+    /// The cosine, with the radius widened by at most the input radius as `cos` is 1-Lipschitz
+    pub fn cos<'a>(&'a self, a: &El<'_, Self>) -> El<'a, Self> {
+        self.lipschitz(&a.v, self.rounded(a.v.mid.cos_ref()))
+    }
+    /// `f(a)` for `f` with Lipschitz constant one and `image` the ball around `f(mid a)`
+    fn lipschitz<'a>(&'a self, a: &BallValue, mut image: BallValue) -> El<'a, Self> {
+        image.rad = up(&image.rad + &a.rad);
+        El {
+            v: Cow::Owned(image),
+            s: self,
+        }
+    }
+    /// The ball around `v` rounded to nearest at the precision, with radius the rounding error
+    fn rounded<T>(&self, v: T) -> BallValue
+    where
+        Float: AssignRound<T, Round = Round, Ordering = Ordering>,
+    {
+        let (mid, ordering) = Float::with_val_round(self.prec, v, Round::Nearest);
+        let rad = rounding_error(&mid, ordering);
+        BallValue { mid, rad }
+    }
+    /// Lower and upper bounds on the points of `a`, at the precision
+    fn bounds(&self, a: &BallValue) -> (Float, Float) {
+        let lo = Float::with_val_round(self.prec, &a.mid - &a.rad, Round::Down).0;
+        let hi = Float::with_val_round(self.prec, &a.mid + &a.rad, Round::Up).0;
+        (lo, hi)
+    }
+    /// The ball around `[f(lo), f(hi)]` for increasing `f`, rounding each endpoint outward
+    fn increasing(
+        &self,
+        mut lo: Float,
+        mut hi: Float,
+        f: impl Fn(&mut Float, Round) -> Ordering,
+    ) -> El<'_, Self> {
+        f(&mut lo, Round::Down);
+        f(&mut hi, Round::Up);
+        self.enclosing(lo, hi)
+    }
+    /// The ball around the interval `[lo, hi]`
+    fn enclosing(&self, lo: Float, hi: Float) -> El<'_, Self> {
+        if !lo.is_finite() || !hi.is_finite() {
+            return self.indeterminate();
+        }
+        let mid = Float::with_val(self.prec, &lo + &hi) / 2u32;
+        let rad = up(&hi - &mid).max(&up(&mid - &lo));
+        El {
+            v: Cow::Owned(BallValue { mid, rad }),
+            s: self,
+        }
+    }
+    /// The ball around `mid` computed with the given `ordering` and radius bound `rad` for the
+    /// exact operation
+    fn widened(&self, (mid, ordering): (Float, Ordering), rad: Float) -> BallValue {
+        let rad = up(&rad + &rounding_error(&mid, ordering));
+        if rad.is_nan() {
+            // From an infinite radius times zero
+            return self.indeterminate().v.into_owned();
+        }
+        BallValue { mid, rad }
+    }
+}
+
+/// `v` rounded up at the radius precision
+fn up<T>(v: T) -> Float
+where
+    Float: AssignRound<T, Round = Round, Ordering = Ordering>,
+{
+    Float::with_val_round(RAD_PREC, v, Round::Up).0
+}
+
+/// An upper bound on the error of `x` rounded to nearest with the result `ordering`: zero when
+/// exact, otherwise an ulp of `x`
+fn rounding_error(x: &Float, ordering: Ordering) -> Float {
+    match (ordering, x.get_exp()) {
+        (Ordering::Equal, _) => Float::new(RAD_PREC),
+        (_, Some(e)) => Float::with_val(RAD_PREC, 1) << (e - x.prec() as i32),
+        (_, None) => Float::with_val(RAD_PREC, Special::Infinity),
+    }
+}
+
+/// The exact endpoints of a ball with finite midpoint and radius
+fn endpoints(a: &BallValue) -> Option<(Rational, Rational)> {
+    let (mid, rad) = (a.mid.to_rational()?, a.rad.to_rational()?);
+    Some((Rational::from(&mid - &rad), mid + rad))
+}
+
+impl SAdd for Ball {
+    fn zero(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(BallValue::from(Float::new(self.prec)))
+    }
+    fn add<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let mid = Float::with_val_round(self.prec, &lhs.mid + &rhs.mid, Round::Nearest);
+        Cow::Owned(self.widened(mid, up(&lhs.rad + &rhs.rad)))
+    }
+}
+impl SSub for Ball {
+    fn negate<'a>(&'a self, v: &mut Cow<'a, Self::V>) {
+        v.to_mut().mid.neg_assign();
+    }
+    fn sub<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let mid = Float::with_val_round(self.prec, &lhs.mid - &rhs.mid, Round::Nearest);
+        Cow::Owned(self.widened(mid, up(&lhs.rad + &rhs.rad)))
+    }
+}
+impl SMul for Ball {
+    fn one(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(BallValue::from(Float::with_val(self.prec, 1)))
+    }
+    /// With radius `|m1| r2 + |m2| r1 + r1 r2`
+    fn mul<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let mid = Float::with_val_round(self.prec, &lhs.mid * &rhs.mid, Round::Nearest);
+        Cow::Owned(self.widened(mid, product_rad(&lhs, &rhs)))
+    }
+}
+/// By MPFR's fma with one rounding of the midpoint. With the default `mul_coefficients`, as the
+/// cancellation in Karatsuba multiplication would widen the balls.
+impl SFusedMulAdd for Ball {
+    fn fused_mul_add_ref(&self, acc: &mut Self::V, lhs: &Self::V, rhs: &Self::V) {
+        let mid = Float::with_val_round(
+            self.prec,
+            lhs.mid.mul_add_ref(&rhs.mid, &acc.mid),
+            Round::Nearest,
+        );
+        *acc = self.widened(mid, up(&acc.rad + &product_rad(lhs, rhs)));
+    }
+}
+/// Indeterminate when the divisor contains zero, otherwise with radius
+/// `(|m1| r2 + |m2| r1) / (|m2| (|m2| - r2))`
+impl SDiv for Ball {
+    fn div<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let rhs_abs = Float::with_val_round(RAD_PREC, rhs.mid.abs_ref(), Round::Down).0;
+        let gap = Float::with_val_round(RAD_PREC, &rhs_abs - &rhs.rad, Round::Down).0;
+        if gap.is_nan() || gap <= 0 {
+            return self.indeterminate().v;
+        }
+        let num = cross_rad(&lhs, &rhs);
+        let den = Float::with_val_round(RAD_PREC, &rhs_abs * &gap, Round::Down).0;
+        let mid = Float::with_val_round(self.prec, &lhs.mid / &rhs.mid, Round::Nearest);
+        Cow::Owned(self.widened(mid, up(&num / &den)))
+    }
+}
+impl SField for Ball {}
+
+/// `|m1| r2 + |m2| r1 + r1 r2`, rounded up
+fn product_rad(lhs: &BallValue, rhs: &BallValue) -> Float {
+    up(&cross_rad(lhs, rhs) + &up(&lhs.rad * &rhs.rad))
+}
+
+/// `|m1| r2 + |m2| r1`, rounded up
+fn cross_rad(lhs: &BallValue, rhs: &BallValue) -> Float {
+    let a = up(&*lhs.mid.as_abs() * &rhs.rad);
+    let b = up(&*rhs.mid.as_abs() * &lhs.rad);
+    up(&a + &b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pseudorandom balls with midpoints in `[-8, 8]` and radii below `2^-10`, and points of
+    /// each: the endpoints, the midpoint and a point in between
+    fn balls(b: &Ball, count: usize, seed: u64) -> Vec<(El<'_, Ball>, Vec<Rational>)> {
+        let mut state = seed;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            state >> 40
+        };
+        (0..count)
+            .map(|_| {
+                let mid = Rational::from((next() as i64 - (1 << 23), 1 << 20));
+                let rad = Rational::from((next(), 1u64 << 34));
+                let a = b.ball(Float::with_val(64, &mid), Float::with_val(64, &rad));
+                let points = vec![
+                    Rational::from(&mid - &rad),
+                    Rational::from(&mid + &rad),
+                    mid.clone(),
+                    mid + rad / 3u32,
+                ];
+                (a, points)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn arithmetic_encloses_the_exact_results() {
+        let b = Ball::new(30);
+        let samples = balls(&b, 40, 1);
+        for pair in samples.chunks(2) {
+            let [(x, xs), (y, ys)] = pair else {
+                unreachable!()
+            };
+            let (sum, difference) = (x.copy() + y.copy(), x.copy() - y.copy());
+            let (product, quotient) = (x.copy() * y.copy(), x.copy() / y.copy());
+            for (p, q) in Iterator::zip(xs.iter(), ys) {
+                assert!(
+                    b.contains(&sum, &Rational::from(p + q)),
+                    "{sum} at {p}, {q}"
+                );
+                assert!(b.contains(&difference, &Rational::from(p - q)));
+                assert!(b.contains(&product, &Rational::from(p * q)));
+                assert!(b.contains(&quotient, &Rational::from(p / q)));
+            }
+            // Not much wider than the inputs
+            for ball in [sum, difference, product] {
+                assert!(b.rad(&ball) < 0.1, "{ball}");
+            }
+            let mut acc = x.v.clone().into_owned();
+            b.fused_mul_add_ref(&mut acc, &x.v, &y.v);
+            let acc = b.el(acc);
+            for (p, q) in Iterator::zip(xs.iter(), ys) {
+                assert!(b.contains(&acc, &(Rational::from(p * q) + p)));
+            }
+        }
+        // Division by a ball containing zero is indeterminate, and so are its consequences
+        let around_zero = b.ball(Float::with_val(30, 0.001), Float::with_val(64, 0.01));
+        let q = b.el(b.one().into_owned()) / around_zero;
+        assert!(b.rad(&q).is_infinite());
+        assert!(b.contains(&q, &Rational::from(12345)));
+        let product = q.copy() * b.el(b.zero().into_owned());
+        assert!(b.rad(&product).is_infinite());
+    }
+
+    /// Checks that `result` contains `f` at each of the points, which is bracketed by rounding
+    /// down and up at a much higher precision
+    fn assert_encloses(
+        b: &Ball,
+        result: &El<'_, Ball>,
+        f: fn(&mut Float, Round) -> Ordering,
+        points: &[Rational],
+    ) {
+        for x in points {
+            let (mut down, mut up) = (Float::with_val(300, x), Float::with_val(300, x));
+            f(&mut down, Round::Down);
+            f(&mut up, Round::Up);
+            for y in [down, up] {
+                assert!(
+                    b.contains(result, &y.to_rational().unwrap()),
+                    "{result} at {x}"
+                );
+            }
+        }
+        // Not much wider than the input radius makes it
+        let scale = Float::with_val(64, b.mid(result).abs() + 1u32);
+        assert!(b.rad(result) < scale / 100u32, "{result}");
+    }
+
+    #[test]
+    fn elementary_functions_enclose_the_exact_results() {
+        let b = Ball::new(40);
+        for (a, xs) in balls(&b, 40, 2) {
+            assert_encloses(&b, &b.exp(&a), Float::exp_round, &xs);
+            assert_encloses(&b, &b.atan(&a), Float::atan_round, &xs);
+            assert_encloses(&b, &b.sin(&a), Float::sin_round, &xs);
+            assert_encloses(&b, &b.cos(&a), Float::cos_round, &xs);
+            let positive = a + b.from_rational(&Rational::from(9));
+            let xs: Vec<_> = xs.into_iter().map(|x| x + 9u32).collect();
+            assert_encloses(&b, &b.log(&positive), Float::ln_round, &xs);
+            assert_encloses(&b, &b.sqrt(&positive), Float::sqrt_round, &xs);
+        }
+        // Tight for exact inputs
+        let one = b.el(b.one().into_owned());
+        assert!(b.rad(&b.exp(&one)) < Float::with_val(64, Float::i_exp(1, -36)));
+        assert!(!b.contains(&b.pi(), &Rational::from((355, 113))));
+        // Outside their domains log and sqrt are indeterminate
+        let around_zero = b.ball(Float::with_val(40, 0.5), Float::with_val(64, 1));
+        assert!(b.rad(&b.log(&around_zero)).is_infinite());
+        assert!(b.rad(&b.sqrt(&around_zero)).is_infinite());
+        assert!(!b.rad(&b.sqrt(&b.el(b.zero().into_owned()))).is_infinite());
+    }
+
+    #[test]
+    fn comparisons_are_tri_state() {
+        let b = Ball::new(40);
+        let ball = |mid: f64, rad: f64| b.ball(Float::with_val(40, mid), Float::with_val(64, rad));
+        let (x, y) = (ball(1.0, 0.25), ball(2.0, 0.25));
+        assert_eq!(b.lt(&x, &y), Some(true));
+        assert_eq!(b.le(&x, &y), Some(true));
+        assert_eq!(b.gt(&x, &y), Some(false));
+        assert_eq!(b.ge(&x, &y), Some(false));
+        assert_eq!(b.equal(&x, &y), Some(false));
+        // Overlapping balls decide nothing
+        let z = ball(1.5, 0.5);
+        for cmp in [Ball::lt, Ball::le, Ball::gt, Ball::ge, Ball::equal] {
+            assert_eq!(cmp(&b, &x, &z), None);
+        }
+        // Touching balls: x <= w everywhere, but x < w fails at the shared point
+        let w = ball(1.5, 0.25);
+        assert_eq!(b.le(&x, &w), Some(true));
+        assert_eq!(b.lt(&x, &w), None);
+        assert_eq!(b.equal(&x, &w), None);
+        // Exact balls compare as numbers
+        let (one, two) = (ball(1.0, 0.0), ball(2.0, 0.0));
+        assert_eq!(b.equal(&one, &one.copy()), Some(true));
+        assert_eq!(b.lt(&one, &one.copy()), Some(false));
+        assert_eq!(b.le(&one, &one.copy()), Some(true));
+        assert_eq!(b.lt(&one, &two), Some(true));
+        // The same inexact ball may hold two different numbers
+        assert_eq!(b.equal(&x, &x.copy()), None);
+        let all = b.indeterminate();
+        assert_eq!(b.lt(&all, &one), None);
+        assert_eq!(b.equal(&all, &all.copy()), None);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{DensePolynomial, El, SAdd, SField, SFusedMulAdd, Structure, SuperStructure};
+use rug::{Complete, Integer};
+use std::borrow::Cow;
+
+/// `n v` for a nonnegative integer `n`, by doubling and adding
+pub(crate) fn integer_multiple<S: SAdd>(s: &S, v: &S::V, n: &Integer) -> S::V {
+    assert!(*n >= 0, "negative multiple");
+    let mut acc = s.zero().into_owned();
+    for i in (0..n.significant_bits()).rev() {
+        acc = s.add(Cow::Borrowed(&acc), Cow::Borrowed(&acc)).into_owned();
+        if n.get_bit(i) {
+            acc = s.add(Cow::Owned(acc), Cow::Borrowed(v)).into_owned();
+        }
+    }
+    acc
+}
+
+impl<S: SFusedMulAdd> DensePolynomial<S> {
+    /// The formal derivative
+    pub fn derivative<'a>(&'a self, p: &El<'_, Self>) -> El<'a, Self> {
+        self.nth_derivative(p, 1)
+    }
+    /// The `n`th formal derivative, multiplying each coefficient by a falling factorial
+    pub fn nth_derivative<'a>(&'a self, p: &El<'_, Self>, n: usize) -> El<'a, Self> {
+        assert_eq!(self, p.s);
+        // k (k - 1) ... (k - n + 1) for k = n
+        let mut falling = Integer::factorial(n as u32).complete();
+        let mut ret = Vec::with_capacity(p.v.len().saturating_sub(n));
+        for (k, c) in p.v.iter().enumerate().skip(n) {
+            if k > n {
+                falling *= k;
+                falling = falling.div_exact_u(u32::try_from(k - n).unwrap());
+            }
+            ret.push(integer_multiple(self.inner(), c, &falling));
+        }
+        self.el(ret)
+    }
+    /// `p(x + a)`, by composition with `x + a`
+    pub fn taylor_shift<'a>(&'a self, p: &El<'_, Self>, a: &El<'_, S>) -> El<'a, Self> {
+        assert_eq!(self.inner(), a.s);
+        let shift = self.el(vec![(*a.v).clone(), self.inner().one().into_owned()]);
+        self.compose(p, &shift)
+    }
+    /// `x^n p(1/x)`, the coefficients reversed within degree `n`, which must be at least the
+    /// degree of `p`
+    pub fn reverse<'a>(&'a self, p: &El<'_, Self>, n: usize) -> El<'a, Self> {
+        assert_eq!(self, p.s);
+        assert!(p.v.len() <= n + 1, "reversal below the degree");
+        let mut ret = vec![S::V::default(); n + 1 - p.v.len()];
+        ret.extend(p.v.iter().rev().cloned());
+        self.el(ret)
+    }
+}
+
+impl<S: SFusedMulAdd + SField> DensePolynomial<S> {
+    /// The antiderivative with zero constant term. Panics when some `k` up to the degree plus
+    /// one vanishes in the coefficients, as it does in positive characteristic.
+    pub fn integral<'a>(&'a self, p: &El<'_, Self>) -> El<'a, Self> {
+        assert_eq!(self, p.s);
+        let s = self.inner();
+        let mut ret = vec![s.zero().into_owned()];
+        for (k, c) in p.v.iter().enumerate() {
+            let k1 = integer_multiple(s, &s.one(), &Integer::from(k + 1));
+            assert!(k1 != *s.zero(), "integral in positive characteristic");
+            ret.push(s.div(Cow::Borrowed(c), Cow::Owned(k1)).into_owned());
+        }
+        self.el(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Int, Mod, Rat};
+    use rug::Rational;
+
+    fn ints(v: &[i64]) -> Vec<Integer> {
+        v.iter().map(|&c| Integer::from(c)).collect()
+    }
+
+    /// `len` pseudorandom coefficients in `[-1000, 1000]`
+    fn coefficients(len: usize, seed: u64) -> Vec<Integer> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                Integer::from((state >> 33) % 2001) - 1000
+            })
+            .collect()
+    }
+
+    #[test]
+    fn derivatives() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        assert_eq!(
+            zx.derivative(&zx.el(ints(&[5, 3, 0, 2]))),
+            zx.el(ints(&[3, 0, 6]))
+        );
+        let p = zx.el(coefficients(30, 1));
+        let mut repeated = p.copy();
+        for n in 0..=32 {
+            assert_eq!(zx.nth_derivative(&p, n), repeated, "{n}");
+            repeated = zx.derivative(&repeated);
+        }
+        // Multiples of the characteristic vanish
+        let f7 = Mod::new_prime(Int.el(7));
+        let f7x = DensePolynomial::new_symb("x", &f7);
+        let p = f7x.el(ints(&[1, 1, 1, 1, 1, 1, 1, 1, 1]));
+        assert_eq!(f7x.derivative(&p), f7x.el(ints(&[1, 2, 3, 4, 5, 6, 0, 1])));
+        assert_eq!(f7x.nth_derivative(&p, 7), f7x.el(Vec::new()));
+        assert_eq!(f7x.nth_derivative(&p, 2), f7x.el(ints(&[2, 6, 5, 6, 2])));
+    }
+
+    #[test]
+    fn taylor_shifts_agree_with_composition() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        // (x + 1)^2 = x^2 + 2x + 1
+        let square = zx.el(ints(&[0, 0, 1]));
+        assert_eq!(
+            zx.taylor_shift(&square, &Int.el(1)),
+            zx.el(ints(&[1, 2, 1]))
+        );
+        for (len, a) in [(0, 3), (1, -2), (10, 0), (40, 7), (65, -1)] {
+            let p = zx.el(coefficients(len, len as u64));
+            let a = Int.el(a);
+            let shifted = zx.taylor_shift(&p, &a);
+            let x_plus_a = zx.el(vec![(*a.v).clone(), Integer::from(1)]);
+            assert_eq!(shifted, zx.evaluate_in(&p, &x_plus_a, |c| c.lift(&zx)));
+            // Shifting back recovers p
+            let minus_a = Int.el(-(*a.v).clone());
+            assert_eq!(zx.taylor_shift(&shifted, &minus_a), p);
+        }
+    }
+
+    #[test]
+    fn reversal() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        let p = zx.el(ints(&[1, 2, 3]));
+        assert_eq!(zx.reverse(&p, 2), zx.el(ints(&[3, 2, 1])));
+        assert_eq!(zx.reverse(&p, 4), zx.el(ints(&[0, 0, 3, 2, 1])));
+        // Trailing zeros become leading zeros and are trimmed
+        let q = zx.el(ints(&[0, 0, 5, 1]));
+        assert_eq!(zx.reverse(&q, 3), zx.el(ints(&[1, 5])));
+        assert_eq!(zx.reverse(&zx.reverse(&p, 6), 6), p);
+    }
+
+    #[test]
+    fn integrals_in_small_degree_over_a_prime_field() {
+        let f7 = Mod::new_prime(Int.el(7));
+        let f7x = DensePolynomial::new_symb("x", &f7);
+        let p = f7x.el(ints(&[3, 1, 4, 1, 5, 2]));
+        let integral = f7x.integral(&p);
+        assert_eq!(integral.v[0], *f7.zero());
+        assert_eq!(f7x.derivative(&integral), p);
+    }
+
+    #[test]
+    fn integrals_over_the_rationals() {
+        let qx = DensePolynomial::new_symb("x", &Rat);
+        let c = coefficients(41, 5);
+        let p = qx.el(c.iter().cloned().map(Rational::from).collect::<Vec<_>>());
+        let integral = qx.integral(&p);
+        assert_eq!(integral.v.len(), 42);
+        assert_eq!(integral.v[0], 0);
+        for (k, c) in c.iter().enumerate() {
+            assert_eq!(integral.v[k + 1], Rational::from((c.clone(), k + 1)));
+        }
+        assert_eq!(qx.derivative(&integral), p);
+    }
+
+    #[test]
+    #[should_panic(expected = "integral in positive characteristic")]
+    fn integrals_of_degree_p_minus_one_panic() {
+        let f7 = Mod::new_prime(Int.el(7));
+        let f7x = DensePolynomial::new_symb("x", &f7);
+        f7x.integral(&f7x.el(ints(&[0, 0, 0, 0, 0, 0, 1])));
+    }
+}
@@ -0,0 +1,226 @@
+use crate::{El, Real, SAdd, SDiv, SField, SFusedMulAdd, SMul, SSub, Structure, SuperStructure};
+use rug::{
+    Float,
+    float::{Round, prec_min},
+    ops::AssignRound,
+};
+use std::{borrow::Cow, cmp::Ordering, fmt};
+
+/// Complex numbers with real and imaginary parts in `Real`, by MPC. Every operation rounds both
+/// parts of its exact result once, in the rounding mode of the parts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Complex {
+    real: Real,
+}
+/// A `rug::Complex` as a structure value, in which parts that are NaN equal each other. The
+/// default is a zero of the least precision.
+#[derive(Clone, Debug)]
+pub struct ComplexValue(pub rug::Complex);
+impl Default for ComplexValue {
+    fn default() -> Self {
+        Self(rug::Complex::new(prec_min()))
+    }
+}
+impl PartialEq for ComplexValue {
+    fn eq(&self, other: &Self) -> bool {
+        let eq = |x: &Float, y: &Float| x == y || x.is_nan() && y.is_nan();
+        eq(self.0.real(), other.0.real()) && eq(self.0.imag(), other.0.imag())
+    }
+}
+impl Eq for ComplexValue {}
+impl From<rug::Complex> for ComplexValue {
+    fn from(v: rug::Complex) -> Self {
+        Self(v)
+    }
+}
+impl Structure for Complex {
+    type V = ComplexValue;
+    fn fmt_v(&self, v: &Self::V, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", v.0)
+    }
+    /// Rounds `v` to the structure's precision
+    fn el<'a>(&'a self, v: impl Into<Self::V>) -> El<'a, Self> {
+        let v = v.into().0;
+        let v = if v.prec() == (self.prec(), self.prec()) {
+            ComplexValue(v)
+        } else {
+            self.value(v)
+        };
+        El {
+            v: Cow::Owned(v),
+            s: self,
+        }
+    }
+}
+impl std::fmt::Display for Complex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if f.alternate() {
+            write!(f, ", ")?;
+        }
+        write!(f, "complex {}", self.prec())?;
+        if self.round() != Round::Nearest {
+            write!(f, " round {}", format!("{:?}", self.round()).to_lowercase())?;
+        }
+        Ok(())
+    }
+}
+/// Lifts real numbers as complex numbers with imaginary part zero
+impl SuperStructure for Complex {
+    type Inner = Real;
+    fn inner(&self) -> &Self::Inner {
+        &self.real
+    }
+    fn lifted_from<'a>(&'a self, inner: El<'a, Self::Inner>) -> El<'a, Self> {
+        assert_eq!(&self.real, inner.s);
+        El {
+            v: Cow::Owned(self.value(&inner.v.0)),
+            s: self,
+        }
+    }
+}
+
+impl Complex {
+    /// Rounding to nearest
+    pub fn new(prec: u32) -> Self {
+        Self::with_round(prec, Round::Nearest)
+    }
+    /// Rounding both parts with `round`
+    pub fn with_round(prec: u32, round: Round) -> Self {
+        Self {
+            real: Real::with_round(prec, round),
+        }
+    }
+    pub fn prec(&self) -> u32 {
+        self.real.prec()
+    }
+    pub fn round(&self) -> Round {
+        self.real.round()
+    }
+    pub fn i(&self) -> El<'_, Self> {
+        El {
+            v: Cow::Owned(self.value((0, 1))),
+            s: self,
+        }
+    }
+    /// `re + i im`
+    pub fn from_parts<'a>(&'a self, re: &El<'_, Real>, im: &El<'_, Real>) -> El<'a, Self> {
+        assert_eq!(&self.real, re.s);
+        assert_eq!(&self.real, im.s);
+        El {
+            v: Cow::Owned(self.value((&re.v.0, &im.v.0))),
+            s: self,
+        }
+    }
+    pub fn re<'a>(&'a self, a: &El<'_, Self>) -> El<'a, Real> {
+        self.real.el(a.v.0.real().clone())
+    }
+    pub fn im<'a>(&'a self, a: &El<'_, Self>) -> El<'a, Real> {
+        self.real.el(a.v.0.imag().clone())
+    }
+    /// The absolute value, rounded once
+    pub fn abs<'a>(&'a self, a: &El<'_, Self>) -> El<'a, Real> {
+        El {
+            v: Cow::Owned(self.real.value(a.v.0.abs_ref())),
+            s: &self.real,
+        }
+    }
+    /// `v` at the structure's precision, rounded once
+    pub(crate) fn value<T>(&self, v: T) -> ComplexValue
+    where
+        rug::Complex: AssignRound<T, Round = (Round, Round), Ordering = (Ordering, Ordering)>,
+    {
+        let (prec, round) = (self.prec(), self.round());
+        ComplexValue(rug::Complex::with_val_round(prec, v, (round, round)).0)
+    }
+}
+
+impl SAdd for Complex {
+    fn zero(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(ComplexValue(rug::Complex::new(self.prec())))
+    }
+    fn add<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        Cow::Owned(self.value(&lhs.0 + &rhs.0))
+    }
+}
+impl SSub for Complex {
+    fn negate<'a>(&'a self, v: &mut Cow<'a, Self::V>) {
+        *v = Cow::Owned(self.value(-&v.0));
+    }
+    fn sub<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        Cow::Owned(self.value(&lhs.0 - &rhs.0))
+    }
+}
+impl SMul for Complex {
+    fn one(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(self.value(1))
+    }
+    fn mul<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        Cow::Owned(self.value(&lhs.0 * &rhs.0))
+    }
+}
+/// With the default `mul_coefficients`, as for `Real`
+impl SFusedMulAdd for Complex {
+    fn fused_mul_add_ref(&self, acc: &mut Self::V, lhs: &Self::V, rhs: &Self::V) {
+        *acc = self.value(lhs.0.mul_add_ref(&rhs.0, &acc.0));
+    }
+}
+impl SDiv for Complex {
+    fn div<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        Cow::Owned(self.value(&lhs.0 / &rhs.0))
+    }
+}
+impl SField for Complex {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rug::Rational;
+
+    #[test]
+    fn fused_multiply_adds_round_once() {
+        // (1 + 2^-23 i) (1 - 2^-23 i) - 1 = 2^-46, which separate roundings lose
+        let c = Complex::new(24);
+        let eps = Float::with_val(24, Float::i_exp(1, -23));
+        let a = c.el(rug::Complex::with_val(24, (1, &eps)));
+        let b = c.el(rug::Complex::with_val(24, (1, -eps)));
+        let mut acc = c.el(rug::Complex::with_val(24, (-1, 0))).v.into_owned();
+        assert_eq!(*(a.copy() * b.copy() + c.el(acc.clone())).v, *c.zero());
+        c.fused_mul_add_ref(&mut acc, &a.v, &b.v);
+        assert_eq!(*acc.0.real(), Rational::from((1, 1u64 << 46)));
+        assert!(acc.0.imag().is_zero());
+    }
+
+    #[test]
+    fn rounding_modes() {
+        let third = Rational::from((1, 3));
+        for (round, below) in [(Round::Down, true), (Round::Up, false)] {
+            let c = Complex::with_round(20, round);
+            let real = c.inner();
+            let x = c.from_parts(
+                &real.from_rational(&third),
+                &real.from_rational(&-third.clone()),
+            );
+            assert_eq!(*x.v.0.real() < third, below);
+            assert_eq!(*x.v.0.imag() < -third.clone(), below);
+            // Both parts of a quotient round in the mode
+            let q =
+                c.el(rug::Complex::with_val(20, (1, 0))) / c.el(rug::Complex::with_val(20, (3, 0)));
+            assert_eq!(*q.v.0.real() < third, below);
+            assert_eq!(c.re(&q), real.from_rational(&third));
+            assert!(c.im(&q).v.0.is_zero());
+        }
+        let c = Complex::new(53);
+        let i = c.i();
+        assert_eq!(
+            i.copy() * i.copy(),
+            c.el(rug::Complex::with_val(53, (-1, 0)))
+        );
+        let z = c.el(rug::Complex::with_val(53, (3, 4)));
+        assert_eq!(c.abs(&z).v.0, 5);
+        assert_eq!(
+            Complex::with_round(53, Round::Zero).to_string(),
+            "complex 53 round zero"
+        );
+        assert_eq!(c.to_string(), "complex 53");
+    }
+}
@@ -0,0 +1,305 @@
+use crate::{Complex, DensePolynomial, El, Int, SMul, Structure, Super2Structure};
+use rug::{
+    Assign, Float, Integer, Rational,
+    float::{Constant, Round},
+    ops::{DivAssignRound, MulAssignRound},
+};
+
+/// Precision of the inclusion radii and the bounds behind them
+const BOUND_PREC: u32 = 64;
+
+impl DensePolynomial<Int> {
+    /// The distinct complex roots of the nonzero `p`, as midpoints `z` of at least `prec` bits
+    /// with radii `r` such that the closed disc of radius `r` about `z` contains exactly that
+    /// root, in increasing order of real and then imaginary part.
+    ///
+    /// By Aberth–Ehrlich iteration on the square-free part `q` of degree `n`, certified by the
+    /// Gershgorin-type inclusion: for distinct approximations `z_i`, the discs of radii
+    /// `n |q(z_i) / (lc(q) prod_{j != i} (z_i - z_j))|` cover the roots, with `k` roots in a
+    /// union of `k` discs apart from the others. The values `q(z_i)` are exact and the radii
+    /// rounded outward; while some discs overlap, or reach zero when that is a root too, the
+    /// working precision doubles.
+    pub fn complex_roots(&self, p: &El<'_, Self>, prec: u32) -> Vec<(rug::Complex, Float)> {
+        let mut q = self.square_free_part(p).v.into_owned();
+        let mut ret = Vec::new();
+        if q[0] == 0 {
+            ret.push((rug::Complex::new(prec), Float::new(BOUND_PREC)));
+            q.remove(0);
+        }
+        if q.len() > 1 {
+            let mut prec = prec;
+            let mut z = initial_points(&q, prec);
+            loop {
+                z = aberth(&q, z, &Complex::new(prec));
+                if let Some(radii) = inclusion_radii(&q, &z, !ret.is_empty()) {
+                    ret.extend(z.into_iter().zip(radii));
+                    break;
+                }
+                prec *= 2;
+                let stuck = z.iter().enumerate().any(|(i, a)| {
+                    !a.real().is_finite() || !a.imag().is_finite() || z[..i].iter().any(|b| a == b)
+                });
+                z = if stuck {
+                    initial_points(&q, prec)
+                } else {
+                    z.into_iter()
+                        .map(|a| rug::Complex::with_val(prec, a))
+                        .collect()
+                };
+            }
+        }
+        ret.sort_by(|(a, _), (b, _)| {
+            (a.real().total_cmp(b.real())).then_with(|| a.imag().total_cmp(b.imag()))
+        });
+        ret
+    }
+    /// `complex_roots` of the nonzero polynomial with rational coefficients `p`, lowest degree
+    /// first, through its multiple with the least common denominator
+    pub fn complex_roots_rational(&self, p: &[Rational], prec: u32) -> Vec<(rug::Complex, Float)> {
+        let den = p.iter().fold(Integer::from(1), |l, c| l.lcm(c.denom()));
+        let q: Vec<Integer> = p
+            .iter()
+            .map(|c| Rational::from(c * &den).into_numer_denom().0)
+            .collect();
+        self.complex_roots(&self.el(q), prec)
+    }
+}
+
+/// Starting points for the roots of `q = sum a_k x^k` by the Newton polygon: for each edge
+/// from `i` to `j` of the upper convex hull of the points `(k, log |a_k|)`, `j - i` points
+/// evenly spaced on the circle about zero of radius `|a_i / a_j|^(1 / (j - i))`, near which
+/// that many roots lie, with angles off the symmetry axes
+fn initial_points(q: &[Integer], prec: u32) -> Vec<rug::Complex> {
+    let n = q.len() - 1;
+    let points = q
+        .iter()
+        .enumerate()
+        .filter(|(_, a)| **a != 0)
+        .map(|(k, a)| (k, Float::with_val(BOUND_PREC, a).abs().log2()));
+    let mut hull: Vec<(usize, Float)> = Vec::new();
+    for p in points {
+        while let [.., (i, li), (j, lj)] = &hull[..]
+            && Float::with_val(BOUND_PREC, lj - li) * (p.0 - i) as u32
+                <= Float::with_val(BOUND_PREC, &p.1 - li) * (j - i) as u32
+        {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    let tau = Float::with_val(prec, Constant::Pi) * 2u32;
+    let mut ret = Vec::with_capacity(n);
+    for w in hull.windows(2) {
+        let [(i, li), (j, lj)] = w else {
+            unreachable!()
+        };
+        let count = (j - i) as u32;
+        let radius = Float::with_val(prec, li - lj) / count;
+        let radius = radius.exp2();
+        for m in 0..count {
+            let turns =
+                Float::with_val(prec, m) / count + Float::with_val(prec, *i as u32) / n as u32;
+            let angle: Float = turns * &tau + 0.5;
+            let (sin, cos) = angle.sin_cos(Float::new(prec));
+            ret.push(rug::Complex::with_val(prec, (cos * &radius, sin * &radius)));
+        }
+    }
+    ret
+}
+
+/// Aberth–Ehrlich iteration `z_i <- z_i - w_i / (1 - w_i sum_{j != i} 1 / (z_i - z_j))` with
+/// the Newton corrections `w_i = q(z_i) / q'(z_i)`, updating in place. As convergence is at
+/// least quadratic, `z_i` is final after a correction below half the precision of `c`; the
+/// iteration stops when all are, or when the iterations run out.
+fn aberth(q: &[Integer], z: Vec<rug::Complex>, c: &Complex) -> Vec<rug::Complex> {
+    let poly = DensePolynomial::new_symb("x", c);
+    let f = poly.el(q
+        .iter()
+        .map(|a| c.el2(a.clone()).v.into_owned())
+        .collect::<Vec<_>>());
+    let df = poly.derivative(&f);
+    let one = c.el(c.one().into_owned());
+    let mut z: Vec<El<'_, Complex>> = z.into_iter().map(|a| c.el(a)).collect();
+    let mut done = vec![false; z.len()];
+    let max_iterations = 64 + 4 * q.len() + c.prec() as usize;
+    for _ in 0..max_iterations {
+        for i in 0..z.len() {
+            if done[i] {
+                continue;
+            }
+            let zi = &z[i];
+            let newton = poly.evaluate(&f, zi) / poly.evaluate(&df, zi);
+            // In place, as this sum is most of the work
+            let mut sum = rug::Complex::new(c.prec());
+            let mut diff = rug::Complex::new(c.prec());
+            for (_, zj) in z.iter().enumerate().filter(|(j, _)| *j != i) {
+                diff.assign(&zi.v.0 - &zj.v.0);
+                diff.recip_mut();
+                sum += &diff;
+            }
+            let sum = c.el(sum);
+            let w = newton.copy() / (one.copy() - newton.copy() * sum);
+            let next = zi.copy() - w.copy();
+            if !next.v.0.real().is_finite() || !next.v.0.imag().is_finite() {
+                continue;
+            }
+            let (w, next_abs) = (c.abs(&w), c.abs(&next));
+            done[i] = w.v.0 <= (next_abs.v.0.clone() >> (c.prec() / 2));
+            z[i] = c.el(next.v.into_owned());
+        }
+        if done.iter().all(|d| *d) {
+            break;
+        }
+    }
+    z.into_iter().map(|a| a.v.into_owned().0).collect()
+}
+
+/// Upper bounds on the inclusion radii of the distinct `z_i` for the roots of `q`, or `None`
+/// when the discs are not pairwise disjoint, or with `zero_root` when one reaches zero, a root
+/// of the polynomial `q` came from
+fn inclusion_radii(q: &[Integer], z: &[rug::Complex], zero_root: bool) -> Option<Vec<Float>> {
+    let n = z.len();
+    let lc = Float::with_val_round(BOUND_PREC, &q[n], Round::Zero)
+        .0
+        .abs();
+    // Lower bounds on |z_i - z_j|
+    let mut dist = vec![vec![Float::new(BOUND_PREC); n]; n];
+    for i in 0..n {
+        for j in 0..i {
+            let (a, b) = (&z[i], &z[j]);
+            let dx = Float::with_val_round(BOUND_PREC, a.real() - b.real(), Round::Zero).0;
+            let dy = Float::with_val_round(BOUND_PREC, a.imag() - b.imag(), Round::Zero).0;
+            let d = Float::with_val_round(BOUND_PREC, dx.hypot_ref(&dy), Round::Down).0;
+            dist[j][i] = d.clone();
+            dist[i][j] = d;
+        }
+    }
+    let mut radii = Vec::with_capacity(n);
+    for (i, zi) in z.iter().enumerate() {
+        let mut den = lc.clone();
+        for (_, d) in dist[i].iter().enumerate().filter(|(j, _)| *j != i) {
+            den.mul_assign_round(d, Round::Down);
+        }
+        let mut r = abs_value_bound(q, zi);
+        r.mul_assign_round(n as u32, Round::Up);
+        r.div_assign_round(&den, Round::Up);
+        radii.push(r);
+    }
+    for i in 0..n {
+        for j in 0..i {
+            let reach = Float::with_val_round(BOUND_PREC, &radii[i] + &radii[j], Round::Up).0;
+            if dist[i][j] <= reach {
+                return None;
+            }
+        }
+        if zero_root {
+            let [x, y] = [z[i].real(), z[i].imag()]
+                .map(|c| Float::with_val_round(BOUND_PREC, c, Round::Zero).0);
+            let abs = Float::with_val_round(BOUND_PREC, x.hypot_ref(&y), Round::Down).0;
+            if abs <= radii[i] {
+                return None;
+            }
+        }
+    }
+    Some(radii)
+}
+
+/// An upper bound on `|q(z)|`, from the exact value: with `z = (x + iy) 2^-s` for integers
+/// `x` and `y`, `2^(s n) q(z) = sum a_k (x + iy)^k 2^(s (n - k))` in Gaussian integers
+fn abs_value_bound(q: &[Integer], z: &rug::Complex) -> Float {
+    let n = q.len() - 1;
+    let (mx, ex) = z.real().to_integer_exp().unwrap_or_default();
+    let (my, ey) = z.imag().to_integer_exp().unwrap_or_default();
+    let e = match (mx == 0, my == 0) {
+        (true, _) => ey,
+        (_, true) => ex,
+        _ => ex.min(ey),
+    };
+    let aligned = |m: Integer, ex: i32| if m == 0 { m } else { m << (ex - e) as u32 };
+    let (x, y) = (aligned(mx, ex), aligned(my, ey));
+    let (x, y, s) = if e >= 0 {
+        (x << e as u32, y << e as u32, 0)
+    } else {
+        (x, y, e.unsigned_abs())
+    };
+    let mut re = q[n].clone();
+    let mut im = Integer::new();
+    for (k, a) in q[..n].iter().enumerate().rev() {
+        let next_re = Integer::from(&re * &x) - Integer::from(&im * &y);
+        im = Integer::from(&re * &y) + Integer::from(&im * &x);
+        re = next_re + Integer::from(a << (s * (n - k) as u32));
+    }
+    let re = Float::with_val_round(BOUND_PREC, &re, Round::AwayZero)
+        .0
+        .abs();
+    let im = Float::with_val_round(BOUND_PREC, &im, Round::AwayZero)
+        .0
+        .abs();
+    let abs = Float::with_val_round(BOUND_PREC, re.hypot_ref(&im), Round::Up).0;
+    abs >> (s * n as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ints(v: &[i64]) -> Vec<Integer> {
+        v.iter().map(|&c| Integer::from(c)).collect()
+    }
+
+    #[test]
+    fn discs_exclude_the_zero_root() {
+        // The disc about 1/2 for the root 1 of x - 1 just reaches zero
+        let z = [rug::Complex::with_val(53, (0.5, 0))];
+        let radii = inclusion_radii(&ints(&[-1, 1]), &z, false).unwrap();
+        assert_eq!(radii[0], 0.5);
+        assert!(inclusion_radii(&ints(&[-1, 1]), &z, true).is_none());
+    }
+
+    #[test]
+    fn roots_near_zero() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        // x^2 (2^80 x - 1) (x^2 + 2^-100), scaled to integer coefficients
+        let small = Integer::from(1) << 80u32;
+        let tiny = zx.el(vec![
+            Integer::from(1),
+            Integer::ZERO,
+            Integer::from(1) << 100u32,
+        ]);
+        let p = zx.el(ints(&[0, 0, 1])) * zx.el(vec![Integer::from(-1), small]) * tiny;
+        let roots = zx.complex_roots(&p, 32);
+        assert_eq!(roots.len(), 4);
+        let zero = roots.iter().filter(|(z, _)| z.is_zero()).count();
+        assert_eq!(zero, 1);
+        for (i, (z, r)) in roots.iter().enumerate() {
+            if !z.is_zero() {
+                assert!(Float::with_val(64, z.abs_ref()) > *r);
+            }
+            for (w, s) in &roots[..i] {
+                let d = Float::with_val(64, (z.clone() - w).abs_ref());
+                assert!(d > Float::with_val(64, r + s));
+            }
+        }
+    }
+
+    #[test]
+    fn known_roots_lie_in_their_discs() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        // (x^2 + 1)(x^2 - 2) x
+        let p = zx.el(ints(&[0, -2, 0, -1, 0, 1]));
+        let roots = zx.complex_roots(&p, 100);
+        let sqrt2 = Float::with_val(200, 2).sqrt();
+        let expected = [
+            rug::Complex::with_val(200, (-sqrt2.clone(), 0)),
+            rug::Complex::with_val(200, (0, -1)),
+            rug::Complex::with_val(200, (0, 0)),
+            rug::Complex::with_val(200, (0, 1)),
+            rug::Complex::with_val(200, (sqrt2, 0)),
+        ];
+        assert_eq!(roots.len(), expected.len());
+        for ((z, r), e) in roots.iter().zip(&expected) {
+            assert!(z.prec().0 >= 100);
+            let d = Float::with_val(200, (rug::Complex::with_val(200, z) - e).abs_ref());
+            assert!(d <= *r, "{z} {r}");
+        }
+    }
+}
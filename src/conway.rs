@@ -0,0 +1,147 @@
+use rug::Integer;
+
+/// Conway polynomials over `F_p` as `(p, coefficients)`, with coefficients in increasing degree.
+/// Covers every `p^n` up to 10^6 for primes `p` below 110, computed from the definition: the
+/// least primitive polynomial in the Conway ordering compatible with those of every degree
+/// dividing `n`.
+static CONWAY_POLYNOMIALS: &[(u32, &[u32])] = &[
+    (2, &[1, 1]),
+    (2, &[1, 1, 1]),
+    (2, &[1, 1, 0, 1]),
+    (2, &[1, 1, 0, 0, 1]),
+    (2, &[1, 0, 1, 0, 0, 1]),
+    (2, &[1, 1, 0, 1, 1, 0, 1]),
+    (2, &[1, 1, 0, 0, 0, 0, 0, 1]),
+    (2, &[1, 0, 1, 1, 1, 0, 0, 0, 1]),
+    (2, &[1, 0, 0, 0, 1, 0, 0, 0, 0, 1]),
+    (2, &[1, 1, 1, 1, 0, 1, 1, 0, 0, 0, 1]),
+    (2, &[1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
+    (2, &[1, 1, 0, 1, 0, 1, 1, 1, 0, 0, 0, 0, 1]),
+    (2, &[1, 1, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
+    (2, &[1, 0, 0, 1, 0, 1, 0, 1, 0, 0, 0, 0, 0, 0, 1]),
+    (2, &[1, 0, 1, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
+    (2, &[1, 0, 1, 1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
+    (2, &[1, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
+    (
+        2,
+        &[1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0, 1],
+    ),
+    (
+        2,
+        &[1, 1, 1, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+    ),
+    (3, &[1, 1]),
+    (3, &[2, 2, 1]),
+    (3, &[1, 2, 0, 1]),
+    (3, &[2, 0, 0, 2, 1]),
+    (3, &[1, 2, 0, 0, 0, 1]),
+    (3, &[2, 2, 1, 0, 2, 0, 1]),
+    (3, &[1, 0, 2, 0, 0, 0, 0, 1]),
+    (3, &[2, 2, 2, 0, 1, 2, 0, 0, 1]),
+    (3, &[1, 1, 2, 2, 0, 0, 0, 0, 0, 1]),
+    (3, &[2, 1, 0, 0, 2, 2, 2, 0, 0, 0, 1]),
+    (3, &[1, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
+    (3, &[2, 0, 1, 0, 1, 1, 1, 0, 0, 0, 0, 0, 1]),
+    (5, &[3, 1]),
+    (5, &[2, 4, 1]),
+    (5, &[3, 3, 0, 1]),
+    (5, &[2, 4, 4, 0, 1]),
+    (5, &[3, 4, 0, 0, 0, 1]),
+    (5, &[2, 0, 1, 4, 1, 0, 1]),
+    (5, &[3, 3, 0, 0, 0, 0, 0, 1]),
+    (5, &[2, 4, 3, 0, 1, 0, 0, 0, 1]),
+    (7, &[4, 1]),
+    (7, &[3, 6, 1]),
+    (7, &[4, 0, 6, 1]),
+    (7, &[3, 4, 5, 0, 1]),
+    (7, &[4, 1, 0, 0, 0, 1]),
+    (7, &[3, 6, 4, 5, 1, 0, 1]),
+    (7, &[4, 6, 0, 0, 0, 0, 0, 1]),
+    (11, &[9, 1]),
+    (11, &[2, 7, 1]),
+    (11, &[9, 2, 0, 1]),
+    (11, &[2, 10, 8, 0, 1]),
+    (11, &[9, 0, 10, 0, 0, 1]),
+    (13, &[11, 1]),
+    (13, &[2, 12, 1]),
+    (13, &[11, 2, 0, 1]),
+    (13, &[2, 12, 3, 0, 1]),
+    (13, &[11, 4, 0, 0, 0, 1]),
+    (17, &[14, 1]),
+    (17, &[3, 16, 1]),
+    (17, &[14, 1, 0, 1]),
+    (17, &[3, 10, 7, 0, 1]),
+    (19, &[17, 1]),
+    (19, &[2, 18, 1]),
+    (19, &[17, 4, 0, 1]),
+    (19, &[2, 11, 2, 0, 1]),
+    (23, &[18, 1]),
+    (23, &[5, 21, 1]),
+    (23, &[18, 2, 0, 1]),
+    (23, &[5, 19, 3, 0, 1]),
+    (29, &[27, 1]),
+    (29, &[2, 24, 1]),
+    (29, &[27, 2, 0, 1]),
+    (29, &[2, 15, 2, 0, 1]),
+    (31, &[28, 1]),
+    (31, &[3, 29, 1]),
+    (31, &[28, 1, 0, 1]),
+    (31, &[3, 16, 3, 0, 1]),
+    (37, &[35, 1]),
+    (37, &[2, 33, 1]),
+    (37, &[35, 6, 0, 1]),
+    (41, &[35, 1]),
+    (41, &[6, 38, 1]),
+    (41, &[35, 1, 0, 1]),
+    (43, &[40, 1]),
+    (43, &[3, 42, 1]),
+    (43, &[40, 1, 0, 1]),
+    (47, &[42, 1]),
+    (47, &[5, 45, 1]),
+    (47, &[42, 3, 0, 1]),
+    (53, &[51, 1]),
+    (53, &[2, 49, 1]),
+    (53, &[51, 3, 0, 1]),
+    (59, &[57, 1]),
+    (59, &[2, 58, 1]),
+    (59, &[57, 5, 0, 1]),
+    (61, &[59, 1]),
+    (61, &[2, 60, 1]),
+    (61, &[59, 7, 0, 1]),
+    (67, &[65, 1]),
+    (67, &[2, 63, 1]),
+    (67, &[65, 6, 0, 1]),
+    (71, &[64, 1]),
+    (71, &[7, 69, 1]),
+    (71, &[64, 4, 0, 1]),
+    (73, &[68, 1]),
+    (73, &[5, 70, 1]),
+    (73, &[68, 2, 0, 1]),
+    (79, &[76, 1]),
+    (79, &[3, 78, 1]),
+    (79, &[76, 9, 0, 1]),
+    (83, &[81, 1]),
+    (83, &[2, 82, 1]),
+    (83, &[81, 3, 0, 1]),
+    (89, &[86, 1]),
+    (89, &[3, 82, 1]),
+    (89, &[86, 3, 0, 1]),
+    (97, &[92, 1]),
+    (97, &[5, 96, 1]),
+    (97, &[92, 9, 0, 1]),
+    (101, &[99, 1]),
+    (101, &[2, 97, 1]),
+    (103, &[98, 1]),
+    (103, &[5, 102, 1]),
+    (107, &[105, 1]),
+    (107, &[2, 103, 1]),
+    (109, &[103, 1]),
+    (109, &[6, 108, 1]),
+];
+
+pub(crate) fn conway_polynomial(p: &Integer, n: u32) -> Option<&'static [u32]> {
+    CONWAY_POLYNOMIALS
+        .iter()
+        .find(|(q, coeffs)| *p == *q && coeffs.len() == n as usize + 1)
+        .map(|(_, coeffs)| *coeffs)
+}
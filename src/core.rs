@@ -1,3 +1,4 @@
+use crate::dense_polynomial::SHORT_MUL_THRESHOLD;
 use std::{borrow::Cow, fmt, mem};
 
 pub trait ReqV: Default + Clone + Eq + std::fmt::Debug {}
@@ -93,6 +94,39 @@ impl<'a, S: Structure> El<'a, S> {
     }
 }
 
+impl<'a, S: SMul> El<'a, S> {
+    /// Square-and-multiply exponentiation by a nonnegative exponent
+    pub fn pow(&self, exp: &rug::Integer) -> El<'a, S> {
+        assert!(*exp >= 0, "negative exponent");
+        let s = self.s;
+        let mut acc = s.one().into_owned();
+        for i in (0..exp.significant_bits()).rev() {
+            acc = s.mul(Cow::Borrowed(&acc), Cow::Borrowed(&acc)).into_owned();
+            if exp.get_bit(i) {
+                acc = s.mul(Cow::Owned(acc), Cow::Borrowed(&*self.v)).into_owned();
+            }
+        }
+        El {
+            v: Cow::Owned(acc),
+            s,
+        }
+    }
+}
+
+impl<'a, S: SGcd> El<'a, S> {
+    pub fn gcd(&self, other: &El<'_, S>) -> El<'a, S> {
+        assert_eq!(self.s, other.s);
+        let s = self.s;
+        El {
+            v: Cow::Owned(
+                s.gcd(Cow::Borrowed(&*self.v), Cow::Borrowed(&*other.v))
+                    .into_owned(),
+            ),
+            s,
+        }
+    }
+}
+
 impl<S: Structure> fmt::Display for El<'_, S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         self.s.fmt_v(&self.v, f)?;
@@ -117,6 +151,67 @@ pub trait SMul: Structure {
 }
 pub trait SRem: Structure {
     fn rem<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V>;
+    /// The first `len` of the `mul_coefficients(lhs, rhs)` reduced modulo `m`, for nonempty
+    /// operands. `Mod` multiplies through this. The default reduces after every product, so
+    /// values never exceed those of `Mod::mul`; structures with unbounded values override it to
+    /// reduce each coefficient once, after a faster product.
+    fn mul_coefficients_rem(
+        &self,
+        lhs: &[Self::V],
+        rhs: &[Self::V],
+        len: usize,
+        m: &Self::V,
+    ) -> Vec<Self::V>
+    where
+        Self: SFusedMulAdd,
+    {
+        let mut ret = vec![self.zero().into_owned(); len];
+        for (i, a) in lhs.iter().enumerate().take(len) {
+            for (j, b) in rhs.iter().take(len - i).enumerate() {
+                let slot = &mut ret[i + j];
+                self.fused_mul_add_ref(slot, a, b);
+                *slot = self
+                    .rem(Cow::Owned(mem::take(slot)), Cow::Borrowed(m))
+                    .into_owned();
+            }
+        }
+        ret
+    }
+}
+/// Exact division: `rhs` must divide `lhs`, as every nonzero element does in a field
+pub trait SDiv: Structure + SMul {
+    fn div<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V>;
+}
+/// Euclidean division, with the remainder agreeing with `SRem`
+pub trait SDivRem: Structure + SRem + SSub + SMul {
+    fn div_rem<'a>(
+        &'a self,
+        lhs: Cow<'a, Self::V>,
+        rhs: Cow<'a, Self::V>,
+    ) -> (Cow<'a, Self::V>, Cow<'a, Self::V>);
+}
+/// Marker for structures in which every nonzero element is invertible
+pub trait SField: SDiv + SSub {}
+/// Greatest common divisors in a domain with unique factorization
+pub trait SGcd: SDiv + SSub {
+    /// The greatest common divisor with unit part one; zero only for two zeros
+    fn gcd<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V>;
+    /// The unit `u` for which `v / u` is the normal representative of the associates of `v`,
+    /// such as the sign of an integer or `v` itself in a field; one for zero
+    fn unit_part(&self, v: &Self::V) -> Self::V;
+    /// The characteristic of the quotient by `m`: the least `n > 0` for which `n 1` is a
+    /// multiple of `m`, or zero if there is none
+    fn quotient_characteristic(&self, m: &Self::V) -> rug::Integer;
+    fn characteristic(&self) -> rug::Integer {
+        self.quotient_characteristic(&self.zero())
+    }
+    /// A `p`-th root for the characteristic `p`, if positive and `v` has one
+    fn pth_root(&self, v: &Self::V) -> Option<Self::V>;
+}
+/// Exact division by small positive integers, as in Toom-Cook interpolation
+pub trait SDivSmall: SSub {
+    /// `v / k`, when multiplication by `k` is injective and `v` is a multiple of `k`
+    fn div_small_exact(&self, v: &Self::V, k: u32) -> Option<Self::V>;
 }
 pub trait SFusedMulAdd: Structure + SAdd + SMul {
     fn fused_mul_add_ref(&self, acc: &mut Self::V, lhs: &Self::V, rhs: &Self::V) {
@@ -124,6 +219,41 @@ pub trait SFusedMulAdd: Structure + SAdd + SMul {
         slot = self.add(slot, self.mul(Cow::Borrowed(lhs), Cow::Borrowed(rhs)));
         *acc = slot.into_owned();
     }
+    /// The coefficients of the product of the polynomials with coefficients `lhs` and `rhs`,
+    /// lowest degree first: `lhs.len() + rhs.len() - 1` of them, untrimmed, for nonempty
+    /// operands. `DensePolynomial` multiplies through this, so structures with subquadratic
+    /// algorithms, such as through `mul_karatsuba`, override it.
+    fn mul_coefficients(&self, lhs: &[Self::V], rhs: &[Self::V]) -> Vec<Self::V> {
+        if lhs.is_empty() || rhs.is_empty() {
+            return Vec::new();
+        }
+        let mut ret = vec![self.zero().into_owned(); lhs.len() + rhs.len() - 1];
+        for (i, a) in lhs.iter().enumerate() {
+            for (j, b) in rhs.iter().enumerate() {
+                self.fused_mul_add_ref(&mut ret[i + j], a, b);
+            }
+        }
+        ret
+    }
+    /// The first `len` of the `mul_coefficients(lhs, rhs)`, for nonempty operands and `len` at
+    /// most their number: a short product of only the terms below `x^len` for operands shorter
+    /// than `SHORT_MUL_THRESHOLD`, and the full product cut off otherwise
+    fn mul_coefficients_low(&self, lhs: &[Self::V], rhs: &[Self::V], len: usize) -> Vec<Self::V> {
+        if usize::min(lhs.len(), rhs.len()) >= SHORT_MUL_THRESHOLD
+            || len >= lhs.len() + rhs.len() - 1
+        {
+            let mut ret = self.mul_coefficients(lhs, rhs);
+            ret.truncate(len);
+            return ret;
+        }
+        let mut ret = vec![self.zero().into_owned(); len];
+        for (i, a) in lhs.iter().enumerate().take(len) {
+            for (j, b) in rhs.iter().take(len - i).enumerate() {
+                self.fused_mul_add_ref(&mut ret[i + j], a, b);
+            }
+        }
+        ret
+    }
 }
 
 macro_rules! impl_op {
@@ -167,6 +297,13 @@ impl_op!(
     ::std::ops::MulAssign,
     mul_assign
 );
+impl_op!(
+    SDiv,
+    ::std::ops::Div,
+    div,
+    ::std::ops::DivAssign,
+    div_assign
+);
 impl_op!(
     SRem,
     ::std::ops::Rem,
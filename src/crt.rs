@@ -0,0 +1,281 @@
+use crate::{
+    El, Int, Mod, SAdd, SDivSmall, SFusedMulAdd, SInteger, SMul, SSub, Structure, SuperStructure,
+    mul_toom3,
+};
+use rug::{Complete, Integer};
+use std::{borrow::Cow, fmt, mem};
+
+/// Chinese remainder theorem: combines residues from several `Mod` structures into a single
+/// residue modulo the lcm of their moduli. The moduli need not be coprime; returns `None` if the
+/// residues are inconsistent or there are none.
+pub fn crt<'a, S: SInteger + 'a>(
+    residues: impl IntoIterator<Item = El<'a, Mod<S>>>,
+) -> Option<(Mod<Int>, Integer)> {
+    let mut residues = residues.into_iter().peekable();
+    residues.peek()?;
+    let (x, m) = crt_integers(residues.map(|el| {
+        let inner = el.s.inner();
+        (inner.to_integer(&el.v), inner.to_integer(&el.s.modulus().v))
+    }))?;
+    Some((Mod::new(Int.el(m)), x))
+}
+
+/// Combines `(residue, modulus)` pairs into `(x, lcm)` with `0 <= x < lcm`
+pub(crate) fn crt_integers(
+    residues: impl IntoIterator<Item = (Integer, Integer)>,
+) -> Option<(Integer, Integer)> {
+    let mut x = Integer::ZERO;
+    let mut m = Integer::from(1);
+    for (a, n) in residues {
+        assert!(n > 0, "crt moduli must be positive");
+        let a = a.div_rem_euc(n.clone()).1;
+        let (g, s, _) = m.clone().extended_gcd(n.clone(), Integer::new());
+        // x + m * t = a (mod n)  <=>  (m / g) * t = (a - x) / g (mod n / g)
+        let diff = (&a - &x).complete();
+        if !diff.is_divisible(&g) {
+            return None;
+        }
+        let n_g = (&n / &g).complete();
+        let t = (diff.div_exact(&g) * s).div_rem_euc(n_g.clone()).1;
+        x += &m * t;
+        m *= n_g;
+    }
+    Some((x, m))
+}
+
+/// Residue number system: an integer represented by its residues modulo pairwise coprime moduli,
+/// with arithmetic performed componentwise.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rns {
+    moduli: Vec<Mod<Int>>,
+    product: Integer,
+    /// `basis[i]` is 1 modulo `moduli[i]` and 0 modulo every other modulus
+    basis: Vec<Integer>,
+}
+impl Structure for Rns {
+    type V = Vec<Integer>;
+    fn fmt_v(&self, v: &Self::V, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "(")?;
+        for (i, r) in v.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{r}")?;
+        }
+        write!(f, ")")
+    }
+}
+impl std::fmt::Display for Rns {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if f.alternate() {
+            write!(f, ", ")?;
+        }
+        write!(f, "rns")?;
+        for (i, m) in self.moduli.iter().enumerate() {
+            write!(f, "{}{}", if i == 0 { " " } else { "·" }, m.modulus().v)?;
+        }
+        Ok(())
+    }
+}
+impl SuperStructure for Rns {
+    type Inner = Int;
+    fn inner(&self) -> &Self::Inner {
+        &Int
+    }
+    fn lifted_from<'a>(&'a self, inner: El<'a, Self::Inner>) -> El<'a, Self> {
+        El {
+            v: Cow::Owned(
+                self.moduli
+                    .iter()
+                    .map(|m| inner.v.div_rem_euc_ref(&m.modulus().v).complete().1)
+                    .collect(),
+            ),
+            s: self,
+        }
+    }
+}
+
+impl Rns {
+    /// Panics unless the moduli are positive and pairwise coprime
+    pub fn new<'a>(moduli: impl IntoIterator<Item = El<'a, Int>>) -> Self {
+        let moduli: Vec<Integer> = moduli.into_iter().map(|m| m.v.into_owned()).collect();
+        assert!(!moduli.is_empty(), "rns needs at least one modulus");
+        for (i, m) in moduli.iter().enumerate() {
+            assert!(*m > 1, "rns moduli must be greater than one");
+            for n in &moduli[..i] {
+                assert_eq!(m.gcd_ref(n).complete(), 1, "rns moduli must be coprime");
+            }
+        }
+        let product: Integer = moduli.iter().product();
+        let basis = moduli
+            .iter()
+            .map(|m| {
+                let cofactor = (&product / m).complete();
+                let inverse = cofactor.invert_ref(m).unwrap().complete();
+                cofactor * inverse
+            })
+            .collect();
+        Self {
+            moduli: moduli.into_iter().map(|m| Mod::new(Int.el(m))).collect(),
+            product,
+            basis,
+        }
+    }
+    pub fn moduli(&self) -> &[Mod<Int>] {
+        &self.moduli
+    }
+    /// The product of the moduli, which is the size of the represented range
+    pub fn product(&self) -> El<'static, Int> {
+        El {
+            v: Cow::Owned(self.product.clone()),
+            s: &Int,
+        }
+    }
+    /// Reconstructs the represented integer in `[0, product)`
+    pub fn to_int(&self, el: &El<'_, Self>) -> El<'static, Int> {
+        assert_eq!(self, el.s);
+        let x: Integer = Iterator::zip(el.v.iter(), self.basis.iter())
+            .map(|(r, e)| (r * e).complete())
+            .sum();
+        El {
+            v: Cow::Owned(x.div_rem_euc(self.product.clone()).1),
+            s: &Int,
+        }
+    }
+    /// Reconstructs the represented integer in `(-product / 2, product / 2]`
+    pub fn to_int_signed(&self, el: &El<'_, Self>) -> El<'static, Int> {
+        let mut x = self.to_int(el);
+        if x.v.as_ref().clone() * 2 > self.product {
+            *x.v.to_mut() -= &self.product;
+        }
+        x
+    }
+
+    fn zip_with(
+        &self,
+        mut lhs: Vec<Integer>,
+        rhs: &[Integer],
+        op: impl for<'b> Fn(&'b Mod<Int>, Cow<'b, Integer>, Cow<'b, Integer>) -> Cow<'b, Integer>,
+    ) -> Vec<Integer> {
+        for ((l, r), m) in lhs.iter_mut().zip(rhs).zip(&self.moduli) {
+            *l = op(m, Cow::Owned(mem::take(l)), Cow::Borrowed(r)).into_owned();
+        }
+        lhs
+    }
+}
+impl SAdd for Rns {
+    fn zero(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(vec![Integer::ZERO; self.moduli.len()])
+    }
+    fn add<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        Cow::Owned(self.zip_with(lhs.into_owned(), &rhs, <Mod<Int> as SAdd>::add))
+    }
+}
+impl SSub for Rns {
+    fn negate<'a>(&'a self, v: &mut Cow<'a, Self::V>) {
+        for (r, m) in Iterator::zip(v.to_mut().iter_mut(), &self.moduli) {
+            let mut slot = Cow::Owned(mem::take(r));
+            m.negate(&mut slot);
+            *r = slot.into_owned();
+        }
+    }
+    fn sub<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        Cow::Owned(self.zip_with(lhs.into_owned(), &rhs, <Mod<Int> as SSub>::sub))
+    }
+}
+impl SMul for Rns {
+    fn one(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(vec![Integer::from(1); self.moduli.len()])
+    }
+    fn mul<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        Cow::Owned(self.zip_with(lhs.into_owned(), &rhs, <Mod<Int> as SMul>::mul))
+    }
+}
+impl SFusedMulAdd for Rns {
+    fn fused_mul_add_ref(&self, acc: &mut Self::V, lhs: &Self::V, rhs: &Self::V) {
+        for (((a, l), r), m) in acc.iter_mut().zip(lhs).zip(rhs).zip(&self.moduli) {
+            m.fused_mul_add_ref(a, l, r);
+        }
+    }
+    fn mul_coefficients(&self, lhs: &[Self::V], rhs: &[Self::V]) -> Vec<Self::V> {
+        mul_toom3(self, lhs, rhs)
+    }
+}
+impl SDivSmall for Rns {
+    fn div_small_exact(&self, v: &Self::V, k: u32) -> Option<Self::V> {
+        Iterator::zip(v.iter(), &self.moduli)
+            .map(|(r, m)| m.div_small_exact(r, k))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DensePolynomial, I};
+
+    /// `crt` of `x = a (mod n)` for the pairs `(a, n)`, as `(x, lcm)`
+    fn combined(pairs: &[(i64, i64)]) -> Option<(Integer, Integer)> {
+        let moduli: Vec<Mod<Int>> = pairs.iter().map(|&(_, n)| Mod::new(Int.el(n))).collect();
+        let residues =
+            Iterator::zip(pairs.iter(), &moduli).map(|(&(a, _), m)| m.el(Integer::from(a)));
+        crt(residues).map(|(m, x)| (x, m.modulus().v.into_owned()))
+    }
+
+    #[test]
+    fn combined_residues() {
+        let pair = |x: i64, m: i64| Some((Integer::from(x), Integer::from(m)));
+        assert_eq!(combined(&[(2, 3), (3, 5), (2, 7)]), pair(23, 105));
+        // Consistent residues for moduli sharing factors, modulo their lcm
+        assert_eq!(combined(&[(2, 6), (8, 10)]), pair(8, 30));
+        assert_eq!(combined(&[(5, 12), (11, 18), (1, 4)]), pair(29, 36));
+        assert_eq!(combined(&[(-1, 4)]), pair(3, 4));
+        // Inconsistent ones, and none at all
+        assert_eq!(combined(&[(1, 4), (2, 6)]), None);
+        assert_eq!(combined(&[(0, 9), (1, 12)]), None);
+        assert_eq!(combined(&[]), None);
+        // Residues of fixed-width integers
+        let moduli = [Mod::new(I::<64>.el(9)), Mod::new(I::<64>.el(8))];
+        let (m, x) = crt([moduli[0].el(4), moduli[1].el(7)]).unwrap();
+        assert_eq!(
+            (x, m.modulus().v.into_owned()),
+            (Integer::from(31), Integer::from(72))
+        );
+    }
+
+    #[test]
+    fn rns_round_trips() {
+        let rns = Rns::new([3, 5, 7, 11, 13].map(|m| Int.el(m)));
+        assert_eq!(*rns.product().v, 15015);
+        for x in (-7507..=7507).step_by(97) {
+            let el = Int.el(x).lift(&rns);
+            assert_eq!(*rns.to_int_signed(&el).v, x);
+            assert_eq!(
+                *rns.to_int(&el).v,
+                Integer::from(x).div_rem_euc(Integer::from(15015)).1
+            );
+        }
+        let (a, b, c) = (1234, -567, 89);
+        let lifted = |x: i64| Int.el(x).lift(&rns);
+        let value = lifted(a) * lifted(b) - lifted(c);
+        let expected = Integer::from(a * b - c).div_rem_euc(Integer::from(15015)).1;
+        assert_eq!(*rns.to_int(&value).v, expected);
+        // Products of polynomials over the residues, Toom-3 included
+        let rns = Rns::new([1_000_003, 1_000_033, 998_244_353].map(|m| Int.el(m)));
+        let lifted = |x: i64| Int.el(x).lift(&rns);
+        let rx = DensePolynomial::new_symb("x", &rns);
+        let zx = DensePolynomial::new_symb("x", &Int);
+        let coeffs: Vec<i64> = (0..800).map(|i| (i * i * 31 + 7) % 19 - 9).collect();
+        let p = zx.el(coeffs.iter().map(|&c| Integer::from(c)).collect::<Vec<_>>());
+        let q = rx.el(coeffs
+            .iter()
+            .map(|&c| lifted(c).v.into_owned())
+            .collect::<Vec<_>>());
+        let expected = p.copy() * p.copy();
+        let product = q.copy() * q.copy();
+        assert_eq!(product.v.len(), expected.v.len());
+        for (r, c) in Iterator::zip(product.v.iter(), expected.v.iter()) {
+            assert_eq!(*rns.to_int_signed(&rns.el(r.clone())).v, *c);
+        }
+    }
+}
@@ -1,6 +1,29 @@
-use crate::{El, SAdd, SFusedMulAdd, SMul, SSub, Structure, SuperStructure};
+use crate::{
+    El, SAdd, SDiv, SDivRem, SDivSmall, SField, SFusedMulAdd, SIntegerResidues, SMul, SRem, SSub,
+    Structure, SuperStructure, kronecker, ntt,
+};
+use rug::Integer;
 use std::{borrow::Cow, fmt, mem};
 
+/// Multiplication algorithms for `DensePolynomial`. `SMul::mul` picks one automatically from the
+/// operand sizes and coefficient structure; `DensePolynomial::mul_with` forces one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MulAlgorithm {
+    Schoolbook,
+    /// Needs subtraction of coefficients
+    Karatsuba,
+    /// Needs subtraction and exact division of coefficients by 2 and 3
+    Toom3,
+    /// Number-theoretic transform, for coefficients that are integers or integers modulo `m`
+    Ntt,
+    /// Kronecker substitution: packs the coefficients into one big integer multiplied by GMP,
+    /// for the same coefficients as `Ntt`
+    Kronecker,
+}
+
+/// Polynomials in one variable. Values are coefficient vectors, lowest degree first, without
+/// trailing zero coefficients, so the zero polynomial is the empty vector and equal polynomials
+/// have equal values.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DensePolynomial<S: Structure + SFusedMulAdd> {
     symbol: String,
@@ -9,6 +32,9 @@ pub struct DensePolynomial<S: Structure + SFusedMulAdd> {
 impl<S: SFusedMulAdd> Structure for DensePolynomial<S> {
     type V = Vec<S::V>;
     fn fmt_v(&self, coeffs: &Self::V, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if coeffs.is_empty() {
+            return self.inner.fmt_v(&self.inner.zero(), f);
+        }
         for (i, (deg, coeff)) in coeffs
             .iter()
             .enumerate()
@@ -30,6 +56,14 @@ impl<S: SFusedMulAdd> Structure for DensePolynomial<S> {
         }
         Ok(())
     }
+    fn el<'a>(&'a self, v: impl Into<Self::V>) -> El<'a, Self> {
+        let mut v = v.into();
+        self.trim(&mut v);
+        El {
+            v: Cow::Owned(v),
+            s: self,
+        }
+    }
 }
 impl<S: SFusedMulAdd> std::fmt::Display for DensePolynomial<S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
@@ -47,10 +81,7 @@ impl<S: SFusedMulAdd> SuperStructure for DensePolynomial<S> {
     fn lifted_from(&self, inner: El<'_, Self::Inner>) -> El<'_, Self> {
         let El { v, s } = inner;
         assert_eq!(&self.inner, s);
-        El {
-            v: Cow::Owned(vec![v.into_owned()]),
-            s: &self,
-        }
+        self.el(vec![v.into_owned()])
     }
 }
 /*
@@ -81,14 +112,117 @@ impl<S: SFusedMulAdd> DensePolynomial<S> {
             inner: inner.clone(),
         }
     }
+    pub(crate) fn symbol(&self) -> &str {
+        &self.symbol
+    }
+    /// Drops trailing zero coefficients
+    fn trim(&self, coeffs: &mut Vec<S::V>) {
+        while coeffs.last().is_some_and(|c| *c == *self.inner.zero()) {
+            coeffs.pop();
+        }
+    }
+    /// `None` for the zero polynomial
+    pub fn degree(&self, a: &El<'_, Self>) -> Option<usize> {
+        a.v.len().checked_sub(1)
+    }
+    /// Zero for the zero polynomial
+    pub fn leading_coefficient<'a>(&'a self, a: &El<'_, Self>) -> El<'a, S> {
+        match a.v.last() {
+            Some(c) => self.inner.el(c.clone()),
+            None => El {
+                v: self.inner.zero(),
+                s: &self.inner,
+            },
+        }
+    }
+    pub fn is_zero(&self, a: &El<'_, Self>) -> bool {
+        a.v.is_empty()
+    }
+    fn mul_schoolbook<'a>(
+        &'a self,
+        lhs: Cow<'_, <Self as Structure>::V>,
+        rhs: Cow<'_, <Self as Structure>::V>,
+    ) -> Cow<'a, <Self as Structure>::V> {
+        let [lhs_cap, rhs_cap] = [&lhs, &rhs].map(|side| match side {
+            Cow::Borrowed(_) => 0,
+            Cow::Owned(v) => v.capacity(),
+        });
+        let (mut target, src) = if lhs_cap >= rhs_cap {
+            (lhs.into_owned(), &rhs)
+        } else {
+            (rhs.into_owned(), &lhs)
+        };
+        let n = target.len();
+        let m = src.len();
+        if n == 0 || m == 0 {
+            return self.zero();
+        }
+        target.resize(n + m - 1, Default::default());
+        for k in (0..(n + m - 1)).rev() {
+            {
+                let mut slot = Cow::Owned(mem::take(&mut target[k]));
+                slot = self.inner.mul(slot, Cow::Borrowed(&src[0]));
+                target[k] = slot.into_owned();
+            }
+            for j in (usize::saturating_sub(k, n) + 1)..usize::min(m, k + 1) {
+                let i = k - j;
+                assert!(i < k);
+                let (pre, post) = target.split_at_mut(k);
+                self.inner.fused_mul_add_ref(&mut post[0], &pre[i], &src[j]);
+            }
+        }
+        Cow::Owned(target)
+    }
     pub fn symb(&self) -> El<'_, Self> {
-        El {
-            v: Cow::Owned(vec![
-                self.inner.zero().into_owned(),
-                self.inner.one().into_owned(),
-            ]),
-            s: &self,
+        self.el(vec![
+            self.inner.zero().into_owned(),
+            self.inner.one().into_owned(),
+        ])
+    }
+    /// The coefficients of `lhs rhs` below `x^len`, trimmed, through
+    /// `SFusedMulAdd::mul_coefficients_low`
+    pub(crate) fn mul_low(&self, lhs: &[S::V], rhs: &[S::V], len: usize) -> Vec<S::V> {
+        let lhs = &lhs[..usize::min(lhs.len(), len)];
+        let rhs = &rhs[..usize::min(rhs.len(), len)];
+        if lhs.is_empty() || rhs.is_empty() {
+            return Vec::new();
         }
+        let len = usize::min(len, lhs.len() + rhs.len() - 1);
+        let mut ret = self.inner.mul_coefficients_low(lhs, rhs, len);
+        self.trim(&mut ret);
+        ret
+    }
+}
+impl<S: SIntegerResidues + SDivSmall> DensePolynomial<S> {
+    /// The product computed with the given algorithm, for benchmarking. Panics if the algorithm
+    /// does not apply to the coefficient structure.
+    pub fn mul_with<'a>(
+        &'a self,
+        lhs: &El<'_, Self>,
+        rhs: &El<'_, Self>,
+        algorithm: MulAlgorithm,
+    ) -> El<'a, Self> {
+        assert_eq!(self, lhs.s);
+        assert_eq!(self, rhs.s);
+        let (a, b) = (&*lhs.v, &*rhs.v);
+        let v = match algorithm {
+            MulAlgorithm::Schoolbook => self
+                .mul_schoolbook(Cow::Borrowed(a), Cow::Borrowed(b))
+                .into_owned(),
+            MulAlgorithm::Karatsuba => mul_karatsuba(&self.inner, a, b),
+            MulAlgorithm::Toom3 => {
+                let divide = FastMul::toom3_division(&self.inner)
+                    .expect("coefficients lack exact division by 2 and 3");
+                FastMul {
+                    s: &self.inner,
+                    toom3: Some((KARATSUBA_THRESHOLD, divide)),
+                }
+                .mul(a, b)
+            }
+            MulAlgorithm::Ntt => mul_residues(&self.inner, a, b, ntt::mul_residues),
+            MulAlgorithm::Kronecker => mul_residues(&self.inner, a, b, kronecker::mul_residues),
+        };
+        self.el(v)
     }
 }
 impl<S: SFusedMulAdd> SAdd for DensePolynomial<S> {
@@ -110,6 +244,10 @@ impl<S: SFusedMulAdd> SAdd for DensePolynomial<S> {
             slot2 = self.inner.add(slot2, Cow::Borrowed(item));
             *slot = slot2.into_owned();
         }
+        if src.len() > target.len() {
+            target.extend_from_slice(&src[target.len()..]);
+        }
+        self.trim(&mut target);
         Cow::Owned(target)
     }
 }
@@ -133,6 +271,12 @@ impl<S: SFusedMulAdd + SSub> SSub for DensePolynomial<S> {
                 lhs_ii = self.inner.sub(lhs_ii, Cow::Borrowed(rhs_i));
                 *lhs_i = lhs_ii.into_owned();
             }
+            for rhs_i in rhs.iter().skip(lhs.len()) {
+                let mut item = Cow::Borrowed(rhs_i);
+                self.inner.negate(&mut item);
+                lhs.push(item.into_owned());
+            }
+            self.trim(&mut lhs);
             Cow::Owned(lhs)
         } else {
             let mut rhs = rhs.into_owned();
@@ -146,53 +290,653 @@ impl<S: SFusedMulAdd + SSub> SSub for DensePolynomial<S> {
                 self.inner.negate(&mut rhs_ii);
                 *rhs_i = rhs_ii.into_owned();
             }
+            rhs.extend(lhs.iter().skip(rhs.len()).cloned());
+            self.trim(&mut rhs);
             Cow::Owned(rhs)
         }
     }
 }
 impl<S: SFusedMulAdd> SMul for DensePolynomial<S> {
     fn one(&self) -> Cow<'_, Self::V> {
-        Cow::Owned(vec![self.inner.one().into_owned()])
+        self.el(vec![self.inner.one().into_owned()]).v
     }
     fn mul(&self, lhs: Cow<'_, Self::V>, rhs: Cow<'_, Self::V>) -> Cow<'_, Self::V> {
-        let [lhs_cap, rhs_cap] = [&lhs, &rhs].map(|side| match side {
-            Cow::Borrowed(_) => 0,
-            Cow::Owned(v) => v.capacity(),
-        });
-        let (mut target, src) = if lhs_cap >= rhs_cap {
-            (lhs.into_owned(), &rhs)
-        } else {
-            (rhs.into_owned(), &lhs)
-        };
-        let n = target.len();
-        let m = src.len();
-        target.resize(n + m - 1, Default::default());
-        for k in (0..(n + m - 1)).rev() {
-            {
-                let mut slot = Cow::Owned(mem::take(&mut target[k]));
-                slot = self.inner.mul(slot, Cow::Borrowed(&src[0]));
-                target[k] = slot.into_owned();
-            }
-            for j in (usize::saturating_sub(k, n) + 1)..usize::min(m, k + 1) {
-                let i = k - j;
-                assert!(i < k);
-                let (pre, post) = target.split_at_mut(k);
-                self.inner.fused_mul_add_ref(&mut post[0], &pre[i], &src[j]);
-            }
-        }
-        Cow::Owned(target)
+        // Leading coefficients may multiply to zero
+        let mut ret = self.inner.mul_coefficients(&lhs, &rhs);
+        self.trim(&mut ret);
+        Cow::Owned(ret)
     }
 }
 impl<S: SFusedMulAdd> SFusedMulAdd for DensePolynomial<S> {
     fn fused_mul_add_ref(&self, acc: &mut Self::V, lhs: &Self::V, rhs: &Self::V) {
         let n = lhs.len();
         let m = rhs.len();
+        if n == 0 || m == 0 {
+            return;
+        }
+        let prod = self.inner.mul_coefficients(lhs, rhs);
         acc.resize(usize::max(acc.len(), n + m - 1), Default::default());
-        for i in 0..n {
-            for j in 0..m {
-                self.inner
-                    .fused_mul_add_ref(&mut acc[i + j], &lhs[i], &rhs[j]);
+        for (slot, c) in Iterator::zip(acc.iter_mut(), prod) {
+            let mut slot2 = Cow::Owned(mem::take(slot));
+            slot2 = self.inner.add(slot2, Cow::Owned(c));
+            *slot = slot2.into_owned();
+        }
+        self.trim(acc);
+    }
+    /// Kronecker substitution `y = x^k` into one product over the coefficient structure, with
+    /// `k` the length of the longest product of two coefficients so that none overlap, once that
+    /// product is long enough for subquadratic coefficient multiplication to pay off
+    fn mul_coefficients(&self, lhs: &[Self::V], rhs: &[Self::V]) -> Vec<Self::V> {
+        if lhs.is_empty() || rhs.is_empty() {
+            return Vec::new();
+        }
+        let width = |v: &[Self::V]| v.iter().map(Vec::len).max().unwrap();
+        let k = width(lhs) + width(rhs);
+        if k < 2 || usize::min(lhs.len(), rhs.len()) * (k - 1) < KARATSUBA_THRESHOLD {
+            let mut ret = vec![Vec::new(); lhs.len() + rhs.len() - 1];
+            for (i, a) in lhs.iter().enumerate() {
+                for (j, b) in rhs.iter().enumerate() {
+                    self.fused_mul_add_ref(&mut ret[i + j], a, b);
+                }
+            }
+            return ret;
+        }
+        let k = k - 1;
+        let pack = |v: &[Self::V]| -> Vec<S::V> {
+            let mut ret = vec![self.inner.zero().into_owned(); v.len() * k];
+            for (i, c) in v.iter().enumerate() {
+                ret[i * k..i * k + c.len()].clone_from_slice(c);
+            }
+            ret
+        };
+        let prod = self.inner.mul_coefficients(&pack(lhs), &pack(rhs));
+        prod.chunks(k)
+            .take(lhs.len() + rhs.len() - 1)
+            .map(|c| self.el(c.to_vec()).v.into_owned())
+            .collect()
+    }
+}
+impl<S: SFusedMulAdd + SField> SDivRem for DensePolynomial<S> {
+    fn div_rem<'a>(
+        &'a self,
+        lhs: Cow<'a, Self::V>,
+        rhs: Cow<'a, Self::V>,
+    ) -> (Cow<'a, Self::V>, Cow<'a, Self::V>) {
+        let mut divisor = rhs.into_owned();
+        self.trim(&mut divisor);
+        let Some(lc) = divisor.last() else {
+            panic!("polynomial division by zero");
+        };
+        let lc_inv = self
+            .inner
+            .div(self.inner.one(), Cow::Borrowed(lc))
+            .into_owned();
+        let (quot, rem) = self.div_rem_unit(lhs.into_owned(), &divisor, lc_inv);
+        (Cow::Owned(quot), Cow::Owned(rem))
+    }
+}
+impl<S: SFusedMulAdd + SSub> DensePolynomial<S> {
+    /// `(q, r)` with `a = q b + r` and `deg r < deg b`, for a monic `b`, which makes the division
+    /// work over any ring
+    pub fn div_rem_monic<'a>(
+        &'a self,
+        a: &El<'_, Self>,
+        b: &El<'_, Self>,
+    ) -> (El<'a, Self>, El<'a, Self>) {
+        assert_eq!(self, a.s);
+        assert_eq!(self, b.s);
+        assert!(
+            b.v.last() == Some(&*self.inner.one()),
+            "divisor must be monic"
+        );
+        let one = self.inner.one().into_owned();
+        let (quot, rem) = self.div_rem_unit((*a.v).clone(), &b.v, one);
+        (self.el(quot), self.el(rem))
+    }
+    /// Long division by the nonzero trimmed `divisor`, whose leading coefficient has inverse
+    /// `lc_inv`
+    fn div_rem_unit(
+        &self,
+        mut rem: Vec<S::V>,
+        divisor: &[S::V],
+        lc_inv: S::V,
+    ) -> (Vec<S::V>, Vec<S::V>) {
+        self.trim(&mut rem);
+        if rem.len() < divisor.len() {
+            return (Vec::new(), rem);
+        }
+        if usize::min(rem.len() - divisor.len() + 1, divisor.len()) >= NEWTON_DIV_THRESHOLD {
+            return self.div_rem_newton(&rem, divisor, lc_inv);
+        }
+        let mut quot = vec![S::V::default(); rem.len() - divisor.len() + 1];
+        for i in (0..quot.len()).rev() {
+            let top = mem::take(&mut rem[i + divisor.len() - 1]);
+            let c = self
+                .inner
+                .mul(Cow::Owned(top), Cow::Borrowed(&lc_inv))
+                .into_owned();
+            for (j, d) in divisor.iter().enumerate().take(divisor.len() - 1) {
+                let slot = Cow::Owned(mem::take(&mut rem[i + j]));
+                let prod = self.inner.mul(Cow::Borrowed(&c), Cow::Borrowed(d));
+                rem[i + j] = self.inner.sub(slot, prod).into_owned();
             }
+            quot[i] = c;
+        }
+        rem.truncate(divisor.len() - 1);
+        self.trim(&mut rem);
+        self.trim(&mut quot);
+        (quot, rem)
+    }
+    /// The power series inverse of `f` to `prec` terms, by Newton iteration `g <- g (2 - f g)`.
+    /// The constant term of `f` must be invertible, with inverse `f0_inv`.
+    pub(crate) fn inverse_series(&self, f: &[S::V], prec: usize, f0_inv: S::V) -> Vec<S::V> {
+        let two = self.el(vec![
+            self.inner
+                .add(self.inner.one(), self.inner.one())
+                .into_owned(),
+        ]);
+        let mut g = self.el(vec![f0_inv]);
+        let mut k = 1;
+        while k < prec {
+            k = usize::min(2 * k, prec);
+            let fg = self.el(self.mul_low(f, &g.v, k));
+            let correction = two.copy() - fg;
+            g = self.el(self.mul_low(&g.v, &correction.v, k));
+        }
+        g.v.into_owned()
+    }
+    /// Division through the reversed polynomials: `rev(q) = rev(a) / rev(b) mod x^(deg q + 1)`,
+    /// with the inverse by Newton iteration, so the cost is a few multiplications
+    fn div_rem_newton(&self, a: &[S::V], b: &[S::V], lc_inv: S::V) -> (Vec<S::V>, Vec<S::V>) {
+        let quot_len = a.len() - b.len() + 1;
+        let reversed = |v: &[S::V]| -> Vec<S::V> { v.iter().rev().cloned().collect() };
+        let b_rev_inv = self.inverse_series(&reversed(b), quot_len, lc_inv);
+        let a_rev = reversed(&a[a.len() - quot_len..]);
+        let mut quot = self.mul_low(&a_rev, &b_rev_inv, quot_len);
+        quot.resize(quot_len, S::V::default());
+        quot.reverse();
+        self.trim(&mut quot);
+        // The remainder has degree below that of b, so only the low terms of q b are needed
+        let prod = self.mul_low(&quot, b, b.len() - 1);
+        let rem = self.sub(Cow::Owned(a[..b.len() - 1].to_vec()), Cow::Owned(prod));
+        (quot, rem.into_owned())
+    }
+}
+impl<S: SFusedMulAdd + SField> SRem for DensePolynomial<S> {
+    fn rem<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        self.div_rem(lhs, rhs).1
+    }
+    /// Each coefficient reduced once, after `mul_coefficients_low`
+    fn mul_coefficients_rem(
+        &self,
+        lhs: &[Self::V],
+        rhs: &[Self::V],
+        len: usize,
+        m: &Self::V,
+    ) -> Vec<Self::V> {
+        self.mul_coefficients_low(lhs, rhs, len)
+            .into_iter()
+            .map(|c| self.rem(Cow::Owned(c), Cow::Borrowed(m)).into_owned())
+            .collect()
+    }
+}
+/// Exact division, by long division with exact division of the coefficients. Panics when `rhs`
+/// does not divide `lhs`.
+impl<S: SFusedMulAdd + SDiv + SSub> SDiv for DensePolynomial<S> {
+    fn div<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let mut rem = lhs.into_owned();
+        self.trim(&mut rem);
+        let Some(lc) = rhs.last() else {
+            panic!("polynomial division by zero");
+        };
+        if rem.len() < rhs.len() {
+            assert!(rem.is_empty(), "inexact polynomial division");
+            return self.zero();
+        }
+        let mut quot = vec![S::V::default(); rem.len() - rhs.len() + 1];
+        for i in (0..quot.len()).rev() {
+            let top = mem::take(&mut rem[i + rhs.len() - 1]);
+            let c = self
+                .inner
+                .div(Cow::Owned(top), Cow::Borrowed(lc))
+                .into_owned();
+            for (j, d) in rhs.iter().enumerate().take(rhs.len() - 1) {
+                let slot = Cow::Owned(mem::take(&mut rem[i + j]));
+                let prod = self.inner.mul(Cow::Borrowed(&c), Cow::Borrowed(d));
+                rem[i + j] = self.inner.sub(slot, prod).into_owned();
+            }
+            quot[i] = c;
+        }
+        assert!(
+            rem.iter().all(|c| *c == *self.inner.zero()),
+            "inexact polynomial division"
+        );
+        self.trim(&mut quot);
+        Cow::Owned(quot)
+    }
+}
+
+/// Below this many coefficients in the shorter operand, schoolbook multiplication is fastest. In
+/// `examples/mul_thresholds.rs` the crossover is near 200 integer coefficients of 64 bits, 64 of
+/// 256 bits and below 48 of 1024 bits.
+///
+/// `mul_karatsuba` and `mul_toom3` serve the coefficients without a Kronecker substitution:
+/// primitive integers, `GF2`, the limbs of `Gf2Poly` and power series. `Int` substitutes into one
+/// integer product instead, which beats them at every length (see `KRONECKER_THRESHOLD`), and
+/// nested polynomials substitute into one product over their coefficients, with its own kernel.
+/// `Real`, `Complex`, `Ball`, `Padic` and Laurent series stay with schoolbook multiplication, as
+/// the sums and differences of Karatsuba would round or lose precision.
+const KARATSUBA_THRESHOLD: usize = 96;
+/// From this many coefficients on, Toom-3 beats Karatsuba, by some 10% on 1024-bit integers and
+/// about evenly on 64-bit and 256-bit ones in `examples/mul_thresholds.rs`
+const TOOM3_THRESHOLD: usize = 768;
+/// From this many integer coefficients on, Kronecker substitution beats schoolbook
+/// multiplication, at 64, 256 and 1024 bits alike in `examples/mul_thresholds.rs`, so Karatsuba
+/// and Toom-3, both slower than it there, are not used for `Int`
+pub(crate) const KRONECKER_THRESHOLD: usize = 32;
+/// From this many integer coefficients on, transforms modulo one prime beat Kronecker
+/// substitution by 5 to 10% when the product's coefficients need 49 to 61 bits, as with 20-bit
+/// operands in `examples/mul_thresholds.rs`. More primes lose to it at every length measured,
+/// up to 2^17 coefficients, and so do fewer bits.
+pub(crate) const MULTI_PRIME_NTT_THRESHOLD: usize = 8192;
+/// From this many coefficients on, a transform modulo a suitable prime beats Kronecker
+/// substitution over the integers, by some 15% at 256 coefficients and 40% at 1024 in
+/// `examples/mul_thresholds.rs`
+pub(crate) const NTT_THRESHOLD: usize = 256;
+/// Below this many coefficients in an operand, multiplying only the terms of a short product
+/// beats cutting off the full product, by some 40% at 32 coefficients, modulo primes and over the
+/// integers alike
+pub(crate) const SHORT_MUL_THRESHOLD: usize = 96;
+/// From this many coefficients in both the divisor and the quotient, division by Newton
+/// iteration beats long division
+const NEWTON_DIV_THRESHOLD: usize = 64;
+
+/// Karatsuba multiplication of coefficient slices, with schoolbook multiplication below
+/// `KARATSUBA_THRESHOLD` coefficients, for overriding `SFusedMulAdd::mul_coefficients`
+pub fn mul_karatsuba<S: SFusedMulAdd + SSub>(s: &S, lhs: &[S::V], rhs: &[S::V]) -> Vec<S::V> {
+    FastMul { s, toom3: None }.mul(lhs, rhs)
+}
+/// `mul_karatsuba`, switching to Toom-3 from `TOOM3_THRESHOLD` coefficients on when 2 and 3
+/// can be divided out exactly
+pub fn mul_toom3<S: SFusedMulAdd + SDivSmall>(s: &S, lhs: &[S::V], rhs: &[S::V]) -> Vec<S::V> {
+    let toom3 = FastMul::toom3_division(s).map(|divide| (TOOM3_THRESHOLD, divide));
+    FastMul { s, toom3 }.mul(lhs, rhs)
+}
+
+/// Multiplication on integer representatives of the coefficients, by `mul` taking the operands'
+/// residues and the modulus
+fn mul_residues<S: SIntegerResidues>(
+    s: &S,
+    lhs: &[S::V],
+    rhs: &[S::V],
+    mul: fn(&[Integer], &[Integer], &Integer) -> Vec<Integer>,
+) -> Vec<S::V> {
+    let residues = |v: &[S::V]| -> Vec<Integer> { v.iter().map(|c| s.to_residue(c)).collect() };
+    let product = mul(&residues(lhs), &residues(rhs), &s.integer_modulus());
+    product.into_iter().map(|c| s.of_residue(c)).collect()
+}
+
+type DivSmall<S> = fn(&S, &<S as Structure>::V, u32) -> Option<<S as Structure>::V>;
+
+/// Subquadratic multiplication of coefficient slices. Products of nonempty slices have length
+/// `a.len() + b.len() - 1`.
+struct FastMul<'s, S: SFusedMulAdd + SSub> {
+    s: &'s S,
+    /// Where Toom-3 takes over from Karatsuba, with the exact division its interpolation needs
+    toom3: Option<(usize, DivSmall<S>)>,
+}
+impl<'s, S: SFusedMulAdd + SSub> FastMul<'s, S> {
+    /// Exact division for Toom-3 interpolation, if 2 and 3 can be divided out
+    fn toom3_division(s: &S) -> Option<DivSmall<S>>
+    where
+        S: SDivSmall,
+    {
+        let two = s.add(s.one(), s.one());
+        let three = s.add(two.clone(), s.one());
+        let exact = s.div_small_exact(&two, 2).is_some() && s.div_small_exact(&three, 3).is_some();
+        exact.then_some(S::div_small_exact as DivSmall<S>)
+    }
+
+    fn mul(&self, a: &[S::V], b: &[S::V]) -> Vec<S::V> {
+        let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+        if b.is_empty() {
+            return Vec::new();
+        }
+        if b.len() < KARATSUBA_THRESHOLD {
+            return self.schoolbook(a, b);
+        }
+        if a.len() > b.len() {
+            // Unbalanced operands are multiplied in chunks the size of the shorter one
+            let mut ret = self.zeros(a.len() + b.len() - 1);
+            for (k, chunk) in a.chunks(b.len()).enumerate() {
+                self.add_into(&mut ret, &self.mul(chunk, b), k * b.len());
+            }
+            return ret;
+        }
+        if let Some((threshold, divide)) = self.toom3
+            && b.len() >= threshold
+        {
+            self.toom3(a, b, divide)
+        } else {
+            self.karatsuba(a, b)
+        }
+    }
+
+    fn schoolbook(&self, a: &[S::V], b: &[S::V]) -> Vec<S::V> {
+        let mut ret = self.zeros(a.len() + b.len() - 1);
+        for (i, x) in a.iter().enumerate() {
+            for (j, y) in b.iter().enumerate() {
+                self.s.fused_mul_add_ref(&mut ret[i + j], x, y);
+            }
+        }
+        ret
+    }
+
+    /// `(a0 + a1 X)(b0 + b1 X) = z0 + ((a0 + a1)(b0 + b1) - z0 - z2) X + z2 X^2`
+    fn karatsuba(&self, a: &[S::V], b: &[S::V]) -> Vec<S::V> {
+        let n = a.len();
+        let half = n.div_ceil(2);
+        let (a0, a1) = a.split_at(half);
+        let (b0, b1) = b.split_at(half);
+        let z0 = self.mul(a0, b0);
+        let z2 = self.mul(a1, b1);
+        let mut z1 = self.mul(&self.sum(a0, a1), &self.sum(b0, b1));
+        self.sub_from(&mut z1, &z0);
+        self.sub_from(&mut z1, &z2);
+        let mut ret = self.zeros(2 * n - 1);
+        self.add_into(&mut ret, &z0, 0);
+        self.add_into(&mut ret, &z1, half);
+        self.add_into(&mut ret, &z2, 2 * half);
+        ret.truncate(2 * n - 1);
+        ret
+    }
+
+    /// Evaluates at `0, 1, -1, -2, infinity` and interpolates with Bodrato's sequence
+    fn toom3(&self, a: &[S::V], b: &[S::V], divide: DivSmall<S>) -> Vec<S::V> {
+        let n = a.len();
+        let third = n.div_ceil(3);
+        let evaluate = |p: &[S::V]| {
+            let (p0, rest) = p.split_at(third);
+            let (p1, p2) = rest.split_at(third);
+            let t = self.sum(p0, p2);
+            let at_1 = self.sum(&t, p1);
+            let at_m1 = self.difference(&t, p1);
+            let at_m2 = self.sum(&at_m1, p2);
+            let at_m2 = self.difference(&self.sum(&at_m2, &at_m2), p0);
+            [p0.to_vec(), at_1, at_m1, at_m2, p2.to_vec()]
+        };
+        let [a0, a1, am1, am2, ainf] = evaluate(a);
+        let [b0, b1, bm1, bm2, binf] = evaluate(b);
+        let r0 = self.mul(&a0, &b0);
+        let r1 = self.mul(&a1, &b1);
+        let rm1 = self.mul(&am1, &bm1);
+        let rm2 = self.mul(&am2, &bm2);
+        let rinf = self.mul(&ainf, &binf);
+
+        let div_exact = |v: Vec<S::V>, k| -> Vec<S::V> {
+            v.iter()
+                .map(|c| divide(self.s, c, k).expect("inexact division in toom-3 interpolation"))
+                .collect()
+        };
+        let r3 = div_exact(self.difference(&rm2, &r1), 3);
+        let r1 = div_exact(self.difference(&r1, &rm1), 2);
+        let r2 = self.difference(&rm1, &r0);
+        let r3 = div_exact(self.difference(&r2, &r3), 2);
+        let r3 = self.sum(&r3, &self.sum(&rinf, &rinf));
+        let r2 = self.difference(&self.sum(&r2, &r1), &rinf);
+        let r1 = self.difference(&r1, &r3);
+
+        let mut ret = self.zeros(2 * n - 1);
+        for (i, r) in [r0, r1, r2, r3, rinf].iter().enumerate() {
+            self.add_into(&mut ret, r, i * third);
+        }
+        ret.truncate(2 * n - 1);
+        ret
+    }
+
+    fn zeros(&self, len: usize) -> Vec<S::V> {
+        vec![self.s.zero().into_owned(); len]
+    }
+    /// `dst += src X^offset`, growing `dst` as needed
+    fn add_into(&self, dst: &mut Vec<S::V>, src: &[S::V], offset: usize) {
+        if dst.len() < src.len() + offset {
+            dst.resize(src.len() + offset, self.s.zero().into_owned());
+        }
+        for (slot, c) in Iterator::zip(dst[offset..].iter_mut(), src) {
+            let mut slot2 = Cow::Owned(mem::take(slot));
+            slot2 = self.s.add(slot2, Cow::Borrowed(c));
+            *slot = slot2.into_owned();
+        }
+    }
+    fn sub_from(&self, dst: &mut Vec<S::V>, src: &[S::V]) {
+        if dst.len() < src.len() {
+            dst.resize(src.len(), self.s.zero().into_owned());
+        }
+        for (slot, c) in Iterator::zip(dst.iter_mut(), src) {
+            let slot2 = Cow::Owned(mem::take(slot));
+            *slot = self.s.sub(slot2, Cow::Borrowed(c)).into_owned();
+        }
+    }
+    fn sum(&self, a: &[S::V], b: &[S::V]) -> Vec<S::V> {
+        let mut ret = a.to_vec();
+        self.add_into(&mut ret, b, 0);
+        ret
+    }
+    fn difference(&self, a: &[S::V], b: &[S::V]) -> Vec<S::V> {
+        let mut ret = a.to_vec();
+        self.sub_from(&mut ret, b);
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{I, Int, Mod, PowerSeries};
+
+    fn ints(v: &[i64]) -> Vec<Integer> {
+        v.iter().map(|&c| Integer::from(c)).collect()
+    }
+
+    #[test]
+    fn trailing_zeros_are_trimmed() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        assert_eq!(zx.el(ints(&[1, 0])), zx.el(ints(&[1])));
+        assert_eq!(zx.el(ints(&[0, 0, 0])), zx.el(Vec::new()));
+        assert_eq!(zx.degree(&zx.el(ints(&[3, 2, 0, 0]))), Some(1));
+        assert_eq!(zx.degree(&zx.el(ints(&[0]))), None);
+        assert!(zx.is_zero(&zx.el(ints(&[0, 0]))));
+        assert_eq!(*zx.leading_coefficient(&zx.el(ints(&[4, 5, 0]))).v, 5);
+        assert_eq!(zx.el(ints(&[0, 0])).to_string(), "0 (poly x, int)");
+    }
+
+    #[test]
+    fn sums_of_different_lengths_keep_all_coefficients() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        let short = zx.el(ints(&[1, 2]));
+        let long = zx.el(ints(&[3, 4, 5, 6]));
+        assert_eq!(short.copy() + long.copy(), zx.el(ints(&[4, 6, 5, 6])));
+        assert_eq!(long.copy() + short.copy(), zx.el(ints(&[4, 6, 5, 6])));
+        assert_eq!(short.copy() - long.copy(), zx.el(ints(&[-2, -2, -5, -6])));
+        assert_eq!(long.copy() - short.copy(), zx.el(ints(&[2, 2, 5, 6])));
+        // Cancelling leading terms leave a trimmed value
+        let cancel = zx.el(ints(&[1, 1, -5, -6]));
+        assert_eq!(long.copy() + cancel, zx.el(ints(&[4, 5])));
+        assert_eq!(long.copy() - long.copy(), zx.el(Vec::new()));
+    }
+
+    #[test]
+    fn products_with_zero_are_zero() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        let zero = zx.el(Vec::new());
+        let p = zx.el(ints(&[1, 2, 3]));
+        assert_eq!(zero.copy() * p.copy(), zero);
+        assert_eq!(p.copy() * zero.copy(), zero);
+        assert_eq!(zero.copy() * zero.copy(), zero);
+        for algorithm in [
+            MulAlgorithm::Schoolbook,
+            MulAlgorithm::Karatsuba,
+            MulAlgorithm::Toom3,
+            MulAlgorithm::Ntt,
+            MulAlgorithm::Kronecker,
+        ] {
+            assert_eq!(zx.mul_with(&zero, &p, algorithm), zero);
+            assert_eq!(zx.mul_with(&p, &zero, algorithm), zero);
+        }
+    }
+
+    #[test]
+    fn leading_zero_divisors_are_trimmed() {
+        let z6 = Mod::new(Int.el(6));
+        let z6x = DensePolynomial::new_symb("x", &z6);
+        let a = z6x.el(ints(&[1, 2]));
+        let b = z6x.el(ints(&[1, 3]));
+        let prod = a * b;
+        assert_eq!(prod, z6x.el(ints(&[1, 5])));
+        assert_eq!(z6x.degree(&prod), Some(1));
+    }
+
+    #[test]
+    fn algorithms_agree() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        for (n, m) in [(1, 1), (5, 300), (97, 97), (300, 800), (1000, 1000)] {
+            let coeffs = |len: i64, seed: i64| -> Vec<Integer> {
+                (0..len)
+                    .map(|i| Integer::from((i * i * 7919 + seed) % 20011 - 10005) << 40)
+                    .collect()
+            };
+            let a = zx.el(coeffs(n, 1));
+            let b = zx.el(coeffs(m, 2));
+            let expected = zx.mul_with(&a, &b, MulAlgorithm::Schoolbook);
+            assert_eq!(a.copy() * b.copy(), expected);
+            for algorithm in [
+                MulAlgorithm::Karatsuba,
+                MulAlgorithm::Toom3,
+                MulAlgorithm::Ntt,
+                MulAlgorithm::Kronecker,
+            ] {
+                assert_eq!(zx.mul_with(&a, &b, algorithm), expected, "{algorithm:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn automatic_karatsuba_and_toom3_match_schoolbook() {
+        let small = |len: usize, seed: u64| -> Vec<i64> {
+            pseudo_random(len, seed, 31)
+                .iter()
+                .map(|c| c.to_i64().unwrap() - 15)
+                .collect()
+        };
+        // Karatsuba above `KARATSUBA_THRESHOLD` and Toom-3 above `TOOM3_THRESHOLD`
+        let zx = DensePolynomial::new_symb("x", &I::<64>);
+        for (n, m) in [(100, 100), (100, 250), (800, 800)] {
+            let a = zx.el(small(n, 1));
+            let b = zx.el(small(m, 2));
+            let expected = zx.mul_with(&a, &b, MulAlgorithm::Schoolbook);
+            assert_eq!(a * b, expected);
+        }
+        let ps = PowerSeries::new_symb("t", &Int, 4);
+        let px = DensePolynomial::new_symb("x", &ps);
+        let series = |seed: u64| -> Vec<Vec<Integer>> {
+            (0..100).map(|i| pseudo_random(4, seed + i, 1000)).collect()
+        };
+        let a = px.el(series(1));
+        let b = px.el(series(500));
+        let expected = px.mul_schoolbook(a.v.clone(), b.v.clone()).into_owned();
+        assert_eq!(*(a * b).v, expected);
+    }
+
+    #[test]
+    fn monic_division_over_the_integers() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        // Long enough for the Newton path as well
+        for len in [3, 200] {
+            let a = zx.el((0..2 * len)
+                .map(|i| Integer::from(i * i - 50))
+                .collect::<Vec<_>>());
+            let mut b: Vec<Integer> = (0..len).map(|i| Integer::from(3 * i - 7)).collect();
+            b.push(Integer::from(1));
+            let b = zx.el(b);
+            let (q, r) = zx.div_rem_monic(&a, &b);
+            assert!(zx.degree(&r) < zx.degree(&b));
+            assert_eq!(q * b + r, a);
+        }
+    }
+
+    /// Deterministic coefficients below `modulus`
+    fn pseudo_random(len: usize, seed: u64, modulus: u64) -> Vec<Integer> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1);
+                Integer::from((state >> 33) % modulus)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn short_products_are_low_terms_of_full_products() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        for (n, m) in [(0, 5), (1, 1), (10, 7), (40, 40), (100, 30), (150, 150)] {
+            let a = pseudo_random(n, 1, 1000);
+            let b = pseudo_random(m, 2, 1000);
+            let full = zx.el(a.clone()) * zx.el(b.clone());
+            for len in [1, 5, 31, 32, 33, 64, 100, 160, 299, 400] {
+                let mut expected = full.v.to_vec();
+                expected.truncate(len);
+                assert_eq!(
+                    zx.mul_low(&a, &b, len),
+                    zx.el(expected).v.to_vec(),
+                    "{n} {m} {len}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn newton_division() {
+        let field = Mod::new_prime(Int.el(1_000_003));
+        let fx = DensePolynomial::new_symb("x", &field);
+        // Around the threshold in the quotient and divisor lengths, and far beyond it
+        for (a_len, b_len) in [
+            (127, 64),
+            (128, 64),
+            (129, 65),
+            (300, 64),
+            (300, 200),
+            (600, 300),
+        ] {
+            let a = fx.el(pseudo_random(a_len, a_len as u64, 1_000_003));
+            let mut b = pseudo_random(b_len, b_len as u64 + 7, 1_000_003);
+            *b.last_mut().unwrap() = Integer::from(12_345);
+            let b = fx.el(b);
+            let (q, r) = fx.div_rem(Cow::Borrowed(&*a.v), Cow::Borrowed(&*b.v));
+            let (q, r) = (fx.el(q.into_owned()), fx.el(r.into_owned()));
+            assert_eq!(fx.degree(&q), Some(a_len - b_len));
+            assert!(fx.degree(&r) < fx.degree(&b));
+            assert_eq!(q * b + r, a, "{a_len} {b_len}");
+        }
+        // An exact quotient leaves no remainder
+        let b = fx.el(pseudo_random(100, 3, 1_000_003));
+        let c = fx.el(pseudo_random(150, 4, 1_000_003));
+        let prod = b.copy() * c.copy();
+        let (q, r) = fx.div_rem(prod.v, Cow::Borrowed(&*b.v));
+        assert_eq!((q.into_owned(), r.is_empty()), (c.v.into_owned(), true));
+    }
+
+    #[test]
+    fn inverse_series() {
+        let field = Mod::new_prime(Int.el(1_000_003));
+        let fx = DensePolynomial::new_symb("x", &field);
+        let mut f = pseudo_random(50, 5, 1_000_003);
+        f[0] = Integer::from(2);
+        for prec in [1, 2, 7, 64, 200] {
+            let g = fx.inverse_series(&f, prec, Integer::from(500_002));
+            assert!(g.len() <= prec);
+            assert_eq!(fx.mul_low(&f, &g, prec), vec![Integer::from(1)], "{prec}");
         }
     }
 }
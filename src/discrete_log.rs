@@ -0,0 +1,401 @@
+use crate::{El, Int, SMul, crt::crt_integers, int::factorize};
+use rug::{Complete, Integer, ops::Pow};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+/// Solves `g^x = h` in a group of known order `n` (so `g^n = 1`), returning the least such `x`.
+/// Uses Pohlig–Hellman over the factorization of the order of `g`, with baby-step giant-step or
+/// Pollard rho for the prime-order subproblems.
+pub fn discrete_log<S: SMul>(
+    g: &El<'_, S>,
+    h: &El<'_, S>,
+    n: &El<'_, Int>,
+) -> Option<El<'static, Int>>
+where
+    S::V: Hash,
+{
+    assert_eq!(g.s, h.s);
+    assert!(g.pow(&n.v).v == g.s.one(), "g^n must be one");
+    let order = element_order(g, &n.v);
+    let mut residues = Vec::new();
+    for (q, e) in factorize(&order) {
+        let qe = q.clone().pow(e);
+        let cofactor = (&order / &qe).complete();
+        let x = prime_power_log(&g.pow(&cofactor), &h.pow(&cofactor), &q, e)?;
+        residues.push((x, qe));
+    }
+    let (x, _) = crt_integers(residues)?;
+    (g.pow(&x).v == h.v).then(|| int_el(x))
+}
+
+/// Solves `g^x = h` for `0 <= x < n` by baby-step giant-step, in `O(sqrt(n))` time and memory
+pub fn discrete_log_bsgs<S: SMul>(
+    g: &El<'_, S>,
+    h: &El<'_, S>,
+    n: &El<'_, Int>,
+) -> Option<El<'static, Int>>
+where
+    S::V: Hash,
+{
+    bsgs(g, h, &n.v).map(int_el)
+}
+
+/// Solves `g^x = h` with Pollard's rho method, where `n` is the order of `g`. Takes `O(sqrt(n))`
+/// expected time and constant memory; best suited to prime `n`.
+pub fn discrete_log_rho<S: SMul>(
+    g: &El<'_, S>,
+    h: &El<'_, S>,
+    n: &El<'_, Int>,
+) -> Option<El<'static, Int>>
+where
+    S::V: Hash,
+{
+    rho(g, h, &n.v).map(int_el)
+}
+
+/// Solves `g^x = h` for `x` in the interval `[lo, hi]` with Pollard's kangaroo method, in
+/// `O(sqrt(hi - lo))` expected time and constant memory, independently of the group order. The
+/// interval should be shorter than the order of `g`, as walks meeting a period away from it
+/// find nothing.
+pub fn discrete_log_kangaroo<S: SMul>(
+    g: &El<'_, S>,
+    h: &El<'_, S>,
+    lo: &El<'_, Int>,
+    hi: &El<'_, Int>,
+) -> Option<El<'static, Int>>
+where
+    S::V: Hash,
+{
+    assert_eq!(g.s, h.s);
+    assert!(lo.v <= hi.v);
+    // Each attempt uses a different pseudorandom walk, so a failed attempt is usually cured by
+    // trying again
+    (0..8)
+        .find_map(|salt| kangaroo(g, h, &lo.v, &hi.v, salt))
+        .map(int_el)
+}
+
+fn int_el(x: Integer) -> El<'static, Int> {
+    El {
+        v: Cow::Owned(x),
+        s: &Int,
+    }
+}
+
+fn is_one<S: SMul>(x: &El<'_, S>) -> bool {
+    *x.v == *x.s.one()
+}
+
+fn bucket<V: Hash>(v: &V, salt: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    salt.hash(&mut hasher);
+    v.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The order of `g`, given that it divides `n`
+fn element_order<S: SMul>(g: &El<'_, S>, n: &Integer) -> Integer {
+    let mut order = n.clone();
+    for (q, _) in factorize(n) {
+        while order.is_divisible(&q) {
+            let smaller = (&order / &q).complete();
+            if !is_one(&g.pow(&smaller)) {
+                break;
+            }
+            order = smaller;
+        }
+    }
+    order
+}
+
+/// Solves `g^x = h` where `g` has order `q^e`, one base-`q` digit at a time
+fn prime_power_log<S: SMul>(g: &El<'_, S>, h: &El<'_, S>, q: &Integer, e: u32) -> Option<Integer>
+where
+    S::V: Hash,
+{
+    let order = q.clone().pow(e);
+    let gamma = g.pow(&q.clone().pow(e - 1));
+    let mut x = Integer::ZERO;
+    let mut q_k = Integer::from(1);
+    for k in 0..e {
+        // g^-x computed as g^(order - x), since there is no inversion
+        let g_inv_x = g.pow(&(&order - &x).complete());
+        let h_k = El {
+            v: g.s.mul(g_inv_x.v, Cow::Borrowed(&*h.v)),
+            s: g.s,
+        }
+        .pow(&q.clone().pow(e - 1 - k));
+        let d = prime_order_log(&gamma, &h_k, q)?;
+        x += d * &q_k;
+        q_k *= q;
+    }
+    Some(x)
+}
+
+/// Solves `g^x = h` where `g` has prime order `q`
+fn prime_order_log<S: SMul>(g: &El<'_, S>, h: &El<'_, S>, q: &Integer) -> Option<Integer>
+where
+    S::V: Hash,
+{
+    if q.significant_bits() <= 40 {
+        bsgs(g, h, q)
+    } else {
+        rho(g, h, q)
+    }
+}
+
+/// Baby-step giant-step without inversion: finds `i`, `j` with `g^(i m) = h g^j`. There is
+/// nothing to find for `n <= 0`.
+fn bsgs<S: SMul>(g: &El<'_, S>, h: &El<'_, S>, n: &Integer) -> Option<Integer>
+where
+    S::V: Hash,
+{
+    assert_eq!(g.s, h.s);
+    if *n <= 0 {
+        return None;
+    }
+    let s = g.s;
+    let m = (n.clone() - 1u32).sqrt() + 1u32;
+    let m_usize = m
+        .to_usize()
+        .expect("group order too large for baby-step giant-step");
+    let mut baby = HashMap::with_capacity(m_usize);
+    let mut acc = h.v.clone().into_owned();
+    for j in 0..m_usize {
+        baby.entry(acc.clone()).or_insert(j);
+        acc = s.mul(Cow::Owned(acc), Cow::Borrowed(&*g.v)).into_owned();
+    }
+    let giant = g.pow(&m);
+    let mut acc = giant.v.clone().into_owned();
+    for i in 1..=m_usize {
+        if let Some(&j) = baby.get(&acc) {
+            let x = (Integer::from(i) * &m - j).div_rem_euc_ref(n).complete().1;
+            return Some(x);
+        }
+        acc = s
+            .mul(Cow::Owned(acc), Cow::Borrowed(&*giant.v))
+            .into_owned();
+    }
+    None
+}
+
+/// Pollard rho with Floyd cycle detection on the walk `x = g^a h^b`
+fn rho<S: SMul>(g: &El<'_, S>, h: &El<'_, S>, n: &Integer) -> Option<Integer>
+where
+    S::V: Hash,
+{
+    assert_eq!(g.s, h.s);
+    let s = g.s;
+    let step = |(x, a, b): &mut (S::V, Integer, Integer), salt: u64| match bucket(x, salt) % 3 {
+        0 => {
+            *x = s.mul(Cow::Borrowed(x), Cow::Borrowed(&*g.v)).into_owned();
+            *a += 1;
+            *a %= n;
+        }
+        1 => {
+            *x = s.mul(Cow::Borrowed(x), Cow::Borrowed(x)).into_owned();
+            *a <<= 1;
+            *b <<= 1;
+            *a %= n;
+            *b %= n;
+        }
+        _ => {
+            *x = s.mul(Cow::Borrowed(x), Cow::Borrowed(&*h.v)).into_owned();
+            *b += 1;
+            *b %= n;
+        }
+    };
+    for salt in 0..16u64 {
+        let a0 = Integer::from(salt * 7919 + 1) % n;
+        let b0 = Integer::from(salt * 104729 + 1) % n;
+        let x0 = s.mul(g.pow(&a0).v, h.pow(&b0).v).into_owned();
+        let mut tortoise = (x0, a0, b0);
+        let mut hare = tortoise.clone();
+        loop {
+            step(&mut tortoise, salt);
+            step(&mut hare, salt);
+            step(&mut hare, salt);
+            if tortoise.0 == hare.0 {
+                break;
+            }
+        }
+        // g^a1 h^b1 = g^a2 h^b2, so (b1 - b2) x = a2 - a1 (mod n)
+        let db = (&tortoise.2 - &hare.2)
+            .complete()
+            .div_rem_euc_ref(n)
+            .complete()
+            .1;
+        let da = (&hare.1 - &tortoise.1)
+            .complete()
+            .div_rem_euc_ref(n)
+            .complete()
+            .1;
+        if let Some(x) = solve_linear_congruence(&db, &da, n, |x| g.pow(x).v == h.v) {
+            return Some(x);
+        }
+    }
+    None
+}
+
+/// Finds `x` with `a x = b (mod n)` that passes `check`, trying each of the `gcd(a, n)`
+/// solutions when there are few enough
+fn solve_linear_congruence(
+    a: &Integer,
+    b: &Integer,
+    n: &Integer,
+    check: impl Fn(&Integer) -> bool,
+) -> Option<Integer> {
+    let d = a.gcd_ref(n).complete();
+    if !b.is_divisible(&d) || d > 1 << 16 {
+        return None;
+    }
+    let n_d = (n / &d).complete();
+    let x0 = if n_d == 1 {
+        Integer::ZERO
+    } else {
+        let a_d = (a / &d).complete();
+        (b / &d).complete() * a_d.invert(&n_d).ok()? % &n_d
+    };
+    (0..d.to_u32().unwrap())
+        .map(|k| &x0 + (&n_d * k).complete())
+        .find(|x| check(x))
+}
+
+fn kangaroo<S: SMul>(
+    g: &El<'_, S>,
+    h: &El<'_, S>,
+    lo: &Integer,
+    hi: &Integer,
+    salt: u64,
+) -> Option<Integer>
+where
+    S::V: Hash,
+{
+    let s = g.s;
+    let width = (hi - lo).complete();
+    // Jumps are powers of two with mean about sqrt(width) / 2
+    let k = (width.clone().sqrt().significant_bits() + 1) as usize;
+    let jumps: Vec<Integer> = (0..k).map(|i| Integer::from(1) << i as u32).collect();
+    let jump_els: Vec<S::V> = jumps.iter().map(|j| g.pow(j).v.into_owned()).collect();
+    let hop = |x: &mut S::V, dist: &mut Integer| {
+        let i = (bucket(x, salt) % k as u64) as usize;
+        *x = s
+            .mul(Cow::Borrowed(x), Cow::Borrowed(&jump_els[i]))
+            .into_owned();
+        *dist += &jumps[i];
+    };
+
+    // The tame kangaroo starts at the top of the interval and sets a trap
+    let mut tame = g.pow(hi).v.into_owned();
+    let mut tame_dist = Integer::ZERO;
+    let tame_steps = (width.clone().sqrt() + 1u32) * 4u32;
+    let mut i = Integer::ZERO;
+    while i < tame_steps {
+        hop(&mut tame, &mut tame_dist);
+        i += 1;
+    }
+    let trap_exp = hi + tame_dist;
+
+    // The wild kangaroo starts at h; if it lands in the trap, h = g^(trap_exp - wild_dist)
+    let mut wild = h.v.clone().into_owned();
+    let mut wild_dist = Integer::ZERO;
+    let max_dist = (&trap_exp - lo).complete();
+    while wild_dist <= max_dist {
+        if wild == tame {
+            // Walks that wrap around the group order meet outside the interval
+            let x = (&trap_exp - &wild_dist).complete();
+            return (*lo <= x && x <= *hi && g.pow(&x).v == h.v).then_some(x);
+        }
+        hop(&mut wild, &mut wild_dist);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DensePolynomial, Mod, Structure};
+
+    /// Every method's answer to `g^x = h` for `g` of order `n`, with the kangaroos searching
+    /// near `x`
+    fn all_methods<S: SMul>(
+        g: &El<'_, S>,
+        h: &El<'_, S>,
+        n: &Integer,
+        x: &Integer,
+    ) -> [Option<Integer>; 4]
+    where
+        S::V: Hash,
+    {
+        let n = Int.el(n.clone());
+        let lo = Int.el(Integer::from(x - 50u32).max(Integer::ZERO));
+        let hi = Int.el(Integer::from(x + 500u32));
+        [
+            discrete_log(g, h, &n),
+            discrete_log_bsgs(g, h, &n),
+            discrete_log_rho(g, h, &n),
+            discrete_log_kangaroo(g, h, &lo, &hi),
+        ]
+        .map(|x| x.map(|x| x.v.into_owned()))
+    }
+
+    #[test]
+    fn logs_modulo_a_prime() {
+        // The units modulo p have order 2 * 3 * 166667
+        let p = Integer::from(1_000_003);
+        let zp = Mod::new_prime(Int.el(p.clone()));
+        let g = zp.el(Integer::from(2));
+        let n = element_order(&g, &(&p - 1u32).complete());
+        for x in [0u32, 1, 2, 12_345, 999_999] {
+            let x = Integer::from(x) % &n;
+            let h = g.pow(&x);
+            for (method, found) in all_methods(&g, &h, &n, &x).into_iter().enumerate() {
+                assert_eq!(found.as_ref(), Some(&x), "method {method}");
+            }
+        }
+        // Outside the subgroup of squares
+        let square = zp.el(Integer::from(4));
+        let non_residue = zp.el(Integer::from(-1).div_rem_euc(p.clone()).1);
+        let n = Int.el((&p - 1u32).complete() / 2u32);
+        assert!(discrete_log(&square, &non_residue, &n).is_none());
+        assert!(discrete_log_bsgs(&square, &non_residue, &n).is_none());
+    }
+
+    #[test]
+    fn logs_in_a_polynomial_quotient_field() {
+        // F_5[x] / (x^5 - x + 1), with 3124 = 4 * 11 * 71 units
+        let f5 = Mod::new_prime(Int.el(5));
+        let f5x = DensePolynomial::new_symb("x", &f5);
+        let coeffs = |v: &[u32]| -> Vec<Integer> { v.iter().map(|&c| Integer::from(c)).collect() };
+        let field = Mod::new_irreducible(f5x.el(coeffs(&[1, 4, 0, 0, 0, 1])));
+        let g = field.el(coeffs(&[2, 1]));
+        let n = element_order(&g, &Integer::from(3124));
+        for x in [0u32, 7, 100, 3000] {
+            let x = Integer::from(x) % &n;
+            let h = g.pow(&x);
+            for (method, found) in all_methods(&g, &h, &n, &x).into_iter().enumerate() {
+                assert_eq!(found.as_ref(), Some(&x), "method {method}");
+            }
+        }
+    }
+
+    #[test]
+    fn search_ranges() {
+        let zp = Mod::new_prime(Int.el(101));
+        let g = zp.el(Integer::from(2));
+        let zero = Int.el(Integer::ZERO);
+        assert!(discrete_log_bsgs(&g, &g, &zero).is_none());
+        assert!(bsgs(&g, &g, &Integer::from(-3)).is_none());
+        assert_eq!(
+            discrete_log_bsgs(&g, &g, &Int.el(100)).map(|x| x.v.into_owned()),
+            Some(Integer::from(1))
+        );
+        // Kangaroos answer within their interval, even past the order of g
+        let (lo, hi) = (Int.el(1000), Int.el(1010));
+        let found = discrete_log_kangaroo(&g, &g.pow(&Integer::from(5)), &lo, &hi);
+        assert_eq!(found.map(|x| x.v.into_owned()), Some(Integer::from(1005)));
+    }
+}
@@ -0,0 +1,37 @@
+use crate::SDivRem;
+use std::{borrow::Cow, mem};
+
+/// Extended Euclidean algorithm: returns `(g, x, y)` with `g = a x + b y` a greatest common
+/// divisor of `a` and `b`
+pub(crate) fn xgcd<S: SDivRem>(s: &S, a: S::V, b: S::V) -> (S::V, S::V, S::V) {
+    xgcd_until(s, a, b, |_| false)
+}
+
+/// The extended Euclidean algorithm stopped at the first remainder `r = a x + b y` for which
+/// `done` holds, returning `(r, x, y)`, or at the greatest common divisor if there is none
+pub(crate) fn xgcd_until<S: SDivRem>(
+    s: &S,
+    a: S::V,
+    b: S::V,
+    done: impl Fn(&S::V) -> bool,
+) -> (S::V, S::V, S::V) {
+    let zero = s.zero().into_owned();
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (s.one().into_owned(), zero.clone());
+    let (mut y0, mut y1) = (zero.clone(), s.one().into_owned());
+    while r1 != zero && !done(&r0) {
+        let (q, r) = s.div_rem(Cow::Borrowed(&r0), Cow::Borrowed(&r1));
+        let (q, r) = (q.into_owned(), r.into_owned());
+        r0 = mem::replace(&mut r1, r);
+        for (c0, c1) in [(&mut x0, &mut x1), (&mut y0, &mut y1)] {
+            let next = s
+                .sub(
+                    Cow::Borrowed(&*c0),
+                    s.mul(Cow::Borrowed(&q), Cow::Borrowed(&*c1)),
+                )
+                .into_owned();
+            *c0 = mem::replace(c1, next);
+        }
+    }
+    (r0, x0, y0)
+}
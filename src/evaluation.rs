@@ -0,0 +1,258 @@
+use crate::{
+    DensePolynomial, El, SAdd, SField, SFusedMulAdd, SMul, SRem, Structure, SuperStructure,
+};
+use std::borrow::Cow;
+
+impl<S: SFusedMulAdd> DensePolynomial<S> {
+    /// `p(x)` by Horner's rule
+    pub fn evaluate<'a>(&self, p: &El<'_, Self>, x: &El<'a, S>) -> El<'a, S> {
+        assert_eq!(self, p.s);
+        assert_eq!(self.inner(), x.s);
+        let s = x.s;
+        let mut acc = s.zero().into_owned();
+        for c in p.v.iter().rev() {
+            acc = s.mul(Cow::Owned(acc), Cow::Borrowed(&*x.v)).into_owned();
+            acc = s.add(Cow::Owned(acc), Cow::Borrowed(c)).into_owned();
+        }
+        El {
+            v: Cow::Owned(acc),
+            s,
+        }
+    }
+    /// `p(x)` by Horner's rule, for `x` in an algebra over the coefficients into which `embed`
+    /// maps them, such as `|c| c.lift(ring)` for a polynomial or quotient ring over them
+    pub fn evaluate_in<'a, A: SAdd + SMul>(
+        &'a self,
+        p: &El<'_, Self>,
+        x: &El<'a, A>,
+        embed: impl Fn(El<'a, S>) -> El<'a, A>,
+    ) -> El<'a, A> {
+        assert_eq!(self, p.s);
+        let s = x.s;
+        let mut acc = s.zero().into_owned();
+        for c in p.v.iter().rev() {
+            acc = s.mul(Cow::Owned(acc), Cow::Borrowed(&*x.v)).into_owned();
+            let c = embed(self.inner().el(c.clone()));
+            assert_eq!(s, c.s);
+            acc = s.add(Cow::Owned(acc), c.v).into_owned();
+        }
+        El {
+            v: Cow::Owned(acc),
+            s,
+        }
+    }
+    /// `p(q(x))`, splitting `p` in halves so the work is a few products of balanced size
+    pub fn compose<'a>(&'a self, p: &El<'_, Self>, q: &El<'_, Self>) -> El<'a, Self> {
+        assert_eq!(self, p.s);
+        assert_eq!(self, q.s);
+        // powers[k] is q^(2^k)
+        let mut powers = vec![(*q.v).clone()];
+        while 2 << (powers.len() - 1) < p.v.len() {
+            let last = powers.last().unwrap();
+            let square = self.mul(Cow::Borrowed(last), Cow::Borrowed(last));
+            powers.push(square.into_owned());
+        }
+        self.el(self.compose_split(&p.v, &powers))
+    }
+    fn compose_split(&self, p: &[S::V], powers: &[Vec<S::V>]) -> Vec<S::V> {
+        if p.len() <= 1 {
+            return self.el(p.to_vec()).v.into_owned();
+        }
+        // The largest power of two below the length
+        let k = (p.len() - 1).ilog2() as usize;
+        let (lo, hi) = p.split_at(1 << k);
+        let hi = self.compose_split(hi, &powers[..k]);
+        let hi = self.mul(Cow::Owned(hi), Cow::Borrowed(&powers[k]));
+        let lo = self.compose_split(lo, &powers[..k]);
+        self.add(Cow::Owned(lo), hi).into_owned()
+    }
+}
+
+impl<S: SFusedMulAdd + SField> DensePolynomial<S> {
+    /// `p` at each of the points, by reducing `p` down a subproduct tree of the points
+    pub fn evaluate_many<'a>(&'a self, p: &El<'_, Self>, points: &[El<'_, S>]) -> Vec<El<'a, S>> {
+        assert_eq!(self, p.s);
+        if points.len() < MULTIPOINT_THRESHOLD {
+            return points
+                .iter()
+                .map(|x| {
+                    let y = self.evaluate(p, x);
+                    self.inner().el(y.v.into_owned())
+                })
+                .collect();
+        }
+        let tree = SubproductTree::new(self, points);
+        tree.remainders(&p.v)
+            .into_iter()
+            .map(|r| match r.into_iter().next() {
+                Some(c) => self.inner().el(c),
+                None => El {
+                    v: self.inner().zero(),
+                    s: self.inner(),
+                },
+            })
+            .collect()
+    }
+}
+
+/// From this many points on, multipoint evaluation beats Horner's rule at each point
+const MULTIPOINT_THRESHOLD: usize = 160;
+
+/// The products of `x - a_i` over ever larger runs of the points `a_i`: `levels[0]` holds the
+/// linear factors, each level multiplies adjacent pairs of the one below, and the last level is
+/// the product of all of them
+pub(crate) struct SubproductTree<'s, S: SFusedMulAdd + SField> {
+    poly: &'s DensePolynomial<S>,
+    pub(crate) levels: Vec<Vec<Vec<S::V>>>,
+}
+impl<'s, S: SFusedMulAdd + SField> SubproductTree<'s, S> {
+    pub(crate) fn new(poly: &'s DensePolynomial<S>, points: &[El<'_, S>]) -> Self {
+        assert!(!points.is_empty(), "subproduct tree of no points");
+        let s = poly.inner();
+        let mut level: Vec<Vec<S::V>> = points
+            .iter()
+            .map(|a| {
+                assert_eq!(s, a.s);
+                let mut neg = Cow::Borrowed(&*a.v);
+                s.negate(&mut neg);
+                poly.el(vec![neg.into_owned(), s.one().into_owned()])
+                    .v
+                    .into_owned()
+            })
+            .collect();
+        let mut levels = Vec::new();
+        while level.len() > 1 {
+            let next = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => poly.mul(Cow::Borrowed(a), Cow::Borrowed(b)).into_owned(),
+                    [a] => a.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(level);
+            level = next;
+        }
+        levels.push(level);
+        Self { poly, levels }
+    }
+    /// The product of all the `x - a_i`
+    pub(crate) fn root(&self) -> &Vec<S::V> {
+        &self.levels.last().unwrap()[0]
+    }
+    /// `p` modulo each `x - a_i`, which is the constant `p(a_i)`
+    pub(crate) fn remainders(&self, p: &[S::V]) -> Vec<Vec<S::V>> {
+        let mut rems = vec![
+            self.poly
+                .rem(Cow::Owned(p.to_vec()), Cow::Borrowed(self.root()))
+                .into_owned(),
+        ];
+        for level in self.levels.iter().rev().skip(1) {
+            rems = level
+                .iter()
+                .enumerate()
+                .map(|(i, m)| {
+                    self.poly
+                        .rem(Cow::Borrowed(&rems[i / 2]), Cow::Borrowed(m))
+                        .into_owned()
+                })
+                .collect();
+        }
+        rems
+    }
+    /// `sum c_i m / (x - a_i)` for `m` the product of all the `x - a_i`, by combining adjacent
+    /// sums up the tree
+    pub(crate) fn linear_combination(&self, c: Vec<Vec<S::V>>) -> Vec<S::V> {
+        let mut sums = c;
+        for level in &self.levels[..self.levels.len() - 1] {
+            sums = Iterator::zip(sums.chunks(2), level.chunks(2))
+                .map(|pair| match pair {
+                    ([s0, s1], [m0, m1]) => {
+                        let lhs = self.poly.mul(Cow::Borrowed(s0), Cow::Borrowed(m1));
+                        let rhs = self.poly.mul(Cow::Borrowed(s1), Cow::Borrowed(m0));
+                        self.poly.add(lhs, rhs).into_owned()
+                    }
+                    ([s0], [_]) => s0.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+        sums.pop().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Int, Mod};
+    use rug::Integer;
+
+    const P: u32 = 1_000_003;
+
+    /// `len` pseudorandom residues modulo `P`
+    fn residues(len: usize, seed: u64) -> Vec<Integer> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                Integer::from((state >> 33) % u64::from(P))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn multipoint_evaluation_agrees_with_horner() {
+        let fp = Mod::new_prime(Int.el(P));
+        let fpx = DensePolynomial::new_symb("x", &fp);
+        // Below and above the threshold, with more and fewer points than the degree
+        for (len, count) in [(1, 5), (40, 20), (300, 200), (100, 400)] {
+            let p = fpx.el(residues(len, len as u64));
+            let points: Vec<_> = residues(count, 7 + count as u64)
+                .into_iter()
+                .map(|x| fp.el(x))
+                .collect();
+            let values = fpx.evaluate_many(&p, &points);
+            assert_eq!(values.len(), count);
+            for (x, y) in Iterator::zip(points.iter(), &values) {
+                assert_eq!(*y, fpx.evaluate(&p, x));
+            }
+        }
+        let zero = fpx.el(Vec::new());
+        let points: Vec<_> = residues(200, 3).into_iter().map(|x| fp.el(x)).collect();
+        assert!(
+            fpx.evaluate_many(&zero, &points)
+                .iter()
+                .all(|y| *y.v == *fp.zero())
+        );
+    }
+
+    #[test]
+    fn composition_agrees_with_horner() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        let ints = |v: Vec<Integer>| v.into_iter().map(|c| c - P / 2).collect::<Vec<_>>();
+        for (len, inner_len) in [(0, 3), (1, 3), (2, 1), (5, 0), (17, 4), (64, 2), (33, 9)] {
+            let p = zx.el(ints(residues(len, 1)));
+            let q = zx.el(ints(residues(inner_len, 2)));
+            let naive = zx.evaluate_in(&p, &q, |c| c.lift(&zx));
+            assert_eq!(zx.compose(&p, &q), naive, "{len} {inner_len}");
+        }
+        // Over a prime field, where the halves are multiplied with reduction
+        let fp = Mod::new_prime(Int.el(P));
+        let fpx = DensePolynomial::new_symb("x", &fp);
+        let p = fpx.el(residues(100, 3));
+        let q = fpx.el(residues(6, 4));
+        assert_eq!(
+            fpx.compose(&p, &q),
+            fpx.evaluate_in(&p, &q, |c| c.lift(&fpx))
+        );
+        // p(q(x)) at a point is p at q of the point
+        let x = fp.el(Integer::from(12345));
+        let at_q = fpx.evaluate(&q, &x);
+        assert_eq!(
+            fpx.evaluate(&fpx.compose(&p, &q), &x),
+            fpx.evaluate(&p, &at_q)
+        );
+    }
+}
@@ -0,0 +1,493 @@
+use crate::{
+    DensePolynomial, El, Int, Mod, SAdd, SDiv, SField, SFusedMulAdd, SGcd, SMul, SPolynomialGcd,
+    SRem, SSub, Structure, SuperStructure,
+    conway::conway_polynomial,
+    euclid::xgcd,
+    int::{factorize, is_prime},
+};
+use rug::{Complete, Integer, ops::Pow};
+use std::{borrow::Cow, fmt};
+
+/// The finite field with `p^n` elements, as polynomials in a named generator modulo an
+/// irreducible defining polynomial of degree `n` over the prime field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GF {
+    n: u32,
+    ring: Mod<DensePolynomial<Mod<Int, true>>, true>,
+    /// Polynomials in `x` over the prime field, for defining and minimal polynomials
+    poly: DensePolynomial<Mod<Int, true>>,
+}
+impl Structure for GF {
+    type V = Vec<Integer>;
+    fn fmt_v(&self, v: &Self::V, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        self.ring.fmt_v(v, f)
+    }
+    /// Reduces the coefficients modulo `p` and the polynomial modulo the defining polynomial, so
+    /// that equal elements have equal values
+    fn el<'a>(&'a self, v: impl Into<Self::V>) -> El<'a, Self> {
+        let p = self.characteristic().v;
+        let v: Vec<Integer> = v
+            .into()
+            .into_iter()
+            .map(|c| c.div_rem_euc_ref(&p).complete().1)
+            .collect();
+        let poly = self.ring.inner();
+        let v = poly.rem(Cow::Owned(v), self.ring.modulus().v).into_owned();
+        El {
+            v: Cow::Owned(v),
+            s: self,
+        }
+    }
+}
+impl std::fmt::Display for GF {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if f.alternate() {
+            write!(f, ", ")?;
+        }
+        write!(f, "gf {}^{}", self.characteristic().v, self.n)
+    }
+}
+impl SuperStructure for GF {
+    type Inner = Mod<Int, true>;
+    fn inner(&self) -> &Self::Inner {
+        self.ring.inner().inner()
+    }
+    fn lifted_from<'a>(&'a self, inner: El<'a, Self::Inner>) -> El<'a, Self> {
+        assert_eq!(self.inner(), inner.s);
+        let c = inner
+            .v
+            .into_owned()
+            .div_rem_euc(self.characteristic().v.into_owned())
+            .1;
+        El {
+            v: Cow::Owned(if c == 0 { Vec::new() } else { vec![c] }),
+            s: self,
+        }
+    }
+}
+
+impl GF {
+    /// The field with `p^n` elements and generator named `symbol`. The defining polynomial is the
+    /// Conway polynomial when it is in the bundled table, and otherwise the first irreducible
+    /// polynomial in lexicographic order.
+    pub fn new_symb(symbol: impl AsRef<str>, p: El<'_, Int>, n: u32) -> Self {
+        assert!(n >= 1, "extension degree must be positive");
+        assert!(is_prime(&p.v), "characteristic must be prime");
+        let base = Mod::new_prime(p.copy());
+        let poly = DensePolynomial::new_symb("x", &base);
+        let modulus = match conway_polynomial(&p.v, n) {
+            Some(coeffs) => coeffs.iter().map(|&c| Integer::from(c)).collect(),
+            None if n == 1 => {
+                // The Conway polynomial of degree one is x minus the least primitive root
+                let root = least_primitive_root(&p.v);
+                vec![&*p.v - root, Integer::from(1)]
+            }
+            None => first_irreducible(&poly, n),
+        };
+        Self::with_modulus(symbol, poly.el(modulus))
+    }
+    /// The field defined by the given irreducible polynomial over a prime field, with generator
+    /// named `symbol`
+    pub fn with_modulus(
+        symbol: impl AsRef<str>,
+        modulus: El<'_, DensePolynomial<Mod<Int, true>>>,
+    ) -> Self {
+        let n = modulus.s.degree(&modulus).unwrap_or(0) as u32;
+        let ring_poly = DensePolynomial::new_symb(symbol, modulus.s.inner());
+        Self {
+            n,
+            ring: Mod::new_irreducible(ring_poly.el(modulus.v.into_owned())),
+            poly: modulus.s.clone(),
+        }
+    }
+
+    pub fn characteristic(&self) -> El<'_, Int> {
+        self.inner().modulus()
+    }
+    pub fn degree(&self) -> u32 {
+        self.n
+    }
+    /// The number of elements, `p^n`
+    pub fn order(&self) -> El<'static, Int> {
+        El {
+            v: Cow::Owned(self.characteristic().v.into_owned().pow(self.n)),
+            s: &Int,
+        }
+    }
+    /// The monic defining polynomial, in `x`
+    pub fn defining_polynomial(&self) -> El<'_, DensePolynomial<Mod<Int, true>>> {
+        El {
+            v: self.ring.modulus().v,
+            s: &self.poly,
+        }
+    }
+    /// The generator, a root of the defining polynomial
+    pub fn symb(&self) -> El<'_, Self> {
+        let x = vec![Integer::ZERO, Integer::from(1)];
+        El {
+            v: Cow::Owned(
+                self.ring
+                    .inner()
+                    .rem(Cow::Owned(x), self.ring.modulus().v)
+                    .into_owned(),
+            ),
+            s: self,
+        }
+    }
+    pub fn inv<'a>(&'a self, a: &El<'_, Self>) -> Option<El<'a, Self>> {
+        assert_eq!(self, a.s);
+        let inv = self.ring.inv(&self.ring.el(a.v.clone().into_owned()))?;
+        Some(self.el(inv.v.into_owned()))
+    }
+    /// The Frobenius automorphism `a -> a^p`
+    pub fn frobenius<'a>(&'a self, a: &El<'_, Self>) -> El<'a, Self> {
+        a.pow(&self.characteristic().v).extend_lifetime(self)
+    }
+    /// The conjugates `a, a^p, a^(p^2), ...` up to the first repetition
+    pub fn conjugates<'a>(&'a self, a: &El<'_, Self>) -> Vec<El<'a, Self>> {
+        let mut ret = vec![self.el((*a.v).clone())];
+        loop {
+            let next = self.frobenius(ret.last().unwrap());
+            if next == ret[0] {
+                return ret;
+            }
+            ret.push(next);
+        }
+    }
+    /// The trace `a + a^p + ... + a^(p^(n-1))` down to the prime field
+    pub fn trace<'a>(&'a self, a: &El<'_, Self>) -> El<'a, Mod<Int, true>> {
+        let mut acc = a.copy().extend_lifetime(self);
+        let mut sum = acc.copy().extend_lifetime(self);
+        for _ in 1..self.n {
+            acc = self.frobenius(&acc);
+            sum += acc.copy().extend_lifetime(self);
+        }
+        self.to_prime_field(&sum)
+    }
+    /// The norm `a a^p ... a^(p^(n-1))` down to the prime field
+    pub fn norm<'a>(&'a self, a: &El<'_, Self>) -> El<'a, Mod<Int, true>> {
+        let p = self.characteristic().v.into_owned();
+        let exp = (self.order().v.into_owned() - 1u32) / (p - 1u32);
+        self.to_prime_field(&a.pow(&exp))
+    }
+    /// The monic minimal polynomial over the prime field, in `x`
+    pub fn minimal_polynomial<'a>(
+        &'a self,
+        a: &El<'_, Self>,
+    ) -> El<'a, DensePolynomial<Mod<Int, true>>> {
+        let over_self = DensePolynomial::new_symb("x", self);
+        let mut acc = over_self.one().into_owned();
+        for c in self.conjugates(a) {
+            let mut neg = c.v;
+            self.negate(&mut neg);
+            let factor = vec![neg.into_owned(), self.one().into_owned()];
+            acc = over_self
+                .mul(Cow::Owned(acc), Cow::Owned(factor))
+                .into_owned();
+        }
+        let coeffs = acc
+            .iter()
+            .map(|c| c.first().cloned().unwrap_or_default())
+            .collect::<Vec<_>>();
+        El {
+            v: Cow::Owned(coeffs),
+            s: &self.poly,
+        }
+    }
+    /// The first generator of the multiplicative group, with the generator tried first and then
+    /// the elements in lexicographic order of their coefficients
+    pub fn primitive_element(&self) -> El<'_, Self> {
+        let group_order = self.order().v.into_owned() - 1u32;
+        let factors = factorize(&group_order);
+        let is_primitive = |g: &El<'_, Self>| {
+            *g.v != *self.zero()
+                && factors
+                    .iter()
+                    .all(|(q, _)| *g.pow(&(&group_order / q).complete()).v != *self.one())
+        };
+        let p = self.characteristic().v.into_owned();
+        let gen_ = self.symb();
+        if is_primitive(&gen_) {
+            return gen_;
+        }
+        (1u32..)
+            .map(|k| self.el(base_p_digits(Integer::from(k), &p)))
+            .find(is_primitive)
+            .unwrap()
+    }
+
+    fn to_prime_field<'a>(&'a self, a: &El<'_, Self>) -> El<'a, Mod<Int, true>> {
+        assert!(a.v.len() <= 1, "element is not in the prime field");
+        self.inner().el(a.v.first().cloned().unwrap_or_default())
+    }
+}
+impl SAdd for GF {
+    fn zero(&self) -> Cow<'_, Self::V> {
+        self.ring.zero()
+    }
+    fn add<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        self.ring.add(lhs, rhs)
+    }
+}
+impl SSub for GF {
+    fn negate<'a>(&'a self, v: &mut Cow<'a, Self::V>) {
+        self.ring.negate(v)
+    }
+    fn sub<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        self.ring.sub(lhs, rhs)
+    }
+}
+impl SMul for GF {
+    fn one(&self) -> Cow<'_, Self::V> {
+        self.ring.one()
+    }
+    fn mul<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        self.ring.mul(lhs, rhs)
+    }
+}
+impl SDiv for GF {
+    fn div<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        self.ring.div(lhs, rhs)
+    }
+}
+impl SField for GF {}
+impl SGcd for GF {
+    fn gcd<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        if *lhs == *self.zero() && *rhs == *self.zero() {
+            self.zero()
+        } else {
+            self.one()
+        }
+    }
+    fn unit_part(&self, v: &Self::V) -> Self::V {
+        if *v == *self.zero() {
+            self.one().into_owned()
+        } else {
+            v.clone()
+        }
+    }
+    fn quotient_characteristic(&self, m: &Self::V) -> Integer {
+        if *m == *self.zero() {
+            self.characteristic().v.into_owned()
+        } else {
+            Integer::from(1)
+        }
+    }
+    /// `v^(p^(n-1))`, as `v^(p^n) = v`
+    fn pth_root(&self, v: &Self::V) -> Option<Self::V> {
+        let p = self.characteristic().v.into_owned();
+        let exp = p.pow(self.n - 1);
+        Some(self.el(v.clone()).pow(&exp).v.into_owned())
+    }
+}
+impl SPolynomialGcd for GF {}
+impl SFusedMulAdd for GF {
+    fn fused_mul_add_ref(&self, acc: &mut Self::V, lhs: &Self::V, rhs: &Self::V) {
+        self.ring.fused_mul_add_ref(acc, lhs, rhs)
+    }
+    fn mul_coefficients(&self, lhs: &[Self::V], rhs: &[Self::V]) -> Vec<Self::V> {
+        self.ring.mul_coefficients(lhs, rhs)
+    }
+}
+
+/// Coefficients of `k` written in base `p`, lowest first
+fn base_p_digits(mut k: Integer, p: &Integer) -> Vec<Integer> {
+    let mut ret = Vec::new();
+    while k != 0 {
+        let (q, r) = k.div_rem_euc_ref(p).complete();
+        ret.push(r);
+        k = q;
+    }
+    ret
+}
+
+fn least_primitive_root(p: &Integer) -> Integer {
+    let order = (p - 1u32).complete();
+    let factors = factorize(&order);
+    (1u32..)
+        .map(Integer::from)
+        .find(|g| {
+            factors.iter().all(|(q, _)| {
+                g.pow_mod_ref(&(&order / q).complete(), p)
+                    .unwrap()
+                    .complete()
+                    != 1
+            })
+        })
+        .unwrap()
+}
+
+/// The first monic irreducible polynomial of degree `n`, ordering by coefficients read as base
+/// `p` digits with the constant term least significant
+fn first_irreducible(poly: &DensePolynomial<Mod<Int, true>>, n: u32) -> Vec<Integer> {
+    let p = poly.inner().modulus().v.into_owned();
+    (1u32..)
+        .map(|k| {
+            let mut coeffs = base_p_digits(Integer::from(k), &p);
+            coeffs.resize(n as usize, Integer::ZERO);
+            coeffs.push(Integer::from(1));
+            coeffs
+        })
+        .find(|coeffs| is_irreducible(&poly.el(coeffs.clone())))
+        .unwrap()
+}
+
+/// Rabin's test: a polynomial `f` of degree `n` over `F_p` is irreducible exactly when `f`
+/// divides `x^(p^n) - x` and is coprime to `x^(p^(n/q)) - x` for each prime `q` dividing `n`
+pub(crate) fn is_irreducible(f: &El<'_, DensePolynomial<Mod<Int, true>>>) -> bool {
+    let poly = f.s;
+    let mut f_coeffs = f.v.clone().into_owned();
+    while f_coeffs.last() == Some(&Integer::ZERO) {
+        f_coeffs.pop();
+    }
+    let n = match f_coeffs.len() {
+        0 | 1 => return false,
+        len => len as u32 - 1,
+    };
+    if n == 1 {
+        return true;
+    }
+    let p = poly.inner().modulus().v.into_owned();
+    let quotient = Mod::new(poly.el(f_coeffs.clone()));
+    let x = quotient.el(vec![Integer::ZERO, Integer::from(1)]);
+    // x^(p^k) for k = 0..=n
+    let mut frobenius_powers = vec![x.copy()];
+    for _ in 0..n {
+        let next = frobenius_powers.last().unwrap().pow(&p);
+        frobenius_powers.push(next);
+    }
+    let x_minus = |k: u32| {
+        let diff = frobenius_powers[k as usize].copy() - x.copy();
+        diff.v.into_owned()
+    };
+    let mut zero_check = x_minus(n);
+    while zero_check.last() == Some(&Integer::ZERO) {
+        zero_check.pop();
+    }
+    if !zero_check.is_empty() {
+        return false;
+    }
+    factorize(&Integer::from(n)).into_iter().all(|(q, _)| {
+        let (g, _, _) = xgcd(poly, f_coeffs.clone(), x_minus(n / q.to_u32().unwrap()));
+        g.iter().skip(1).all(|c| *c == 0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every element of `field`, in lexicographic order of their coefficients
+    fn elements(field: &GF) -> Vec<El<'_, GF>> {
+        let p = field.characteristic().v.into_owned();
+        let q = field.order().v.to_u32().unwrap();
+        (0..q)
+            .map(|k| field.el(base_p_digits(Integer::from(k), &p)))
+            .collect()
+    }
+
+    fn ints(v: &[u32]) -> Vec<Integer> {
+        v.iter().map(|&c| Integer::from(c)).collect()
+    }
+
+    #[test]
+    fn construction() {
+        let gf256 = GF::new_symb("a", Int.el(2), 8);
+        assert_eq!(
+            *gf256.defining_polynomial().v,
+            ints(&[1, 0, 1, 1, 1, 0, 0, 0, 1])
+        );
+        assert_eq!(*gf256.order().v, 256);
+        let gf243 = GF::new_symb("a", Int.el(3), 5);
+        assert_eq!(*gf243.defining_polynomial().v, ints(&[1, 2, 0, 0, 0, 1]));
+        assert_eq!(gf243.degree(), 5);
+        // x minus the least primitive root, from the table and past it
+        assert_eq!(
+            *GF::new_symb("a", Int.el(5), 1).defining_polynomial().v,
+            ints(&[3, 1])
+        );
+        let gf113 = GF::new_symb("a", Int.el(113), 1);
+        assert_eq!(*gf113.defining_polynomial().v, ints(&[110, 1]));
+        // Past the table, the first irreducible polynomial: x^2 + k for the least nonsquare -k
+        let gf113_2 = GF::new_symb("a", Int.el(113), 2);
+        let f = gf113_2.defining_polynomial();
+        let k = f.v[0].to_u32().unwrap();
+        assert_eq!(f.v[1..], ints(&[0, 1]));
+        let is_square = |c: u32| (0..113u32).any(|x| x * x % 113 == c % 113);
+        assert!(!is_square(113 - k));
+        assert!((1..k).all(|j| is_square(113 - j)));
+        let field = GF::with_modulus("b", f.copy());
+        assert_eq!(field.to_string(), "gf 113^2");
+    }
+
+    #[test]
+    fn field_operations() {
+        for (p, n) in [(2, 8), (3, 5)] {
+            let field = GF::new_symb("a", Int.el(p), n);
+            let q = field.order().v.into_owned();
+            let zero = field.zero().into_owned();
+            let primitive = field.primitive_element();
+            for (q_factor, _) in factorize(&(&q - 1u32).complete()) {
+                let exp = (&q - 1u32).complete() / q_factor;
+                assert_ne!(*primitive.pow(&exp).v, *field.one());
+            }
+            assert_eq!(*primitive.pow(&(&q - 1u32).complete()).v, *field.one());
+            assert!(field.inv(&field.el(zero.clone())).is_none());
+            let mut previous = field.el(zero);
+            for a in elements(&field) {
+                // Landing in the prime field, which `to_prime_field` asserts
+                let (trace, norm) = (field.trace(&a), field.norm(&a));
+                let sum = a.copy() + previous.copy();
+                let product = a.copy() * previous.copy();
+                assert_eq!(field.trace(&sum), trace.copy() + field.trace(&previous));
+                assert_eq!(field.norm(&product), norm.copy() * field.norm(&previous));
+                // The Frobenius map is additive and has order n
+                let mut image = field.frobenius(&sum);
+                assert_eq!(image, field.frobenius(&a) + field.frobenius(&previous));
+                for _ in 1..n {
+                    image = field.frobenius(&image);
+                }
+                assert_eq!(image, sum);
+                // The minimal polynomial annihilates a, with the conjugates as its roots
+                let minimal = field.minimal_polynomial(&a);
+                let degree = minimal.s.degree(&minimal).unwrap();
+                assert_eq!(degree, field.conjugates(&a).len());
+                assert!((n as usize).is_multiple_of(degree));
+                let mut value = field.el(Vec::new());
+                for c in minimal.v.iter().rev() {
+                    value = value * a.copy() + field.el(vec![c.clone()]);
+                }
+                assert_eq!(*value.v, *field.zero(), "{a}");
+                if let Some(inverse) = field.inv(&a) {
+                    assert_eq!(*(a.copy() * inverse).v, *field.one());
+                }
+                previous = a;
+            }
+        }
+    }
+
+    #[test]
+    fn irreducibility_over_prime_fields() {
+        let f3 = Mod::new_prime(Int.el(3));
+        let poly = DensePolynomial::new_symb("x", &f3);
+        // Monic cubics and quartics over F_3 are irreducible exactly without roots and, for
+        // quartics, without monic quadratic factors
+        let monic = |k: u32, n: u32| {
+            let mut coeffs = base_p_digits(Integer::from(k), &Integer::from(3));
+            coeffs.resize(n as usize, Integer::ZERO);
+            coeffs.push(Integer::from(1));
+            poly.el(coeffs)
+        };
+        let quadratics: Vec<_> = (0..9).map(|k| monic(k, 2)).collect();
+        for n in [3, 4] {
+            for k in 0..3u32.pow(n) {
+                let f = monic(k, n);
+                let factorable = quadratics
+                    .iter()
+                    .chain(&[monic(0, 1), monic(1, 1), monic(2, 1)])
+                    .any(|d| poly.rem(f.v.clone(), d.v.clone()).is_empty());
+                assert_eq!(is_irreducible(&f), !factorable, "{f}");
+            }
+        }
+    }
+}
@@ -0,0 +1,418 @@
+use crate::{
+    DensePolynomial, El, Int, Mod, SDiv, SMul, SRem, SSub, Structure, SuperStructure, finite_field,
+    square_free::Factorization,
+};
+use rug::{Complete, Integer, ops::Pow, rand::RandState};
+use std::{borrow::Cow, mem};
+
+impl DensePolynomial<Mod<Int, true>> {
+    /// Ben-Or's test: `f` of degree `n` over `F_p` is irreducible exactly when it is coprime to
+    /// `x^(p^k) - x` for every `k <= n / 2`, which rejects most reducible polynomials after a few
+    /// powerings
+    pub fn is_irreducible(&self, f: &El<'_, Self>) -> bool {
+        assert_eq!(self, f.s);
+        let n = match self.degree(f) {
+            None | Some(0) => return false,
+            Some(1) => return true,
+            Some(n) => n,
+        };
+        let p = self.inner().modulus().v.into_owned();
+        let quotient = Mod::new(self.el((*f.v).clone()));
+        let x = quotient.el(vec![Integer::ZERO, Integer::from(1)]);
+        let mut h = x.copy();
+        (1..=n / 2).all(|_| {
+            h = h.pow(&p);
+            let g = f.gcd(&self.el((h.copy() - x.copy()).v.into_owned()));
+            self.degree(&g) == Some(0)
+        })
+    }
+    /// Rabin's test: `f` of degree `n` over `F_p` is irreducible exactly when it divides
+    /// `x^(p^n) - x` and is coprime to `x^(p^(n/q)) - x` for each prime `q` dividing `n`
+    pub fn is_irreducible_rabin(&self, f: &El<'_, Self>) -> bool {
+        assert_eq!(self, f.s);
+        finite_field::is_irreducible(f)
+    }
+    /// `(c, [(f_1, e_1), (f_2, e_2), ...])` with `p = c f_1^e_1 f_2^e_2 ...` and the `f_i`
+    /// distinct monic irreducible polynomials, ordered by degree and then by coefficients from
+    /// the top. Splits by Berlekamp's algorithm for small primes and by Cantor–Zassenhaus
+    /// otherwise, drawing random polynomials from `rng`.
+    pub fn factor<'a>(
+        &'a self,
+        p: &El<'_, Self>,
+        rng: &mut RandState<'_>,
+    ) -> Factorization<'a, Mod<Int, true>> {
+        if self
+            .inner()
+            .modulus()
+            .v
+            .to_u32()
+            .is_some_and(|q| q <= BERLEKAMP_MAX_PRIME)
+        {
+            self.factor_berlekamp(p)
+        } else {
+            self.factor_cantor_zassenhaus(p, rng)
+        }
+    }
+    /// `factor` by square-free, distinct-degree and then Cantor–Zassenhaus equal-degree
+    /// factorization, for odd `p`
+    pub fn factor_cantor_zassenhaus<'a>(
+        &'a self,
+        p: &El<'_, Self>,
+        rng: &mut RandState<'_>,
+    ) -> Factorization<'a, Mod<Int, true>> {
+        self.factor_square_free_parts(p, |f| {
+            let mut ret = Vec::new();
+            for (g, d) in self.distinct_degree_factorization(f) {
+                ret.extend(self.equal_degree_factorization(&g, d as usize, rng));
+            }
+            ret
+        })
+    }
+    /// `factor` by square-free factorization and then Berlekamp's algorithm, which is
+    /// deterministic and takes time linear in `p`
+    pub fn factor_berlekamp<'a>(&'a self, p: &El<'_, Self>) -> Factorization<'a, Mod<Int, true>> {
+        self.factor_square_free_parts(p, |f| self.berlekamp(f))
+    }
+    /// The distinct roots in `F_p` of the nonzero `p` with their multiplicities, in increasing
+    /// order. The roots of each square-free part are those of its gcd with `x^p - x`, computed in
+    /// the quotient ring, which is split into linear factors by Cantor–Zassenhaus with `rng`, or
+    /// solved directly in degrees one and two.
+    pub fn roots<'a>(
+        &'a self,
+        p: &El<'_, Self>,
+        rng: &mut RandState<'_>,
+    ) -> Vec<(El<'a, Mod<Int, true>>, u32)> {
+        let s = self.inner();
+        let q = s.modulus().v.into_owned();
+        let x = vec![Integer::ZERO, Integer::from(1)];
+        let mut ret = Vec::new();
+        for (f, e) in self.square_free_factorization(p).1 {
+            let quotient = Mod::new(self.el((*f.v).clone()));
+            // x itself is not reduced when f is linear
+            let power = quotient.el(self
+                .rem(Cow::Borrowed(&x), Cow::Borrowed(&f.v))
+                .into_owned());
+            let power = power.pow(&q).v.into_owned();
+            let g = f.gcd(&(self.el(power) - self.el(x.clone())));
+            ret.extend(self.split_roots(&g, rng).into_iter().map(|r| (s.el(r), e)));
+        }
+        ret.sort_by(|(a, _), (b, _)| a.v.cmp(&b.v));
+        ret
+    }
+    /// The monic irreducible factors of `f`, a monic product of distinct irreducible polynomials
+    /// of degree `d` over `F_p` for odd `p`, by Cantor–Zassenhaus: for a random `a`, `a^((p^d -
+    /// 1) / 2)` is `1` modulo about half of the factors and `-1` modulo the rest, so its
+    /// gcd with `f` after subtracting one usually splits `f`
+    pub fn equal_degree_factorization<'a>(
+        &'a self,
+        f: &El<'_, Self>,
+        d: usize,
+        rng: &mut RandState<'_>,
+    ) -> Vec<El<'a, Self>> {
+        assert_eq!(self, f.s);
+        let q = self.inner().modulus().v.into_owned();
+        assert!(q.is_odd(), "cantor-zassenhaus needs an odd characteristic");
+        let n = self.degree(f).expect("equal-degree factorization of zero");
+        assert!(
+            d > 0 && n.is_multiple_of(d),
+            "degree not a multiple of the factor degree"
+        );
+        if n == d {
+            return vec![self.el((*f.v).clone())];
+        }
+        let exp: Integer = (q.clone().pow(u32::try_from(d).unwrap()) - 1u32) >> 1u32;
+        let quotient = Mod::new(self.el((*f.v).clone()));
+        let one = self.el(self.one().into_owned());
+        loop {
+            let a = self.el((0..n)
+                .map(|_| Integer::from(q.random_below_ref(rng)))
+                .collect::<Vec<_>>());
+            if self.degree(&a).is_none_or(|k| k == 0) {
+                continue;
+            }
+            let mut g = f.gcd(&a);
+            if self.degree(&g) == Some(0) {
+                let b = quotient.el(a.v.into_owned()).pow(&exp);
+                g = f.gcd(&(self.el(b.v.into_owned()) - one.copy()));
+            }
+            if self.degree(&g).is_some_and(|k| 0 < k && k < n) {
+                let rest = self.quo(f, &g);
+                let mut ret = self.equal_degree_factorization(&g, d, rng);
+                ret.extend(self.equal_degree_factorization(&rest, d, rng));
+                return ret;
+            }
+        }
+    }
+    /// The roots of the monic `g`, a product of distinct linear factors
+    fn split_roots(&self, g: &El<'_, Self>, rng: &mut RandState<'_>) -> Vec<Integer> {
+        let q = self.inner().modulus().v.into_owned();
+        let reduce = |x: Integer| x.div_rem_euc_ref(&q).complete().1;
+        match self.degree(g).unwrap() {
+            0 => Vec::new(),
+            1 => vec![reduce(-g.v[0].clone())],
+            2 if q == 2 => vec![Integer::ZERO, Integer::from(1)],
+            // (-b +- sqrt(b^2 - 4c)) / 2
+            2 => {
+                let (b, c) = (&g.v[1], &g.v[0]);
+                let disc = reduce(Integer::from(b.square_ref()) - Integer::from(c * 4u32));
+                let half = Integer::from(&q + 1u32) >> 1u32;
+                let disc = self.inner().el(disc);
+                self.inner()
+                    .sqrts(&disc)
+                    .map(|r| reduce((r.v.into_owned() - b) * &half))
+                    .collect()
+            }
+            _ => self
+                .equal_degree_factorization(g, 1, rng)
+                .into_iter()
+                .map(|h| reduce(-h.v[0].clone()))
+                .collect(),
+        }
+    }
+    /// The monic irreducible factors of the monic square-free `f` by Berlekamp's algorithm: the
+    /// `g` with `g^p = g` modulo `f` form a space of dimension the number of factors, and for
+    /// `g` in it outside the constants, `f` is the product of the `gcd(f, g - s)` over `s` in
+    /// `F_p`
+    fn berlekamp<'a>(&'a self, f: &El<'_, Self>) -> Vec<El<'a, Self>> {
+        let s = self.inner();
+        let n = self.degree(f).unwrap();
+        let quotient = Mod::new(self.el((*f.v).clone()));
+        // Row i is x^(p i) - x^i modulo f
+        let xp = quotient
+            .el(vec![Integer::ZERO, Integer::from(1)])
+            .pow(&s.modulus().v)
+            .v
+            .into_owned();
+        let mut power = self.one().into_owned();
+        let mut matrix = Vec::with_capacity(n);
+        for i in 0..n {
+            let mut row = power.clone();
+            row.resize(n, Integer::ZERO);
+            row[i] = s
+                .sub(Cow::Owned(mem::take(&mut row[i])), s.one())
+                .into_owned();
+            matrix.push(row);
+            power = quotient
+                .mul(Cow::Owned(power), Cow::Borrowed(&xp))
+                .into_owned();
+        }
+        let basis = left_kernel(s, matrix);
+        let mut factors = vec![self.el((*f.v).clone())];
+        // The first basis vector is the constants
+        for v in &basis[1..] {
+            if factors.len() == basis.len() {
+                break;
+            }
+            let v = self.el(v.clone());
+            let mut next = Vec::new();
+            for h in factors {
+                if self.degree(&h) == Some(1) {
+                    next.push(h);
+                    continue;
+                }
+                let mut h = h;
+                for c in 0..s.modulus().v.to_u32().unwrap() {
+                    let shifted = v.copy() - self.el(vec![Integer::from(c)]);
+                    let g = h.gcd(&shifted);
+                    if self.degree(&g).is_some_and(|k| k > 0) {
+                        h = self.quo(&h, &g);
+                        next.push(g);
+                        if self.degree(&h) == Some(0) {
+                            break;
+                        }
+                    }
+                }
+            }
+            factors = next;
+        }
+        factors
+    }
+}
+
+/// Up to this prime, `factor` uses Berlekamp's algorithm
+const BERLEKAMP_MAX_PRIME: u32 = 128;
+
+/// A basis of the vectors `v` with `v M = 0` over the field `s`, in reduced echelon form, whose
+/// first vector is the first unit vector when the first row of `M` is zero
+fn left_kernel(s: &Mod<Int, true>, matrix: Vec<Vec<Integer>>) -> Vec<Vec<Integer>> {
+    let n = matrix.len();
+    // Transposed, so that the kernel is that of a right multiplication
+    let mut a: Vec<Vec<Integer>> = (0..matrix[0].len())
+        .map(|j| matrix.iter().map(|row| row[j].clone()).collect())
+        .collect();
+    let mut pivots = Vec::new();
+    let mut r = 0;
+    for j in 0..n {
+        let Some(k) = (r..a.len()).find(|&k| a[k][j] != 0) else {
+            continue;
+        };
+        a.swap(r, k);
+        let inv = s.div(s.one(), Cow::Borrowed(&a[r][j])).into_owned();
+        for c in &mut a[r] {
+            *c = s.mul(Cow::Borrowed(c), Cow::Borrowed(&inv)).into_owned();
+        }
+        let pivot_row = a[r].clone();
+        for (k, row) in a.iter_mut().enumerate() {
+            if k == r || row[j] == 0 {
+                continue;
+            }
+            let factor = mem::take(&mut row[j]);
+            for (c, d) in row.iter_mut().zip(&pivot_row).skip(j + 1) {
+                let prod = s.mul(Cow::Borrowed(&factor), Cow::Borrowed(d));
+                *c = s.sub(Cow::Borrowed(c), prod).into_owned();
+            }
+        }
+        pivots.push(j);
+        r += 1;
+    }
+    let mut basis = Vec::new();
+    for free in (0..n).filter(|j| !pivots.contains(j)) {
+        let mut v = vec![Integer::ZERO; n];
+        v[free] = Integer::from(1);
+        for (row, &j) in a.iter().zip(&pivots) {
+            let mut neg = Cow::Borrowed(&row[free]);
+            s.negate(&mut neg);
+            v[j] = neg.into_owned();
+        }
+        basis.push(v);
+    }
+    basis
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SDivRem;
+
+    fn random_poly(rng: &mut RandState<'_>, q: u32, len: usize) -> Vec<Integer> {
+        (0..len)
+            .map(|_| Integer::from(Integer::random_below_ref(&Integer::from(q), rng)))
+            .collect()
+    }
+
+    /// Every monic polynomial of degree `n` over `F_q`
+    fn monic(q: u32, n: u32) -> impl Iterator<Item = Vec<Integer>> {
+        (0..q.pow(n)).map(move |mut k| {
+            let mut v = Vec::new();
+            for _ in 0..n {
+                v.push(Integer::from(k % q));
+                k /= q;
+            }
+            v.push(Integer::from(1));
+            v
+        })
+    }
+
+    #[test]
+    fn irreducibility_tests_agree() {
+        let f3 = Mod::new_prime(Int.el(3));
+        let f3x = DensePolynomial::new_symb("x", &f3);
+        // The counts (1/n) sum_(d | n) mu(d) 3^(n/d) of monic irreducibles
+        for (n, count) in [(1, 3), (2, 3), (3, 8), (4, 18), (6, 116)] {
+            let mut irreducible = 0;
+            for f in monic(3, n) {
+                let f = f3x.el(f);
+                let ben_or = f3x.is_irreducible(&f);
+                assert_eq!(ben_or, f3x.is_irreducible_rabin(&f), "{f}");
+                irreducible += usize::from(ben_or);
+            }
+            assert_eq!(irreducible, count, "{n}");
+        }
+        let zero = f3x.el(Vec::new());
+        let one = f3x.el(vec![Integer::from(1)]);
+        assert!(!f3x.is_irreducible(&zero) && !f3x.is_irreducible(&one));
+    }
+
+    #[test]
+    fn berlekamp_and_cantor_zassenhaus_agree() {
+        let mut rng = RandState::new();
+        rng.seed(&Integer::from(3));
+        for q in [3, 5, 101] {
+            let fq = Mod::new_prime(Int.el(q));
+            let fqx = DensePolynomial::new_symb("x", &fq);
+            for i in 0..20 {
+                // Random factors, with repeated ones now and then
+                let mut p = fqx.el(random_poly(&mut rng, q, 1 + i % 9));
+                let repeated = fqx.el(random_poly(&mut rng, q, 1 + i % 4));
+                p *= repeated.copy() * repeated.copy();
+                if p.v.is_empty() {
+                    continue;
+                }
+                let (c, factors) = fqx.factor_berlekamp(&p);
+                let (c_cz, factors_cz) = fqx.factor_cantor_zassenhaus(&p, &mut rng);
+                assert_eq!(c, c_cz);
+                assert_eq!(factors, factors_cz, "{p}");
+                let mut product = fqx.el(vec![c.v.into_owned()]);
+                for (f, e) in &factors {
+                    assert!(fqx.is_irreducible(f));
+                    assert_eq!(*fqx.leading_coefficient(f).v, 1);
+                    product *= f.pow(&Integer::from(*e));
+                }
+                assert_eq!(product, p);
+            }
+        }
+    }
+
+    #[test]
+    fn roots_with_multiplicities() {
+        let mut rng = RandState::new();
+        rng.seed(&Integer::from(4));
+        // Small primes against every element, and a large one against the planted roots
+        for q in [2, 3, 13] {
+            let fq = Mod::new_prime(Int.el(q));
+            let fqx = DensePolynomial::new_symb("x", &fq);
+            for i in 0..30 {
+                let p = fqx.el(random_poly(&mut rng, q, 2 + i % 7));
+                let p = p.copy() * p.copy() * fqx.el(random_poly(&mut rng, q, 3));
+                if p.v.is_empty() {
+                    continue;
+                }
+                let roots: Vec<_> = fqx
+                    .roots(&p, &mut rng)
+                    .into_iter()
+                    .map(|(r, e)| (r.v.into_owned(), e))
+                    .collect();
+                let mut expected = Vec::new();
+                for r in 0..q {
+                    let mut rest = (*p.v).clone();
+                    let x_minus_r = vec![Integer::from(q - r), Integer::from(1)];
+                    let mut e = 0;
+                    loop {
+                        let (quo, rem) =
+                            fqx.div_rem(Cow::Borrowed(&rest), Cow::Borrowed(&x_minus_r));
+                        if !rem.is_empty() {
+                            break;
+                        }
+                        rest = quo.into_owned();
+                        e += 1;
+                    }
+                    if e > 0 {
+                        expected.push((Integer::from(r), e));
+                    }
+                }
+                assert_eq!(roots, expected, "{p}");
+            }
+        }
+        let q = 1_000_003u32;
+        let fq = Mod::new_prime(Int.el(q));
+        let fqx = DensePolynomial::new_symb("x", &fq);
+        // x^2 + 1 has no roots since q is 3 modulo 4
+        let mut p = fqx.el(vec![Integer::from(1), Integer::ZERO, Integer::from(1)]);
+        let planted = [(5, 1), (777, 3), (999_999, 2), (123_456, 1)];
+        for (r, e) in planted {
+            let x_minus_r = fqx.el(vec![Integer::from(q - r), Integer::from(1)]);
+            p *= x_minus_r.pow(&Integer::from(e));
+        }
+        let roots: Vec<_> = fqx
+            .roots(&p, &mut rng)
+            .into_iter()
+            .map(|(r, e)| (r.v.into_owned(), e))
+            .collect();
+        let mut expected: Vec<_> = planted
+            .iter()
+            .map(|&(r, e)| (Integer::from(r), e))
+            .collect();
+        expected.sort();
+        assert_eq!(roots, expected);
+    }
+}
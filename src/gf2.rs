@@ -0,0 +1,593 @@
+use crate::{
+    El, SAdd, SDiv, SDivRem, SField, SFusedMulAdd, SGcd, SIntegerResidues, SMul, SPolynomialGcd,
+    SRem, SSub, Structure, SuperStructure, int::factorize, mul_karatsuba,
+};
+use rug::Integer;
+use std::{borrow::Cow, fmt, mem};
+
+/// The field with two elements, as booleans
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GF2;
+impl Structure for GF2 {
+    type V = bool;
+    fn fmt_v(&self, v: &Self::V, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", u8::from(*v))
+    }
+}
+impl std::fmt::Display for GF2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if f.alternate() {
+            write!(f, ", ")?;
+        }
+        write!(f, "gf2")
+    }
+}
+impl SAdd for GF2 {
+    fn zero(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(false)
+    }
+    fn add(&self, lhs: Cow<'_, Self::V>, rhs: Cow<'_, Self::V>) -> Cow<'_, Self::V> {
+        Cow::Owned(*lhs ^ *rhs)
+    }
+}
+impl SSub for GF2 {
+    fn negate(&self, _: &mut Cow<'_, Self::V>) {}
+    fn sub(&self, lhs: Cow<'_, Self::V>, rhs: Cow<'_, Self::V>) -> Cow<'_, Self::V> {
+        Cow::Owned(*lhs ^ *rhs)
+    }
+}
+impl SMul for GF2 {
+    fn one(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(true)
+    }
+    fn mul(&self, lhs: Cow<'_, Self::V>, rhs: Cow<'_, Self::V>) -> Cow<'_, Self::V> {
+        Cow::Owned(*lhs & *rhs)
+    }
+}
+impl SFusedMulAdd for GF2 {
+    fn fused_mul_add_ref(&self, acc: &mut Self::V, lhs: &Self::V, rhs: &Self::V) {
+        *acc ^= *lhs & *rhs;
+    }
+    fn mul_coefficients(&self, lhs: &[Self::V], rhs: &[Self::V]) -> Vec<Self::V> {
+        mul_karatsuba(self, lhs, rhs)
+    }
+}
+impl SIntegerResidues for GF2 {
+    fn integer_modulus(&self) -> Integer {
+        Integer::from(2)
+    }
+    fn to_residue(&self, v: &Self::V) -> Integer {
+        Integer::from(*v)
+    }
+    fn of_residue(&self, v: Integer) -> Self::V {
+        v.is_odd()
+    }
+}
+impl SRem for GF2 {
+    fn rem(&self, _: Cow<'_, Self::V>, rhs: Cow<'_, Self::V>) -> Cow<'_, Self::V> {
+        assert!(*rhs, "division by zero");
+        Cow::Owned(false)
+    }
+}
+impl SDiv for GF2 {
+    fn div(&self, lhs: Cow<'_, Self::V>, rhs: Cow<'_, Self::V>) -> Cow<'_, Self::V> {
+        assert!(*rhs, "division by zero");
+        Cow::Owned(*lhs)
+    }
+}
+impl SDivRem for GF2 {
+    fn div_rem<'a>(
+        &'a self,
+        lhs: Cow<'a, Self::V>,
+        rhs: Cow<'a, Self::V>,
+    ) -> (Cow<'a, Self::V>, Cow<'a, Self::V>) {
+        (self.div(lhs, rhs), Cow::Owned(false))
+    }
+}
+impl SField for GF2 {}
+impl SGcd for GF2 {
+    fn gcd(&self, lhs: Cow<'_, Self::V>, rhs: Cow<'_, Self::V>) -> Cow<'_, Self::V> {
+        Cow::Owned(*lhs || *rhs)
+    }
+    fn unit_part(&self, _v: &Self::V) -> Self::V {
+        true
+    }
+    fn quotient_characteristic(&self, m: &Self::V) -> Integer {
+        Integer::from(if *m { 1 } else { 2 })
+    }
+    fn pth_root(&self, v: &Self::V) -> Option<Self::V> {
+        Some(*v)
+    }
+}
+impl SPolynomialGcd for GF2 {}
+
+/// Polynomials over GF(2), bit-packed into `u64` limbs with the constant term in the lowest bit
+/// of the first limb. Values are kept without trailing zero limbs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Gf2Poly {
+    symbol: String,
+}
+impl Structure for Gf2Poly {
+    type V = Vec<u64>;
+    fn fmt_v(&self, v: &Self::V, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let Some(deg) = degree(v) else {
+            return write!(f, "0");
+        };
+        for (i, e) in (0..=deg).rev().filter(|&e| bit(v, e)).enumerate() {
+            if i != 0 {
+                write!(f, " + ")?;
+            }
+            match e {
+                0 => write!(f, "1")?,
+                1 => write!(f, "{}", self.symbol)?,
+                _ => write!(f, "{}^{e}", self.symbol)?,
+            }
+        }
+        Ok(())
+    }
+    fn el<'a>(&'a self, v: impl Into<Self::V>) -> El<'a, Self> {
+        let mut v = v.into();
+        trim(&mut v);
+        El {
+            v: Cow::Owned(v),
+            s: self,
+        }
+    }
+}
+impl std::fmt::Display for Gf2Poly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if f.alternate() {
+            write!(f, ", ")?;
+        }
+        write!(f, "poly {}{:#}", self.symbol, GF2)
+    }
+}
+impl SuperStructure for Gf2Poly {
+    type Inner = GF2;
+    fn inner(&self) -> &Self::Inner {
+        &GF2
+    }
+    fn lifted_from<'a>(&'a self, inner: El<'a, Self::Inner>) -> El<'a, Self> {
+        El {
+            v: Cow::Owned(if *inner.v { vec![1] } else { Vec::new() }),
+            s: self,
+        }
+    }
+}
+
+impl Gf2Poly {
+    pub fn new_symb(symbol: impl AsRef<str>) -> Self {
+        Self {
+            symbol: symbol.as_ref().to_owned(),
+        }
+    }
+    pub fn symb(&self) -> El<'_, Self> {
+        self.el(vec![0b10])
+    }
+    /// The polynomial with a one coefficient at each of the given exponents
+    pub fn from_exponents(&self, exponents: impl IntoIterator<Item = usize>) -> El<'_, Self> {
+        let mut v = Vec::new();
+        for e in exponents {
+            if v.len() <= e / 64 {
+                v.resize(e / 64 + 1, 0);
+            }
+            v[e / 64] ^= 1 << (e % 64);
+        }
+        self.el(v)
+    }
+    /// `None` for the zero polynomial
+    pub fn degree(&self, a: &El<'_, Self>) -> Option<usize> {
+        degree(&a.v)
+    }
+    pub fn coefficient(&self, a: &El<'_, Self>, e: usize) -> bool {
+        bit(&a.v, e)
+    }
+    /// The monic greatest common divisor
+    pub fn gcd<'a>(&'a self, a: &El<'_, Self>, b: &El<'_, Self>) -> El<'a, Self> {
+        let (mut a, mut b) = (a.v.clone().into_owned(), b.v.clone().into_owned());
+        trim(&mut a);
+        trim(&mut b);
+        while !b.is_empty() {
+            div_rem_limbs(&mut a, &b);
+            mem::swap(&mut a, &mut b);
+        }
+        self.el(a)
+    }
+    /// Rabin's test: `f` of degree `n` is irreducible exactly when `f` divides `x^(2^n) - x` and
+    /// is coprime to `x^(2^(n/q)) - x` for each prime `q` dividing `n`
+    pub fn is_irreducible(&self, f: &El<'_, Self>) -> bool {
+        let mut f = f.v.clone().into_owned();
+        trim(&mut f);
+        let n = match degree(&f) {
+            None | Some(0) => return false,
+            Some(1) => return true,
+            Some(n) => n,
+        };
+        // x^(2^k) mod f for k = 0..=n, by repeated squaring
+        let mut frobenius_powers = vec![vec![0b10]];
+        for _ in 0..n {
+            let mut next = square_limbs(frobenius_powers.last().unwrap());
+            div_rem_limbs(&mut next, &f);
+            frobenius_powers.push(next);
+        }
+        let x_minus = |k: usize| {
+            let mut diff = frobenius_powers[k].clone();
+            xor_into(&mut diff, &[0b10], 0);
+            trim(&mut diff);
+            diff
+        };
+        if !x_minus(n).is_empty() {
+            return false;
+        }
+        factorize(&Integer::from(n)).into_iter().all(|(q, _)| {
+            let k = n / q.to_usize().unwrap();
+            let g = self.gcd(&self.el(f.clone()), &self.el(x_minus(k)));
+            *g.v == [1]
+        })
+    }
+}
+impl SAdd for Gf2Poly {
+    fn zero(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(Vec::new())
+    }
+    fn add<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let (mut target, src) = match (lhs, rhs) {
+            (Cow::Owned(l), r) if l.len() >= r.len() => (l, r),
+            (l, Cow::Owned(r)) => (r, l),
+            (l, r) => (l.into_owned(), r),
+        };
+        xor_into(&mut target, &src, 0);
+        trim(&mut target);
+        Cow::Owned(target)
+    }
+}
+impl SSub for Gf2Poly {
+    fn negate(&self, _: &mut Cow<'_, Self::V>) {}
+    fn sub<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        self.add(lhs, rhs)
+    }
+}
+impl SMul for Gf2Poly {
+    fn one(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(vec![1])
+    }
+    fn mul<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let mut ret = mul_limbs(&lhs, &rhs);
+        trim(&mut ret);
+        Cow::Owned(ret)
+    }
+}
+impl SFusedMulAdd for Gf2Poly {
+    fn fused_mul_add_ref(&self, acc: &mut Self::V, lhs: &Self::V, rhs: &Self::V) {
+        xor_into(acc, &mul_limbs(lhs, rhs), 0);
+        trim(acc);
+    }
+    fn mul_coefficients(&self, lhs: &[Self::V], rhs: &[Self::V]) -> Vec<Self::V> {
+        mul_karatsuba(self, lhs, rhs)
+    }
+}
+impl SDivRem for Gf2Poly {
+    fn div_rem<'a>(
+        &'a self,
+        lhs: Cow<'a, Self::V>,
+        rhs: Cow<'a, Self::V>,
+    ) -> (Cow<'a, Self::V>, Cow<'a, Self::V>) {
+        let mut rem = lhs.into_owned();
+        let quot = div_rem_limbs(&mut rem, &rhs);
+        (Cow::Owned(quot), Cow::Owned(rem))
+    }
+}
+impl SRem for Gf2Poly {
+    fn rem<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let mut rem = lhs.into_owned();
+        div_rem_limbs(&mut rem, &rhs);
+        Cow::Owned(rem)
+    }
+    /// Each coefficient reduced once, after `mul_coefficients_low`
+    fn mul_coefficients_rem(
+        &self,
+        lhs: &[Self::V],
+        rhs: &[Self::V],
+        len: usize,
+        m: &Self::V,
+    ) -> Vec<Self::V> {
+        self.mul_coefficients_low(lhs, rhs, len)
+            .into_iter()
+            .map(|mut c| {
+                div_rem_limbs(&mut c, m);
+                c
+            })
+            .collect()
+    }
+}
+
+/// Below this many limbs, schoolbook multiplication beats Karatsuba
+const KARATSUBA_THRESHOLD: usize = 16;
+
+fn trim(v: &mut Vec<u64>) {
+    while v.last() == Some(&0) {
+        v.pop();
+    }
+}
+
+fn bit(v: &[u64], e: usize) -> bool {
+    v.get(e / 64).is_some_and(|limb| limb >> (e % 64) & 1 == 1)
+}
+
+fn degree(v: &[u64]) -> Option<usize> {
+    let i = v.iter().rposition(|&limb| limb != 0)?;
+    Some(64 * i + 63 - v[i].leading_zeros() as usize)
+}
+
+/// `dst ^= src << (64 * offset)`, growing `dst` as needed
+fn xor_into(dst: &mut Vec<u64>, src: &[u64], offset: usize) {
+    if dst.len() < src.len() + offset {
+        dst.resize(src.len() + offset, 0);
+    }
+    for (d, s) in dst[offset..].iter_mut().zip(src) {
+        *d ^= s;
+    }
+}
+
+/// `dst ^= src << shift` for a shift in bits, growing `dst` as needed
+fn xor_shifted(dst: &mut Vec<u64>, src: &[u64], shift: usize) {
+    let (words, bits) = (shift / 64, shift % 64);
+    if dst.len() < src.len() + words + 1 {
+        dst.resize(src.len() + words + 1, 0);
+    }
+    for (i, &s) in src.iter().enumerate() {
+        dst[i + words] ^= s << bits;
+        if bits != 0 {
+            dst[i + words + 1] ^= s >> (64 - bits);
+        }
+    }
+}
+
+/// Carry-less product of two limbs, as `(low, high)`
+fn clmul(a: u64, b: u64) -> (u64, u64) {
+    let (mut lo, mut hi) = (0, 0);
+    for i in 0..64 {
+        if b >> i & 1 == 1 {
+            lo ^= a << i;
+            if i != 0 {
+                hi ^= a >> (64 - i);
+            }
+        }
+    }
+    (lo, hi)
+}
+
+fn mul_schoolbook(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut ret = vec![0; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            let (lo, hi) = clmul(x, y);
+            ret[i + j] ^= lo;
+            ret[i + j + 1] ^= hi;
+        }
+    }
+    ret
+}
+
+/// Untrimmed product of `a.len() + b.len()` limbs, by Karatsuba
+fn mul_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    if b.is_empty() {
+        return Vec::new();
+    }
+    if b.len() < KARATSUBA_THRESHOLD {
+        return mul_schoolbook(a, b);
+    }
+    let mut ret = vec![0; a.len() + b.len()];
+    if a.len() > b.len() {
+        // Unbalanced operands are multiplied in chunks the size of the shorter one
+        for (k, chunk) in a.chunks(b.len()).enumerate() {
+            xor_into(&mut ret, &mul_limbs(chunk, b), k * b.len());
+        }
+        return ret;
+    }
+    // (a0 + a1 X)(b0 + b1 X) = z0 + ((a0 + a1)(b0 + b1) - z0 - z2) X + z2 X^2
+    let half = a.len().div_ceil(2);
+    let (a0, a1) = a.split_at(half);
+    let (b0, b1) = b.split_at(half);
+    let z0 = mul_limbs(a0, b0);
+    let z2 = mul_limbs(a1, b1);
+    let mut a01 = a0.to_vec();
+    xor_into(&mut a01, a1, 0);
+    let mut b01 = b0.to_vec();
+    xor_into(&mut b01, b1, 0);
+    let mut z1 = mul_limbs(&a01, &b01);
+    xor_into(&mut z1, &z0, 0);
+    xor_into(&mut z1, &z2, 0);
+    xor_into(&mut ret, &z0, 0);
+    xor_into(&mut ret, &z1, half);
+    xor_into(&mut ret, &z2, 2 * half);
+    ret.truncate(a.len() + b.len());
+    ret
+}
+
+/// Squaring is linear over GF(2): it spreads the bits out, interleaving zeros
+fn square_limbs(a: &[u64]) -> Vec<u64> {
+    fn spread(mut x: u64) -> u64 {
+        x = (x | x << 16) & 0x0000_ffff_0000_ffff;
+        x = (x | x << 8) & 0x00ff_00ff_00ff_00ff;
+        x = (x | x << 4) & 0x0f0f_0f0f_0f0f_0f0f;
+        x = (x | x << 2) & 0x3333_3333_3333_3333;
+        (x | x << 1) & 0x5555_5555_5555_5555
+    }
+    let mut ret = Vec::with_capacity(2 * a.len());
+    for &limb in a {
+        ret.push(spread(limb & 0xffff_ffff));
+        ret.push(spread(limb >> 32));
+    }
+    trim(&mut ret);
+    ret
+}
+
+/// Reduces `rem` modulo `divisor` in place, returning the quotient
+fn div_rem_limbs(rem: &mut Vec<u64>, divisor: &[u64]) -> Vec<u64> {
+    let d = degree(divisor).expect("polynomial division by zero");
+    let divisor = &divisor[..=d / 64];
+    let mut quot = Vec::new();
+    while let Some(r) = degree(rem).filter(|&r| r >= d) {
+        let shift = r - d;
+        xor_shifted(rem, divisor, shift);
+        if quot.len() <= shift / 64 {
+            quot.resize(shift / 64 + 1, 0);
+        }
+        quot[shift / 64] ^= 1 << (shift % 64);
+    }
+    trim(rem);
+    quot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn xorshift(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    fn random_limbs(state: &mut u64, len: usize) -> Vec<u64> {
+        (0..len).map(|_| xorshift(state)).collect()
+    }
+
+    /// The coefficients, one per bit, without trailing zeros
+    fn bits(v: &[u64]) -> Vec<bool> {
+        let mut ret: Vec<bool> = (0..64 * v.len()).map(|e| bit(v, e)).collect();
+        while ret.last() == Some(&false) {
+            ret.pop();
+        }
+        ret
+    }
+
+    fn bit_product(a: &[bool], b: &[bool]) -> Vec<bool> {
+        let mut ret = vec![false; (a.len() + b.len()).saturating_sub(1)];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                ret[i + j] ^= x & y;
+            }
+        }
+        while ret.last() == Some(&false) {
+            ret.pop();
+        }
+        ret
+    }
+
+    /// `(quotient, remainder)` by long division one bit at a time
+    fn bit_div_rem(a: &[bool], b: &[bool]) -> (Vec<bool>, Vec<bool>) {
+        let mut rem = a.to_vec();
+        let mut quot = vec![false; a.len().saturating_sub(b.len()) + 1];
+        while rem.len() >= b.len() {
+            let shift = rem.len() - b.len();
+            quot[shift] = true;
+            for (j, &y) in b.iter().enumerate() {
+                rem[shift + j] ^= y;
+            }
+            while rem.last() == Some(&false) {
+                rem.pop();
+            }
+        }
+        while quot.last() == Some(&false) {
+            quot.pop();
+        }
+        (quot, rem)
+    }
+
+    #[test]
+    fn values_are_trimmed() {
+        let f2x = Gf2Poly::new_symb("x");
+        assert_eq!(f2x.el(vec![1, 0]), f2x.el(vec![1]));
+        assert_eq!(f2x.el(vec![0, 0]), f2x.el(Vec::new()));
+        assert_eq!(
+            f2x.el(vec![0b110, 0, 0]).to_string(),
+            "x^2 + x (poly x, gf2)"
+        );
+        assert_eq!(f2x.from_exponents([70, 70, 3]), f2x.el(vec![0b1000]));
+    }
+
+    #[test]
+    fn products_agree_with_bitwise_schoolbook() {
+        let mut state = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..200 {
+            let (x, y) = (xorshift(&mut state), xorshift(&mut state));
+            let (lo, hi) = clmul(x, y);
+            assert_eq!(bits(&[lo, hi]), bit_product(&bits(&[x]), &bits(&[y])));
+        }
+        // Around and above `KARATSUBA_THRESHOLD`, balanced and not
+        for (n, m) in [
+            (1, 1),
+            (3, 15),
+            (15, 16),
+            (16, 16),
+            (17, 17),
+            (33, 40),
+            (64, 20),
+            (70, 70),
+        ] {
+            let a = random_limbs(&mut state, n);
+            let b = random_limbs(&mut state, m);
+            let expected = bit_product(&bits(&a), &bits(&b));
+            assert_eq!(bits(&mul_limbs(&a, &b)), expected, "{n} {m}");
+            assert_eq!(bits(&mul_schoolbook(&a, &b)), expected, "{n} {m}");
+            assert_eq!(
+                bits(&square_limbs(&a)),
+                bit_product(&bits(&a), &bits(&a)),
+                "{n}"
+            );
+        }
+    }
+
+    #[test]
+    fn division_and_gcd_agree_with_bitwise_references() {
+        let f2x = Gf2Poly::new_symb("x");
+        let mut state = 0x9e37_79b9_7f4a_7c15;
+        for (n, m) in [(1, 1), (2, 1), (5, 3), (20, 17), (40, 2)] {
+            let a = random_limbs(&mut state, n);
+            let mut b = random_limbs(&mut state, m);
+            // A divisor of degree not a multiple of 64
+            b[m - 1] >>= 5;
+            let mut rem = a.clone();
+            let quot = div_rem_limbs(&mut rem, &b);
+            let (q, r) = bit_div_rem(&bits(&a), &bits(&b));
+            assert_eq!((bits(&quot), bits(&rem)), (q, r), "{n} {m}");
+            // gcd(a c, b c) = c for coprime cofactors, which a common factor leaves
+            let c = random_limbs(&mut state, 2);
+            let g = f2x.gcd(&f2x.el(mul_limbs(&a, &c)), &f2x.el(mul_limbs(&b, &c)));
+            let expected = f2x.gcd(&f2x.el(a.clone()), &f2x.el(b.clone()));
+            assert_eq!(g, f2x.el(mul_limbs(&expected.v, &c)), "{n} {m}");
+            for v in [&a, &b] {
+                let (_, r) = bit_div_rem(&bits(v), &bits(&expected.v));
+                assert!(r.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn irreducibility_agrees_with_trial_division() {
+        let f2x = Gf2Poly::new_symb("x");
+        let irreducible = |f: u64| {
+            let f = bits(&[f]);
+            let degree = |v: &[bool]| v.len() - 1;
+            (2..1u64 << (f.len() / 2 + 1))
+                .map(|d| bits(&[d]))
+                .filter(|d| 2 * degree(d) <= degree(&f))
+                .all(|d| !bit_div_rem(&f, &d).1.is_empty())
+        };
+        for f in 2..1 << 11 {
+            assert_eq!(
+                f2x.is_irreducible(&f2x.el(vec![f])),
+                irreducible(f),
+                "{f:b}"
+            );
+        }
+        // The AES polynomial, and polynomials across limbs
+        assert!(f2x.is_irreducible(&f2x.from_exponents([8, 4, 3, 1, 0])));
+        assert!(f2x.is_irreducible(&f2x.from_exponents([127, 1, 0])));
+        assert!(!f2x.is_irreducible(&f2x.from_exponents([128, 0])));
+    }
+}
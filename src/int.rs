@@ -1,5 +1,10 @@
-use crate::{SAdd, SFusedMulAdd, SMul, SRem, SSub, Structure};
-use rug::Complete;
+use crate::{
+    DensePolynomial, El, MulAlgorithm, SAdd, SDiv, SDivRem, SDivSmall, SFusedMulAdd, SGcd, SMul,
+    SPolynomialGcd, SRem, SSub, Structure,
+    dense_polynomial::{KRONECKER_THRESHOLD, MULTI_PRIME_NTT_THRESHOLD, NTT_THRESHOLD},
+    kronecker, mul_karatsuba, ntt,
+};
+use rug::{Complete, Integer, integer::IsPrime};
 use std::{borrow::Cow, fmt, ops::Deref};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -13,6 +18,22 @@ impl std::fmt::Display for Int {
     }
 }
 
+/// Structures whose values are integers, convertible to and from `rug::Integer`
+pub trait SInteger: SRem + SSub + SFusedMulAdd {
+    fn to_integer(&self, v: &Self::V) -> rug::Integer;
+    /// Panics if `v` is not representable
+    fn of_integer(&self, v: rug::Integer) -> Self::V;
+}
+/// Structures whose values are the integers modulo `integer_modulus`, or the integers for zero,
+/// so that polynomials over them can be multiplied through integer products
+pub trait SIntegerResidues: SFusedMulAdd {
+    fn integer_modulus(&self) -> rug::Integer;
+    /// The representative of `v` in `[0, m)` for the modulus `m`, or `v` itself for zero
+    fn to_residue(&self, v: &Self::V) -> rug::Integer;
+    /// The value represented by the integer `v`
+    fn of_residue(&self, v: rug::Integer) -> Self::V;
+}
+
 impl Structure for Int {
     type V = rug::Integer;
     fn fmt_v(&self, v: &Self::V, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
@@ -52,9 +73,258 @@ impl SRem for Int {
         let rhs = rhs.into_owned();
         Cow::Owned(lhs.div_rem_euc(rhs).1)
     }
+    /// Each coefficient reduced once, after a single number-theoretic transform from
+    /// `NTT_THRESHOLD` coefficients on when `m` is a prime with roots of unity of the product's
+    /// length, and `mul_coefficients_low` otherwise
+    fn mul_coefficients_rem(
+        &self,
+        lhs: &[Self::V],
+        rhs: &[Self::V],
+        len: usize,
+        m: &Self::V,
+    ) -> Vec<Self::V> {
+        let full = lhs.len() + rhs.len() - 1;
+        if usize::min(lhs.len(), rhs.len()) >= NTT_THRESHOLD && ntt::single_prime(m, full).is_some()
+        {
+            let mut ret = ntt::mul_residues(lhs, rhs, m);
+            ret.truncate(len);
+            return ret;
+        }
+        self.mul_coefficients_low(lhs, rhs, len)
+            .into_iter()
+            .map(|c| c.div_rem_euc_ref(m).complete().1)
+            .collect()
+    }
+}
+impl SDiv for Int {
+    fn div(&self, lhs: Cow<'_, Self::V>, rhs: Cow<'_, Self::V>) -> Cow<'_, Self::V> {
+        Cow::Owned(lhs.into_owned().div_exact(&rhs))
+    }
+}
+impl SDivRem for Int {
+    /// Returns nonnegative remainder
+    fn div_rem<'a>(
+        &'a self,
+        lhs: Cow<'a, Self::V>,
+        rhs: Cow<'a, Self::V>,
+    ) -> (Cow<'a, Self::V>, Cow<'a, Self::V>) {
+        let (q, r) = lhs.into_owned().div_rem_euc(rhs.into_owned());
+        (Cow::Owned(q), Cow::Owned(r))
+    }
+}
+impl SGcd for Int {
+    fn gcd(&self, lhs: Cow<'_, Self::V>, rhs: Cow<'_, Self::V>) -> Cow<'_, Self::V> {
+        Cow::Owned(lhs.gcd_ref(&rhs).complete())
+    }
+    fn unit_part(&self, v: &Self::V) -> Self::V {
+        Integer::from(if *v < 0 { -1 } else { 1 })
+    }
+    fn quotient_characteristic(&self, m: &Self::V) -> Integer {
+        m.clone().abs()
+    }
+    fn pth_root(&self, _v: &Self::V) -> Option<Self::V> {
+        None
+    }
+}
+/// By the modular algorithm
+impl SPolynomialGcd for Int {
+    fn polynomial_gcd<'a>(
+        poly: &'a DensePolynomial<Self>,
+        a: &El<'_, DensePolynomial<Self>>,
+        b: &El<'_, DensePolynomial<Self>>,
+    ) -> El<'a, DensePolynomial<Self>> {
+        poly.gcd_modular(a, b)
+    }
 }
 impl SFusedMulAdd for Int {
     fn fused_mul_add_ref(&self, acc: &mut Self::V, lhs: &Self::V, rhs: &Self::V) {
         *acc += lhs * rhs
     }
+    /// Through the algorithm `mul_algorithm` picks
+    fn mul_coefficients(&self, lhs: &[Self::V], rhs: &[Self::V]) -> Vec<Self::V> {
+        match mul_algorithm(lhs, rhs) {
+            MulAlgorithm::Ntt => ntt::mul_residues(lhs, rhs, &Integer::ZERO),
+            MulAlgorithm::Kronecker => kronecker::mul_residues(lhs, rhs, &Integer::ZERO),
+            _ => mul_karatsuba(self, lhs, rhs),
+        }
+    }
+}
+/// The algorithm for an integer product: a transform modulo one prime from
+/// `MULTI_PRIME_NTT_THRESHOLD` coefficients on when the product's coefficients need more than 48
+/// bits but fit below that prime, Kronecker substitution from `KRONECKER_THRESHOLD` coefficients
+/// on otherwise, and schoolbook multiplication, which `mul_karatsuba` is at these lengths, below
+fn mul_algorithm(lhs: &[Integer], rhs: &[Integer]) -> MulAlgorithm {
+    let short = usize::min(lhs.len(), rhs.len());
+    if short >= MULTI_PRIME_NTT_THRESHOLD {
+        let bits = |v: &[Integer]| v.iter().map(Integer::significant_bits).max().unwrap();
+        // As bounded in `ntt::mul_residues`, with a sign bit and a spare one
+        let needed = bits(lhs) + bits(rhs) + (usize::BITS - short.leading_zeros()) + 2;
+        if (49..=61).contains(&needed) {
+            return MulAlgorithm::Ntt;
+        }
+    }
+    if short >= KRONECKER_THRESHOLD {
+        MulAlgorithm::Kronecker
+    } else {
+        MulAlgorithm::Schoolbook
+    }
+}
+impl SIntegerResidues for Int {
+    fn integer_modulus(&self) -> Integer {
+        Integer::ZERO
+    }
+    fn to_residue(&self, v: &Self::V) -> Integer {
+        v.clone()
+    }
+    fn of_residue(&self, v: Integer) -> Self::V {
+        v
+    }
+}
+impl SDivSmall for Int {
+    fn div_small_exact(&self, v: &Self::V, k: u32) -> Option<Self::V> {
+        v.is_divisible_u(k)
+            .then(|| Integer::from(v.div_exact_u_ref(k)))
+    }
+}
+impl SInteger for Int {
+    fn to_integer(&self, v: &Self::V) -> rug::Integer {
+        v.clone()
+    }
+    fn of_integer(&self, v: rug::Integer) -> Self::V {
+        v
+    }
+}
+
+impl Int {
+    /// Prime factorization of a positive integer, as `(prime, multiplicity)` in increasing order
+    pub fn factor<'a>(&'a self, n: &El<'_, Self>) -> Vec<(El<'a, Self>, u32)> {
+        factorize(&n.v)
+            .into_iter()
+            .map(|(p, e)| (self.el(p), e))
+            .collect()
+    }
+}
+
+pub(crate) fn is_prime(n: &Integer) -> bool {
+    n.is_probably_prime(30) != IsPrime::No
+}
+
+/// Trial division by small primes, then Pollard–Brent rho on the cofactor
+pub(crate) fn factorize(n: &Integer) -> Vec<(Integer, u32)> {
+    assert!(*n > 0, "can only factor positive integers");
+    let mut n = n.clone();
+    let mut ret: Vec<(Integer, u32)> = Vec::new();
+    for p in (2u32..1000).filter(|&p| is_prime(&Integer::from(p))) {
+        if n.is_divisible_u(p) {
+            let (rest, e) = n.remove_factor(&Integer::from(p));
+            n = rest;
+            ret.push((Integer::from(p), e));
+        }
+    }
+    let mut stack = vec![n];
+    let mut large = Vec::new();
+    while let Some(n) = stack.pop() {
+        if n == 1 {
+            continue;
+        }
+        if is_prime(&n) {
+            large.push(n);
+        } else {
+            let d = pollard_brent(&n);
+            stack.push((&n / &d).complete());
+            stack.push(d);
+        }
+    }
+    large.sort();
+    for p in large {
+        match ret.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => ret.push((p, 1)),
+        }
+    }
+    ret
+}
+
+/// A nontrivial factor of the composite `n`
+fn pollard_brent(n: &Integer) -> Integer {
+    let root = n.clone().sqrt();
+    if root.clone().square() == *n {
+        return root;
+    }
+    for c in 1u32.. {
+        let f = |x: &Integer| (x.clone().square() + c) % n;
+        let (mut x, mut y, mut ys) = (Integer::from(2), Integer::from(2), Integer::new());
+        let mut q = Integer::from(1);
+        let mut g = Integer::from(1);
+        let mut r = 1u64;
+        while g == 1 {
+            x = y.clone();
+            for _ in 0..r {
+                y = f(&y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y.clone();
+                for _ in 0..u64::min(128, r - k) {
+                    y = f(&y);
+                    q = q * (&x - &y).complete().abs() % n;
+                }
+                g = q.gcd_ref(n).complete();
+                k += 128;
+            }
+            r *= 2;
+        }
+        if g == *n {
+            // The batched gcd overshot; backtrack one step at a time
+            loop {
+                ys = f(&ys);
+                g = (&x - &ys).complete().abs().gcd(n);
+                if g != 1 {
+                    break;
+                }
+            }
+        }
+        if g != *n {
+            return g;
+        }
+    }
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `len` deterministic integers of `bits` bits, alternating in sign
+    fn coefficients(len: usize, bits: u32) -> Vec<Integer> {
+        (0..len)
+            .map(|i| {
+                let c = (Integer::from(1) << (bits - 1)) + i * 7919 % (1 << (bits - 1));
+                if i % 2 == 0 { c } else { -c }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn automatic_multiplication_algorithm() {
+        let cases = [
+            (10, 20, MulAlgorithm::Schoolbook),
+            (1000, 20, MulAlgorithm::Kronecker),
+            // 20 + 20 + 14 + 2 bits, one transform prime
+            (8192, 20, MulAlgorithm::Ntt),
+            // Too few bits for the transform to pay off
+            (8192, 8, MulAlgorithm::Kronecker),
+            // Too many for a single prime
+            (8192, 32, MulAlgorithm::Kronecker),
+        ];
+        for (len, bits, expected) in cases {
+            let v = coefficients(len, bits);
+            assert_eq!(mul_algorithm(&v, &v), expected, "{len} {bits}");
+        }
+        let zx = DensePolynomial::new_symb("x", &Int);
+        let a = zx.el(coefficients(8192, 20));
+        let b = zx.el(coefficients(8200, 21)[8..].to_vec());
+        let expected = zx.mul_with(&a, &b, MulAlgorithm::Kronecker);
+        assert_eq!(a * b, expected);
+    }
 }
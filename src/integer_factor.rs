@@ -0,0 +1,673 @@
+use crate::{
+    DensePolynomial, El, Int, Mod, SAdd, SMul, SSub, Structure, SuperStructure, euclid::xgcd,
+    lll::lll, square_free::Factorization,
+};
+use rug::{Complete, Integer, ops::Pow, rand::RandState};
+use std::{borrow::Cow, mem};
+
+/// How modular factors are combined into factors over the integers
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Recombination {
+    Auto,
+    Zassenhaus,
+    VanHoeij,
+}
+
+impl DensePolynomial<Int> {
+    /// `(c, [(f_1, e_1), (f_2, e_2), ...])` with `p = c f_1^e_1 f_2^e_2 ...` and the `f_i`
+    /// distinct irreducible primitive polynomials with positive leading coefficients, ordered by
+    /// degree and then by coefficients from the top. Each square-free part is factored modulo a
+    /// prime, Hensel lifted, and recombined by subset search when there are few modular factors
+    /// and by van Hoeij's lattice reduction otherwise.
+    pub fn factor<'a>(&'a self, p: &El<'_, Self>) -> Factorization<'a, Int> {
+        self.factor_square_free_parts(p, |f| self.factor_square_free(f, Recombination::Auto))
+    }
+    /// `factor` recombining by Zassenhaus' search over subsets of the modular factors, in time
+    /// exponential in their number
+    pub fn factor_zassenhaus<'a>(&'a self, p: &El<'_, Self>) -> Factorization<'a, Int> {
+        self.factor_square_free_parts(p, |f| self.factor_square_free(f, Recombination::Zassenhaus))
+    }
+    /// `factor` recombining by van Hoeij's algorithm: the sums of the logarithmic derivatives
+    /// `f g_i' / g_i` of the modular factors over the true factors have small coefficients, so
+    /// lattice reduction finds the 0-1 vectors of the subsets. Falls back to subset search if
+    /// the lattice has not revealed them once all coefficients are used.
+    pub fn factor_van_hoeij<'a>(&'a self, p: &El<'_, Self>) -> Factorization<'a, Int> {
+        self.factor_square_free_parts(p, |f| self.factor_square_free(f, Recombination::VanHoeij))
+    }
+    /// The monic `g_1, ..., g_r` modulo `p^k`, with coefficients in `[0, p^k)`, for which
+    /// `f = lc(f) g_1 ... g_r` modulo `p^k` and each `g_i` reduces to `factors[i]`, the pairwise
+    /// coprime monic factors of `f` modulo the prime `p`. Lifts quadratically along a balanced
+    /// tree of products.
+    pub fn hensel_lift<'a>(
+        &'a self,
+        f: &El<'_, Self>,
+        factors: &[El<'_, DensePolynomial<Mod<Int, true>>>],
+        k: u32,
+    ) -> Vec<El<'a, Self>> {
+        assert_eq!(self, f.s);
+        assert!(k >= 1, "hensel lifting to p^0");
+        let p = factors[0].s.inner().modulus().v.into_owned();
+        let m = p.clone().pow(k);
+        let lc_inv = self.leading_coefficient(f).v.into_owned().invert(&m);
+        let lc_inv = lc_inv.expect("leading coefficient divisible by p");
+        let monic =
+            f.v.iter()
+                .map(|c| reduce((c * &lc_inv).complete(), &m))
+                .collect();
+        let factors: Vec<_> = factors.iter().map(|g| (*g.v).clone()).collect();
+        let mut ret = Vec::new();
+        lift_tree(monic, &factors, &p, &m, &mut ret);
+        ret.into_iter().map(|g| self.el(g)).collect()
+    }
+    /// The irreducible factors of the primitive square-free `f` with positive leading coefficient
+    fn factor_square_free<'a>(
+        &'a self,
+        f: &El<'a, Self>,
+        method: Recombination,
+    ) -> Vec<El<'a, Self>> {
+        let n = self.degree(f).unwrap();
+        if n <= 1 {
+            return vec![self.el((*f.v).clone())];
+        }
+        let (p, modular) = choose_prime(&f.v);
+        let r = modular.len();
+        if r == 1 {
+            return vec![self.el((*f.v).clone())];
+        }
+        let lc = f.v.last().unwrap();
+        let norm = Integer::from(
+            f.v.iter()
+                .map(|c| c.square_ref())
+                .sum::<Integer>()
+                .sqrt_ref(),
+        ) + 1u32;
+        // Factors of lc f with leading coefficient lc have coefficients below lc 2^n |f|_2, by
+        // Mignotte's bound
+        let mignotte = Integer::from(lc * &norm) << u32::try_from(n).unwrap();
+        let mut k = exponent_above(&p, &(mignotte * 2u32));
+        let van_hoeij = match method {
+            Recombination::Auto => r > ZASSENHAUS_MAX_FACTORS,
+            Recombination::Zassenhaus => false,
+            Recombination::VanHoeij => true,
+        };
+        // The coefficients of f g' / g for a factor g are below n 2^n |f|_2^2, by the same bound
+        // on g and f / g
+        let cld = (norm.square() * n) << u32::try_from(n).unwrap();
+        let cld_exponent = exponent_above(&p, &cld);
+        if van_hoeij {
+            let extra = exponent_above(
+                &p,
+                &(Integer::from(1) << (VAN_HOEIJ_EXTRA_BITS + 2 * r as u32)),
+            );
+            k = u32::max(k, cld_exponent + extra);
+        }
+        let field = Mod::new_prime(Int.el(p.clone()));
+        let poly = DensePolynomial::new_symb("x", &field);
+        let modular: Vec<_> = modular.into_iter().map(|g| poly.el(g)).collect();
+        let lifted: Vec<_> = self
+            .hensel_lift(f, &modular, k)
+            .into_iter()
+            .map(|g| g.v.into_owned())
+            .collect();
+        let pk = p.clone().pow(k);
+        if van_hoeij {
+            let d = p.pow(cld_exponent);
+            if let Some(factors) = self.van_hoeij(f, &lifted, &pk, &d) {
+                return factors;
+            }
+        }
+        self.zassenhaus(f, lifted, &pk)
+    }
+    /// Recombination by trying the products of subsets of the `lifted` factors modulo `pk` in
+    /// increasing size, removing each subset that gives a factor
+    fn zassenhaus<'a>(
+        &'a self,
+        f: &El<'_, Self>,
+        mut lifted: Vec<Vec<Integer>>,
+        pk: &Integer,
+    ) -> Vec<El<'a, Self>> {
+        let mut f = (*f.v).clone();
+        let mut ret = Vec::new();
+        let mut size = 1;
+        'sizes: while 2 * size <= lifted.len() {
+            let mut subset: Vec<usize> = (0..size).collect();
+            loop {
+                let parts: Vec<_> = subset.iter().map(|&i| &lifted[i]).collect();
+                if let Some((g, q)) = self.try_candidate(&f, &parts, pk) {
+                    ret.push(self.el(g));
+                    f = q;
+                    for &i in subset.iter().rev() {
+                        lifted.remove(i);
+                    }
+                    continue 'sizes;
+                }
+                if !next_subset(&mut subset, lifted.len()) {
+                    break;
+                }
+            }
+            size += 1;
+        }
+        ret.push(self.el(f));
+        ret
+    }
+    /// Recombination by van Hoeij's algorithm, adding coefficients of the logarithmic
+    /// derivatives a batch at a time, each scaled down by `d`, an upper bound on those of the
+    /// true factors. `None` if the lattice has not split into the true factors by the end.
+    fn van_hoeij<'a>(
+        &'a self,
+        f: &El<'_, Self>,
+        lifted: &[Vec<Integer>],
+        pk: &Integer,
+        d: &Integer,
+    ) -> Option<Vec<El<'a, Self>>> {
+        let (n, r) = (self.degree(f).unwrap(), lifted.len());
+        let ring = Mod::new(Int.el(pk.clone()));
+        let poly = DensePolynomial::new_symb("x", &ring);
+        let fm = poly.el(f
+            .v
+            .iter()
+            .map(|c| reduce(c.clone(), pk))
+            .collect::<Vec<_>>());
+        let pd = (pk / d).complete();
+        // Row i holds the coefficients of f g_i' / g_i modulo pk, divided by d and rounded
+        let derivatives: Vec<Vec<Integer>> = lifted
+            .iter()
+            .map(|g| {
+                let g = poly.el(g.clone());
+                let num = fm.copy() * poly.derivative(&g);
+                let (q, _) = poly.div_rem_monic(&num, &g);
+                let mut q = q.v.into_owned();
+                q.resize(n, Integer::ZERO);
+                q.into_iter()
+                    .map(|c| (c * 2u32 + d).div_rem_floor(Integer::from(d * 2u32)).0)
+                    .collect()
+            })
+            .collect();
+        // Low and high coefficients alternately
+        let columns: Vec<usize> = (0..n)
+            .map(|j| if j % 2 == 0 { j / 2 } else { n - 1 - j / 2 })
+            .collect();
+        // The lattice vectors kept so far: a 0-1 vector of modular factors followed by the
+        // entries for the coefficients used so far
+        let mut w: Vec<Vec<Integer>> = (0..r)
+            .map(|i| (0..r).map(|j| Integer::from(u32::from(i == j))).collect())
+            .collect();
+        let mut used = 0;
+        for batch in columns.chunks(VAN_HOEIJ_BATCH) {
+            let width = r + used + batch.len();
+            let mut basis: Vec<Vec<Integer>> = w
+                .iter()
+                .map(|row| {
+                    let mut v = row.clone();
+                    for &j in batch {
+                        let sum: Integer = Iterator::zip(row[..r].iter(), &derivatives)
+                            .map(|(a, c)| (a * &c[j]).complete())
+                            .sum();
+                        v.push(symmetric(reduce(sum, &pd), &pd));
+                    }
+                    v
+                })
+                .collect();
+            for k in 0..batch.len() {
+                let mut v = vec![Integer::ZERO; width];
+                v[r + used + k] = pd.clone();
+                basis.push(v);
+            }
+            let dets = lll(&mut basis);
+            used += batch.len();
+            // A 0-1 vector of a factor has its other entries below 1 + r / 2, so the vectors
+            // after the first with a larger Gram–Schmidt norm are not needed for it
+            let bound = r + used * (1 + r.div_ceil(2)).pow(2);
+            let mut kept = basis.len();
+            while kept > 0 && dets[kept] > Integer::from(&dets[kept - 1] * bound) {
+                kept -= 1;
+            }
+            basis.truncate(kept);
+            w = basis;
+            if w.len() <= 1 {
+                return Some(vec![self.el((*f.v).clone())]);
+            }
+            // The row space is that of the 0-1 vectors of a partition exactly when the columns
+            // of equal vectors form as many classes as there are rows
+            let mut classes: Vec<Vec<usize>> = Vec::new();
+            for i in 0..r {
+                match classes
+                    .iter_mut()
+                    .find(|c| w.iter().all(|row| row[c[0]] == row[i]))
+                {
+                    Some(c) => c.push(i),
+                    None => classes.push(vec![i]),
+                }
+            }
+            if classes.len() != w.len() {
+                continue;
+            }
+            let mut rest = (*f.v).clone();
+            let mut factors = Vec::new();
+            for class in &classes {
+                let parts: Vec<_> = class.iter().map(|&i| &lifted[i]).collect();
+                let Some((g, q)) = self.try_candidate(&rest, &parts, pk) else {
+                    break;
+                };
+                factors.push(self.el(g));
+                rest = q;
+            }
+            if factors.len() == classes.len() {
+                return Some(factors);
+            }
+        }
+        None
+    }
+    /// `(g, f / g)` for `g` the primitive part of `lc(f)` times the product of `parts` modulo
+    /// `pk`, in the symmetric range, if it divides `f`
+    fn try_candidate(
+        &self,
+        f: &[Integer],
+        parts: &[&Vec<Integer>],
+        pk: &Integer,
+    ) -> Option<(Vec<Integer>, Vec<Integer>)> {
+        let lc = f.last().unwrap();
+        // The constant term of a factor divides that of lc f
+        let constant = parts
+            .iter()
+            .fold(lc.clone(), |acc, g| reduce(acc * &g[0], pk));
+        let constant = symmetric(constant, pk);
+        if f[0] != 0 && (constant == 0 || !(lc * &f[0]).complete().is_divisible(&constant)) {
+            return None;
+        }
+        let ring = Mod::new(Int.el(pk.clone()));
+        let poly = DensePolynomial::new_symb("x", &ring);
+        let mut g = vec![lc.clone()];
+        for part in parts {
+            g = poly.mul(Cow::Owned(g), Cow::Borrowed(part)).into_owned();
+        }
+        let g = self.el(g.into_iter().map(|c| symmetric(c, pk)).collect::<Vec<_>>());
+        let g = self.primitive_part(&g).v.into_owned();
+        let q = try_div(f, &g)?;
+        Some((g, q))
+    }
+}
+
+/// Up to this many modular factors, `factor` searches subsets rather than reduce lattices
+const ZASSENHAUS_MAX_FACTORS: usize = 8;
+/// Coefficients of the logarithmic derivatives added to the lattice at a time
+const VAN_HOEIJ_BATCH: usize = 4;
+/// Van Hoeij's algorithm lifts to this many bits, plus two per modular factor, above the
+/// coefficient bound, for the lattice reduction to separate the true factors
+const VAN_HOEIJ_EXTRA_BITS: u32 = 64;
+/// The prime for the modular factorization is the one giving the fewest factors among this many
+const PRIME_CANDIDATES: usize = 5;
+
+/// A prime `p` not dividing the leading coefficient of the square-free `f` and modulo which `f`
+/// stays square-free, and the monic factors of `f` modulo `p`
+fn choose_prime(f: &[Integer]) -> (Integer, Vec<Vec<Integer>>) {
+    // The factorizations are deterministic up to order, which `factor` fixes
+    let mut rng = RandState::new();
+    let mut best: Option<(Integer, Vec<Vec<Integer>>)> = None;
+    let mut tried = 0;
+    let mut p = Integer::from(1);
+    while tried < PRIME_CANDIDATES {
+        p.next_prime_mut();
+        if f.last().unwrap().is_divisible(&p) {
+            continue;
+        }
+        let field = Mod::new_prime(Int.el(p.clone()));
+        let poly = DensePolynomial::new_symb("x", &field);
+        let fp = poly.el(f.iter().map(|c| reduce(c.clone(), &p)).collect::<Vec<_>>());
+        if poly.degree(&fp.gcd(&poly.derivative(&fp))) != Some(0) {
+            continue;
+        }
+        tried += 1;
+        let (_, factors) = poly.factor(&fp, &mut rng);
+        if best.as_ref().is_none_or(|(_, b)| factors.len() < b.len()) {
+            let factors = factors.into_iter().map(|(g, _)| g.v.into_owned()).collect();
+            best = Some((p.clone(), factors));
+        }
+        if best.as_ref().is_some_and(|(_, b)| b.len() == 1) {
+            break;
+        }
+    }
+    best.unwrap()
+}
+
+/// Hensel lifts the monic `f` modulo `m` along the tree with the monic `factors` modulo `p` at its
+/// leaves, pushing the lifted leaves to `out`
+fn lift_tree(
+    f: Vec<Integer>,
+    factors: &[Vec<Integer>],
+    p: &Integer,
+    m: &Integer,
+    out: &mut Vec<Vec<Integer>>,
+) {
+    if factors.len() == 1 {
+        out.push(f);
+        return;
+    }
+    let field = Mod::new_prime(Int.el(p.clone()));
+    let poly = DensePolynomial::new_symb("x", &field);
+    let (left, right) = factors.split_at(factors.len() / 2);
+    let product = |part: &[Vec<Integer>]| {
+        part.iter().fold(poly.one().into_owned(), |acc, g| {
+            poly.mul(Cow::Owned(acc), Cow::Borrowed(g)).into_owned()
+        })
+    };
+    let (g, h) = lift_pair(f, product(left), product(right), p, m);
+    lift_tree(g, left, p, m, out);
+    lift_tree(h, right, p, m, out);
+}
+
+/// The monic `g`, `h` modulo `m` with `f = g h` reducing to `g0`, `h0` modulo `p`, by quadratic
+/// Hensel steps, which also lift the Bézout coefficients
+fn lift_pair(
+    f: Vec<Integer>,
+    g0: Vec<Integer>,
+    h0: Vec<Integer>,
+    p: &Integer,
+    m: &Integer,
+) -> (Vec<Integer>, Vec<Integer>) {
+    let field = Mod::new_prime(Int.el(p.clone()));
+    let poly = DensePolynomial::new_symb("x", &field);
+    let (one, mut s, mut t) = xgcd(&poly, g0.clone(), h0.clone());
+    for c in [&mut s, &mut t] {
+        *c = poly.div_coeffs(c, &one[0]).v.into_owned();
+    }
+    let (mut g, mut h) = (g0, h0);
+    let mut modulus = p.clone();
+    while modulus < *m {
+        modulus = Integer::min(modulus.square(), m.clone());
+        let ring = Mod::new(Int.el(modulus.clone()));
+        let poly = DensePolynomial::new_symb("x", &ring);
+        let (add, sub, mul) = (
+            |a: &Vec<Integer>, b: &Vec<Integer>| {
+                poly.add(Cow::Borrowed(a), Cow::Borrowed(b)).into_owned()
+            },
+            |a: &Vec<Integer>, b: &Vec<Integer>| {
+                poly.sub(Cow::Borrowed(a), Cow::Borrowed(b)).into_owned()
+            },
+            |a: &Vec<Integer>, b: &Vec<Integer>| {
+                poly.mul(Cow::Borrowed(a), Cow::Borrowed(b)).into_owned()
+            },
+        );
+        let divide = |a: Vec<Integer>, b: &Vec<Integer>| {
+            let (q, r) = poly.div_rem_monic(&poly.el(a), &poly.el(b.clone()));
+            (q.v.into_owned(), r.v.into_owned())
+        };
+        let reduced = |v: &Vec<Integer>| {
+            poly.el(v
+                .iter()
+                .map(|c| reduce(c.clone(), &modulus))
+                .collect::<Vec<_>>())
+                .v
+                .into_owned()
+        };
+        let (f, g0, h0, s0, t0) = (
+            reduced(&f),
+            reduced(&g),
+            reduced(&h),
+            reduced(&s),
+            reduced(&t),
+        );
+        // With e = f - g h, q and r the quotient and remainder of s e by h: g + t e + q g and
+        // h + r
+        let e = sub(&f, &mul(&g0, &h0));
+        let (q, r) = divide(mul(&s0, &e), &h0);
+        g = add(&add(&g0, &mul(&t0, &e)), &mul(&q, &g0));
+        h = add(&h0, &r);
+        // With b = s g + t h - 1, c and d the quotient and remainder of s b by h: s - d and
+        // t - t b - c g
+        let b = sub(&add(&mul(&s0, &g), &mul(&t0, &h)), &poly.one());
+        let (c, d) = divide(mul(&s0, &b), &h);
+        s = sub(&s0, &d);
+        t = sub(&sub(&t0, &mul(&t0, &b)), &mul(&c, &g));
+    }
+    (g, h)
+}
+
+/// The next subset of `0..n` of the same size in lexicographic order, or `false` after the last
+fn next_subset(subset: &mut [usize], n: usize) -> bool {
+    let k = subset.len();
+    let Some(i) = (0..k).rev().find(|&i| subset[i] < n - k + i) else {
+        return false;
+    };
+    subset[i] += 1;
+    for j in i + 1..k {
+        subset[j] = subset[j - 1] + 1;
+    }
+    true
+}
+
+/// `a / b` if `b` divides `a` over the integers
+fn try_div(a: &[Integer], b: &[Integer]) -> Option<Vec<Integer>> {
+    let lc = b.last().unwrap();
+    let mut rem = a.to_vec();
+    let mut quot = vec![Integer::ZERO; (a.len() + 1).checked_sub(b.len())?];
+    for i in (0..quot.len()).rev() {
+        let top = mem::take(&mut rem[i + b.len() - 1]);
+        if !top.is_divisible(lc) {
+            return None;
+        }
+        let c = top.div_exact(lc);
+        for (j, d) in b.iter().enumerate().take(b.len() - 1) {
+            rem[i + j] -= &c * d;
+        }
+        quot[i] = c;
+    }
+    rem.iter().all(|c| *c == 0).then_some(quot)
+}
+
+/// The least `k` with `p^k > bound`
+fn exponent_above(p: &Integer, bound: &Integer) -> u32 {
+    let mut power = p.clone();
+    let mut k = 1;
+    while power <= *bound {
+        power *= p;
+        k += 1;
+    }
+    k
+}
+
+/// The representative of `x` in `[0, m)`
+fn reduce(x: Integer, m: &Integer) -> Integer {
+    x.div_rem_euc_ref(m).complete().1
+}
+
+/// The representative of `x` in `[0, m)` in `(-m/2, m/2]`
+fn symmetric(x: Integer, m: &Integer) -> Integer {
+    if (x.clone() << 1u32) > *m { x - m } else { x }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ints(v: &[i64]) -> Vec<Integer> {
+        v.iter().map(|&c| Integer::from(c)).collect()
+    }
+
+    /// The product over all signs of `x ± sqrt(a_1) ± ... ± sqrt(a_k)`, irreducible for
+    /// distinct primes `a_i` but with factors of degree at most 2 modulo every prime. Each step
+    /// writes `q(x + sqrt(a)) = u + v sqrt(a)` and takes `q(x + sqrt(a)) q(x - sqrt(a))`.
+    fn swinnerton_dyer<'a>(
+        zx: &'a DensePolynomial<Int>,
+        primes: &[i64],
+    ) -> El<'a, DensePolynomial<Int>> {
+        let (add, mul) = (
+            |a: &Vec<Integer>, b: &Vec<Integer>| {
+                zx.add(Cow::Borrowed(a), Cow::Borrowed(b)).into_owned()
+            },
+            |a: &Vec<Integer>, b: &Vec<Integer>| {
+                zx.mul(Cow::Borrowed(a), Cow::Borrowed(b)).into_owned()
+            },
+        );
+        let x = ints(&[0, 1]);
+        let mut q = x.clone();
+        for &a in primes {
+            let (mut u, mut v) = (Vec::new(), Vec::new());
+            for c in q.iter().rev() {
+                // (u + v s)(x + s) + c with s^2 = a
+                (u, v) = (
+                    add(&add(&mul(&u, &x), &mul(&v, &ints(&[a]))), &vec![c.clone()]),
+                    add(&mul(&v, &x), &u),
+                );
+            }
+            q = zx
+                .sub(
+                    Cow::Owned(mul(&u, &u)),
+                    Cow::Owned(mul(&ints(&[a]), &mul(&v, &v))),
+                )
+                .into_owned();
+        }
+        zx.el(q)
+    }
+
+    /// The `n`-th cyclotomic polynomial, `x^n - 1` divided by those of the proper divisors of `n`
+    fn cyclotomic<'a>(zx: &'a DensePolynomial<Int>, n: usize) -> El<'a, DensePolynomial<Int>> {
+        let mut p = vec![Integer::ZERO; n + 1];
+        p[0] = Integer::from(-1);
+        p[n] = Integer::from(1);
+        let mut p = zx.el(p);
+        for d in (1..n).filter(|&d| n.is_multiple_of(d)) {
+            p = zx.quo(&p, &cyclotomic(zx, d));
+        }
+        p
+    }
+
+    fn product<'a>(
+        zx: &'a DensePolynomial<Int>,
+        (c, factors): &Factorization<'a, Int>,
+    ) -> El<'a, DensePolynomial<Int>> {
+        factors
+            .iter()
+            .fold(zx.el(vec![(*c.v).clone()]), |acc, (f, e)| {
+                acc * f.pow(&Integer::from(*e))
+            })
+    }
+
+    #[test]
+    fn swinnerton_dyer_polynomials_are_irreducible() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        let f = swinnerton_dyer(&zx, &[2, 3, 5]);
+        assert_eq!(f, zx.el(ints(&[576, 0, -960, 0, 352, 0, -40, 0, 1])));
+        let f = swinnerton_dyer(&zx, &[2, 3, 5, 7]);
+        let (_, modular) = choose_prime(&f.v);
+        // Degree at most 2 each
+        assert!(modular.len() >= 8);
+        for (c, factors) in [
+            zx.factor(&f),
+            zx.factor_van_hoeij(&f),
+            zx.factor_zassenhaus(&f),
+        ] {
+            assert_eq!(*c.v, 1);
+            assert_eq!(factors, vec![(f.copy(), 1)]);
+        }
+    }
+
+    #[test]
+    fn van_hoeij_lattice_proves_irreducibility() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        let f = swinnerton_dyer(&zx, &[2, 3, 5, 7]);
+        let (p, modular) = choose_prime(&f.v);
+        let field = Mod::new_prime(Int.el(p.clone()));
+        let poly = DensePolynomial::new_symb("x", &field);
+        let modular: Vec<_> = modular.into_iter().map(|g| poly.el(g)).collect();
+        let n = zx.degree(&f).unwrap();
+        let norm = Integer::from(
+            f.v.iter()
+                .map(|c| c.square_ref())
+                .sum::<Integer>()
+                .sqrt_ref(),
+        ) + 1u32;
+        let cld_exponent = exponent_above(&p, &((norm.square() * n) << n as u32));
+        let k = cld_exponent + exponent_above(&p, &(Integer::from(1) << 128u32));
+        let lifted: Vec<_> = zx
+            .hensel_lift(&f, &modular, k)
+            .into_iter()
+            .map(|g| g.v.into_owned())
+            .collect();
+        let pk = p.clone().pow(k);
+        let factors = zx.van_hoeij(&f, &lifted, &pk, &p.pow(cld_exponent));
+        assert_eq!(factors, Some(vec![f]));
+    }
+
+    #[test]
+    fn swinnerton_dyer_products() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        let f = swinnerton_dyer(&zx, &[2, 3, 5]);
+        let g = swinnerton_dyer(&zx, &[2, 3, 7]);
+        let linear = zx.el(ints(&[-1, 2]));
+        let p = f.copy() * g.copy() * linear.copy();
+        // Enough modular factors for `factor` to reduce lattices
+        let (_, modular) = choose_prime(&p.v);
+        assert!(modular.len() > ZASSENHAUS_MAX_FACTORS);
+        let expected = vec![(linear.copy(), 1), (g.copy(), 1), (f.copy(), 1)];
+        for (c, factors) in [zx.factor(&p), zx.factor_van_hoeij(&p)] {
+            assert_eq!(*c.v, 1);
+            assert_eq!(factors, expected);
+        }
+    }
+
+    #[test]
+    fn cyclotomic_products() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        for n in [12, 30, 36, 60] {
+            let mut p = vec![Integer::ZERO; n + 1];
+            p[0] = Integer::from(-1);
+            p[n] = Integer::from(1);
+            let p = zx.el(p);
+            let (c, factors) = zx.factor(&p);
+            assert_eq!(*c.v, 1);
+            let mut expected: Vec<_> = (1..=n)
+                .filter(|&d| n.is_multiple_of(d))
+                .map(|d| (cyclotomic(&zx, d), 1))
+                .collect();
+            expected.sort_by(|(a, _), (b, _)| {
+                Ord::cmp(&a.v.len(), &b.v.len())
+                    .then_with(|| a.v.iter().rev().cmp(b.v.iter().rev()))
+            });
+            assert_eq!(factors, expected, "x^{n} - 1");
+        }
+        // Repeated cyclotomic factors
+        let (f, g) = (cyclotomic(&zx, 6), cyclotomic(&zx, 15));
+        let p = f.pow(&Integer::from(3)) * g.pow(&Integer::from(2)) * zx.el(ints(&[-6]));
+        let (c, factors) = zx.factor(&p);
+        assert_eq!(*c.v, -6);
+        assert_eq!(
+            factors,
+            vec![(cyclotomic(&zx, 6), 3), (cyclotomic(&zx, 15), 2)]
+        );
+    }
+
+    #[test]
+    fn recombination_methods_agree_and_reconstruct() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        let mut rng = RandState::new();
+        let mut random = |len: u32| {
+            let len = 1 + Integer::from(Integer::random_below_ref(&Integer::from(len), &mut rng))
+                .to_usize()
+                .unwrap();
+            let mut v: Vec<Integer> = (0..len)
+                .map(|_| {
+                    Integer::from(Integer::random_below_ref(&Integer::from(21), &mut rng)) - 10
+                })
+                .collect();
+            v.push(Integer::from(Integer::random_below_ref(&Integer::from(3), &mut rng)) + 1);
+            zx.el(v)
+        };
+        for i in 0..30 {
+            let mut p = random(5) * random(5) * random(3);
+            if i % 3 == 0 {
+                let square = p.copy() * p.copy() * random(2);
+                p = zx.el(square.v.into_owned());
+            }
+            let zassenhaus = zx.factor_zassenhaus(&p);
+            let van_hoeij = zx.factor_van_hoeij(&p);
+            assert_eq!(zassenhaus, van_hoeij, "{p}");
+            assert_eq!(zx.factor(&p), zassenhaus, "{p}");
+            assert_eq!(product(&zx, &zassenhaus), p);
+            for (f, _) in &zassenhaus.1 {
+                assert!(*f.v.last().unwrap() > 0);
+                assert_eq!(*zx.content(f).v, 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use crate::{
+    DensePolynomial, El, SAdd, SField, SFusedMulAdd, SMul, Structure, SuperStructure,
+    euclid::xgcd_until, evaluation::SubproductTree,
+};
+use std::borrow::Cow;
+
+impl<S: SFusedMulAdd + SField> DensePolynomial<S> {
+    /// The polynomial of least degree taking the values `ys` at the distinct points `xs`, by
+    /// subproduct trees for many points and Lagrange's formula for few
+    pub fn interpolate<'a>(&'a self, xs: &[El<'_, S>], ys: &[El<'_, S>]) -> El<'a, Self> {
+        assert_eq!(xs.len(), ys.len(), "as many values as points are needed");
+        if xs.len() < INTERPOLATION_THRESHOLD {
+            return self.interpolate_lagrange(xs, ys);
+        }
+        let tree = SubproductTree::new(self, xs);
+        // m'(x_i) is the product of the x_i - x_j over the other points
+        let weights = tree.remainders(&self.derivative(&self.el(tree.root().clone())).v);
+        let c = Iterator::zip(weights.into_iter(), ys)
+            .map(|(w, y)| {
+                let w = w
+                    .into_iter()
+                    .next()
+                    .expect("interpolation points must be distinct");
+                vec![
+                    self.inner()
+                        .div(Cow::Borrowed(&*y.v), Cow::Owned(w))
+                        .into_owned(),
+                ]
+            })
+            .collect();
+        self.el(tree.linear_combination(c))
+    }
+    /// `interpolate` by Lagrange's formula, with quadratically many operations
+    pub fn interpolate_lagrange<'a>(&'a self, xs: &[El<'_, S>], ys: &[El<'_, S>]) -> El<'a, Self> {
+        assert_eq!(xs.len(), ys.len(), "as many values as points are needed");
+        let s = self.inner();
+        let mut m = self.one().into_owned();
+        for x in xs {
+            assert_eq!(s, x.s);
+            m = self
+                .mul(Cow::Owned(m), Cow::Owned(self.x_minus(x)))
+                .into_owned();
+        }
+        let mut ret = vec![s.zero().into_owned(); xs.len()];
+        for (x, y) in Iterator::zip(xs.iter(), ys) {
+            // m / (x - x_i) by synthetic division, and its value at x_i
+            let mut quot = vec![S::V::default(); xs.len()];
+            let mut acc = s.zero().into_owned();
+            for (k, c) in m.iter().enumerate().skip(1).rev() {
+                acc = s.mul(Cow::Owned(acc), Cow::Borrowed(&*x.v)).into_owned();
+                acc = s.add(Cow::Owned(acc), Cow::Borrowed(c)).into_owned();
+                quot[k - 1] = acc.clone();
+            }
+            let weight = self.evaluate(&self.el(quot.clone()), x);
+            assert!(
+                *weight.v != *s.zero(),
+                "interpolation points must be distinct"
+            );
+            let c = s.div(Cow::Borrowed(&*y.v), weight.v);
+            for (r, q) in Iterator::zip(ret.iter_mut(), &quot) {
+                s.fused_mul_add_ref(r, &c, q);
+            }
+        }
+        self.el(ret)
+    }
+    /// `interpolate` by Newton's divided differences
+    pub fn interpolate_newton<'a>(&'a self, xs: &[El<'_, S>], ys: &[El<'_, S>]) -> El<'a, Self> {
+        assert_eq!(xs.len(), ys.len(), "as many values as points are needed");
+        let mut newton = NewtonInterpolation::new(self);
+        for (x, y) in Iterator::zip(xs.iter(), ys) {
+            newton.push(x, y);
+        }
+        newton.polynomial()
+    }
+    /// The rational function `n / d` through the points, with `n` of degree at most `num_deg`,
+    /// `d` monic of degree less than `xs.len() - num_deg`, by Cauchy interpolation: the
+    /// extended Euclidean algorithm on the product of the `x - x_i` and the polynomial
+    /// interpolant. `None` if no such function takes the values at all the points.
+    pub fn interpolate_rational<'a>(
+        &'a self,
+        xs: &[El<'_, S>],
+        ys: &[El<'_, S>],
+        num_deg: usize,
+    ) -> Option<(El<'a, Self>, El<'a, Self>)> {
+        assert!(
+            num_deg < xs.len(),
+            "numerator degree must be below the point count"
+        );
+        let p = self.interpolate(xs, ys);
+        let m = xs.iter().fold(self.one().into_owned(), |m, x| {
+            self.mul(Cow::Owned(m), Cow::Owned(self.x_minus(x)))
+                .into_owned()
+        });
+        let (num, den) = self.rational_reconstruction(m, p.v.into_owned(), num_deg)?;
+        let zero = self.inner().zero();
+        if self.evaluate_many(&den, xs).iter().any(|d| *d.v == *zero) {
+            return None;
+        }
+        let lc = self.leading_coefficient(&den);
+        Some((self.div_by(num, &lc), self.div_by(den, &lc)))
+    }
+    /// The Padé approximant `n / d` of the power series `f`: `n` of degree at most `num_deg`, `d`
+    /// of degree at most `den_deg` with constant term one, agreeing with `f` up to
+    /// `x^(num_deg + den_deg)`. `None` if there is no such approximant.
+    pub fn pade<'a>(
+        &'a self,
+        f: &El<'_, Self>,
+        num_deg: usize,
+        den_deg: usize,
+    ) -> Option<(El<'a, Self>, El<'a, Self>)> {
+        assert_eq!(self, f.s);
+        let n = num_deg + den_deg + 1;
+        let mut m = vec![S::V::default(); n];
+        m.push(self.inner().one().into_owned());
+        let mut p = (*f.v).clone();
+        p.truncate(n);
+        let (num, den) = self.rational_reconstruction(m, self.el(p).v.into_owned(), num_deg)?;
+        let d0 = self.inner().el(den.v[0].clone());
+        if *d0.v == *self.inner().zero() {
+            return None;
+        }
+        Some((self.div_by(num, &d0), self.div_by(den, &d0)))
+    }
+    /// `(r, t)` with `r = t p mod m`, `deg r <= num_deg` and `deg t < deg m - num_deg`, from the
+    /// first small enough remainder of the extended Euclidean algorithm
+    fn rational_reconstruction(
+        &self,
+        m: Vec<S::V>,
+        p: Vec<S::V>,
+        num_deg: usize,
+    ) -> Option<(El<'_, Self>, El<'_, Self>)> {
+        if p.is_empty() {
+            return Some((self.el(p), self.el(self.one().into_owned())));
+        }
+        let (r, _, t) = xgcd_until(self, m, p, |r| r.len() <= num_deg + 1);
+        if r.len() > num_deg + 1 || t.is_empty() {
+            return None;
+        }
+        Some((self.el(r), self.el(t)))
+    }
+    /// `x - a`
+    fn x_minus(&self, a: &El<'_, S>) -> Vec<S::V> {
+        let mut neg = Cow::Borrowed(&*a.v);
+        self.inner().negate(&mut neg);
+        self.el(vec![neg.into_owned(), self.inner().one().into_owned()])
+            .v
+            .into_owned()
+    }
+    fn div_by<'a>(&'a self, p: El<'_, Self>, c: &El<'_, S>) -> El<'a, Self> {
+        let s = self.inner();
+        self.el(p
+            .v
+            .iter()
+            .map(|a| s.div(Cow::Borrowed(a), Cow::Borrowed(&*c.v)).into_owned())
+            .collect::<Vec<_>>())
+    }
+}
+
+/// From this many points on, subproduct tree interpolation beats Lagrange's formula
+const INTERPOLATION_THRESHOLD: usize = 16;
+
+/// Newton interpolation, one point at a time: after each `push` the polynomial of least degree
+/// through the points so far, in the Newton basis of products of `x - x_i`
+pub struct NewtonInterpolation<'a, S: SFusedMulAdd + SField> {
+    poly: &'a DensePolynomial<S>,
+    /// The divided differences `f[x_0, ..., x_k]`, which are the Newton basis coefficients
+    differences: Vec<S::V>,
+    interpolant: Vec<S::V>,
+    /// The product of `x - x_i` over the points so far
+    basis: Vec<S::V>,
+}
+impl<'a, S: SFusedMulAdd + SField> NewtonInterpolation<'a, S> {
+    pub fn new(poly: &'a DensePolynomial<S>) -> Self {
+        Self {
+            poly,
+            differences: Vec::new(),
+            interpolant: Vec::new(),
+            basis: poly.one().into_owned(),
+        }
+    }
+    /// Adds the point `x` with value `y`, which must differ from the previous points
+    pub fn push(&mut self, x: &El<'_, S>, y: &El<'_, S>) {
+        let (poly, s) = (self.poly, self.poly.inner());
+        assert_eq!(s, x.s);
+        assert_eq!(s, y.s);
+        let at_x = |v: &Vec<S::V>| poly.evaluate(&poly.el(v.clone()), x).v.into_owned();
+        let denom = at_x(&self.basis);
+        assert!(denom != *s.zero(), "interpolation points must be distinct");
+        let diff = s.sub(Cow::Borrowed(&*y.v), Cow::Owned(at_x(&self.interpolant)));
+        let c = s.div(diff, Cow::Owned(denom)).into_owned();
+        let term = poly.mul(Cow::Owned(vec![c.clone()]), Cow::Borrowed(&self.basis));
+        self.interpolant = poly
+            .add(Cow::Owned(std::mem::take(&mut self.interpolant)), term)
+            .into_owned();
+        self.basis = poly
+            .mul(
+                Cow::Owned(std::mem::take(&mut self.basis)),
+                Cow::Owned(poly.x_minus(x)),
+            )
+            .into_owned();
+        self.differences.push(c);
+    }
+    /// The polynomial through the points so far
+    pub fn polynomial(&self) -> El<'a, DensePolynomial<S>> {
+        self.poly.el(self.interpolant.clone())
+    }
+    /// The divided differences `f[x_0]`, `f[x_0, x_1]`, ..., `f[x_0, ..., x_k]`
+    pub fn divided_differences(&self) -> Vec<El<'a, S>> {
+        let s = self.poly.inner();
+        self.differences.iter().map(|c| s.el(c.clone())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Int, Mod};
+    use rug::Integer;
+
+    const P: u32 = 1_000_003;
+
+    /// `len` pseudorandom residues modulo `P`
+    fn residues(len: usize, seed: u64) -> Vec<Integer> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                Integer::from((state >> 33) % u64::from(P))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn interpolants_round_trip() {
+        let fp = Mod::new_prime(Int.el(P));
+        let fpx = DensePolynomial::new_symb("x", &fp);
+        // Below and above the subproduct tree threshold
+        for len in [1, 2, 7, 16, 50, 129] {
+            let p = fpx.el(residues(len, len as u64));
+            // Distinct points 1, 4, 9, ... with their values
+            let xs: Vec<_> = (1..=len).map(|i| fp.el(Integer::from(i * i))).collect();
+            let ys = fpx.evaluate_many(&p, &xs);
+            assert_eq!(fpx.interpolate(&xs, &ys), p, "{len}");
+            assert_eq!(fpx.interpolate_newton(&xs, &ys), p, "{len}");
+            if len <= 50 {
+                assert_eq!(fpx.interpolate_lagrange(&xs, &ys), p, "{len}");
+            }
+        }
+        assert_eq!(fpx.interpolate(&[], &[]), fpx.el(Vec::new()));
+    }
+
+    #[test]
+    fn divided_differences() {
+        let fp = Mod::new_prime(Int.el(P));
+        let fpx = DensePolynomial::new_symb("x", &fp);
+        // x^2 at 0, 1, 3: f[0] = 0, f[0, 1] = 1, f[0, 1, 3] = 1
+        let mut newton = NewtonInterpolation::new(&fpx);
+        for x in [0, 1, 3] {
+            newton.push(&fp.el(Integer::from(x)), &fp.el(Integer::from(x * x)));
+        }
+        let differences: Vec<_> = newton
+            .divided_differences()
+            .into_iter()
+            .map(|c| c.v.into_owned())
+            .collect();
+        assert_eq!(differences, [0, 1, 1]);
+        assert_eq!(
+            newton.polynomial(),
+            fpx.el(vec![Integer::ZERO, Integer::ZERO, Integer::from(1)])
+        );
+    }
+
+    #[test]
+    fn rational_interpolation() {
+        let fp = Mod::new_prime(Int.el(P));
+        let fpx = DensePolynomial::new_symb("x", &fp);
+        let num = fpx.el(residues(4, 1));
+        let mut den = residues(3, 2);
+        den.push(Integer::from(1));
+        let den = fpx.el(den);
+        let xs: Vec<_> = (1..=9u32).map(|i| fp.el(Integer::from(i))).collect();
+        let ys: Vec<_> = Iterator::zip(
+            fpx.evaluate_many(&num, &xs).into_iter(),
+            fpx.evaluate_many(&den, &xs),
+        )
+        .map(|(n, d)| n / d)
+        .collect();
+        let (n, d) = fpx.interpolate_rational(&xs, &ys, 3).unwrap();
+        // In lowest terms with a monic denominator
+        let g = num.gcd(&den);
+        assert_eq!(d, fpx.quo(&den, &g));
+        assert_eq!(n, fpx.quo(&num, &g));
+    }
+
+    #[test]
+    fn pade_approximants_agree_with_the_series() {
+        let fp = Mod::new_prime(Int.el(P));
+        let fpx = DensePolynomial::new_symb("x", &fp);
+        for (num_deg, den_deg) in [(0, 0), (3, 0), (0, 4), (5, 5), (7, 2)] {
+            let f = fpx.el(residues(20, (num_deg * 10 + den_deg) as u64));
+            let (n, d) = fpx.pade(&f, num_deg, den_deg).unwrap();
+            assert!(fpx.degree(&n).is_none_or(|k| k <= num_deg));
+            assert!(fpx.degree(&d).is_some_and(|k| k <= den_deg));
+            assert_eq!(d.v[0], 1);
+            // q f - p = O(x^(num_deg + den_deg + 1))
+            let r = d * f - n;
+            let order = num_deg + den_deg + 1;
+            assert!(
+                r.v.iter().take(order).all(|c| *c == 0),
+                "{num_deg} {den_deg}"
+            );
+        }
+        // exp(x) = 1 + x + x^2 / 2 + ... has the [1/1] approximant (1 + x/2) / (1 - x/2)
+        let half = fp.inv(&fp.el(Integer::from(2))).unwrap().v.into_owned();
+        let exp = fpx.el(vec![Integer::from(1), Integer::from(1), half.clone()]);
+        let (n, d) = fpx.pade(&exp, 1, 1).unwrap();
+        assert_eq!(n, fpx.el(vec![Integer::from(1), half.clone()]));
+        assert_eq!(d, fpx.el(vec![Integer::from(1), Integer::from(P) - half]));
+    }
+}
@@ -0,0 +1,75 @@
+use rug::{Complete, Integer};
+
+/// The product of polynomials with integer coefficients, reduced modulo `modulus`, or exactly
+/// when `modulus` is zero, by Kronecker substitution: both operands are evaluated at a power of
+/// two large enough to separate the product's coefficients, multiplied as single integers, and
+/// the product read back off the digits.
+pub(crate) fn mul_residues(a: &[Integer], b: &[Integer], modulus: &Integer) -> Vec<Integer> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    // Symmetric representatives in (-m/2, m/2] make the coefficients, and so the slots, smaller
+    let lift = |v: &[Integer]| -> Vec<Integer> {
+        if *modulus == 0 {
+            return v.to_vec();
+        }
+        let half = (modulus >> 1u32).complete();
+        v.iter()
+            .map(|x| {
+                let r = x.div_rem_euc_ref(modulus).complete().1;
+                if r > half { r - modulus } else { r }
+            })
+            .collect()
+    };
+    let (a, b) = (lift(a), lift(b));
+    let max_abs = |v: &[Integer]| v.iter().map(|x| x.clone().abs()).max().unwrap();
+    // Every coefficient of the product is at most this in absolute value
+    let bound = max_abs(&a) * max_abs(&b) * usize::min(a.len(), b.len());
+    // Slots two bits wider than the bound keep each signed coefficient, and each partial sum of
+    // lower coefficients, strictly inside half a slot
+    let width = bound.significant_bits() + 2;
+    let product = pack(&a, width) * pack(&b, width);
+    let mut ret = Vec::with_capacity(a.len() + b.len() - 1);
+    unpack(product, width, a.len() + b.len() - 1, &mut ret);
+    if *modulus != 0 {
+        for c in &mut ret {
+            *c = c.div_rem_euc_ref(modulus).complete().1;
+        }
+    }
+    ret
+}
+
+/// `sum v[i] 2^(width i)`, splitting in halves so every shift is of a number about as large as
+/// the result
+fn pack(v: &[Integer], width: u32) -> Integer {
+    if let [c] = v {
+        return c.clone();
+    }
+    let (lo, hi) = v.split_at(v.len() / 2);
+    (pack(hi, width) << shift(width, lo.len())) + pack(lo, width)
+}
+
+/// Reads `count` signed coefficients of `width` bits off `x`, lowest first, into `out`
+fn unpack(x: Integer, width: u32, count: usize, out: &mut Vec<Integer>) {
+    if count == 1 {
+        out.push(x);
+        return;
+    }
+    let lo_count = count / 2;
+    let bits = shift(width, lo_count);
+    // The low half is the signed residue of x modulo 2^bits
+    let mut lo = x.keep_bits_ref(bits).complete();
+    if lo.get_bit(bits - 1) {
+        lo -= Integer::from(1) << bits;
+    }
+    let hi = (x - &lo) >> bits;
+    unpack(lo, width, lo_count, out);
+    unpack(hi, width, count - lo_count, out);
+}
+
+fn shift(width: u32, count: usize) -> u32 {
+    u32::try_from(count)
+        .ok()
+        .and_then(|count| width.checked_mul(count))
+        .expect("polynomial too large for kronecker substitution")
+}
@@ -0,0 +1,409 @@
+use crate::{
+    DensePolynomial, El, PowerSeries, SAdd, SDiv, SField, SFusedMulAdd, SMul, SSub, Structure,
+    SuperStructure,
+};
+use std::{borrow::Cow, fmt, mem};
+
+/// Laurent series in one variable, `x^v (c_0 + c_1 x + ...)`, either exact or known up to an
+/// absolute precision `O(x^p)` carried by each value. The structure caps the number of known
+/// coefficients, so products and inverses keep at most that many terms.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LaurentSeries<S: Structure + SFusedMulAdd> {
+    precision: usize,
+    poly: DensePolynomial<S>,
+}
+/// `x^valuation (coeffs[0] + coeffs[1] x + ...) + O(x^precision)`, with `coeffs[0]` nonzero, no
+/// trailing zero coefficients and no coefficients from `x^precision` on. Zero series have no
+/// coefficients and valuation zero; `precision` is `None` for exact series.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LaurentValue<V> {
+    valuation: isize,
+    coeffs: Vec<V>,
+    precision: Option<isize>,
+}
+/// The exact series of a polynomial, from its coefficients lowest degree first
+impl<V> From<Vec<V>> for LaurentValue<V> {
+    fn from(coeffs: Vec<V>) -> Self {
+        Self {
+            valuation: 0,
+            coeffs,
+            precision: None,
+        }
+    }
+}
+impl<S: SFusedMulAdd> Structure for LaurentSeries<S> {
+    type V = LaurentValue<S::V>;
+    fn fmt_v(&self, v: &Self::V, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let s = self.poly.inner();
+        let symbol = self.poly.symbol();
+        if v.coeffs.is_empty() && v.precision.is_none() {
+            return s.fmt_v(&s.zero(), f);
+        }
+        let mut first = true;
+        for (k, coeff) in v
+            .coeffs
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| *c != &*s.zero())
+        {
+            if !mem::take(&mut first) {
+                write!(f, " + ")?;
+            }
+            let deg = v.valuation + k as isize;
+            if deg == 0 || coeff != &*s.one() {
+                s.fmt_v(coeff, f)?
+            }
+            match deg {
+                0 => {}
+                1 => write!(f, "{symbol}")?,
+                _ => write!(f, "{symbol}^{deg}")?,
+            }
+        }
+        if let Some(p) = v.precision {
+            if !first {
+                write!(f, " + ")?;
+            }
+            write!(f, "O({symbol}^{p})")?;
+        }
+        Ok(())
+    }
+    fn el<'a>(&'a self, v: impl Into<Self::V>) -> El<'a, Self> {
+        El {
+            v: Cow::Owned(self.normalized(v.into())),
+            s: self,
+        }
+    }
+}
+impl<S: SFusedMulAdd> std::fmt::Display for LaurentSeries<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if f.alternate() {
+            write!(f, ", ")?;
+        }
+        write!(
+            f,
+            "laurent {} prec {}{:#}",
+            self.poly.symbol(),
+            self.precision,
+            self.poly.inner()
+        )
+    }
+}
+/// Lifts polynomials as exact series, and coefficients through them with `lift2` and `el2`
+impl<S: SFusedMulAdd> SuperStructure for LaurentSeries<S> {
+    type Inner = DensePolynomial<S>;
+    fn inner(&self) -> &Self::Inner {
+        &self.poly
+    }
+    fn lifted_from<'a>(&'a self, inner: El<'a, Self::Inner>) -> El<'a, Self> {
+        let El { v, s } = inner;
+        assert_eq!(&self.poly, s);
+        self.el(v.into_owned())
+    }
+}
+
+impl<S: SFusedMulAdd> LaurentSeries<S> {
+    /// Series in `symbol` keeping at most `precision` coefficients
+    pub fn new_symb(symbol: impl AsRef<str>, inner: &S, precision: usize) -> Self {
+        assert!(precision > 0, "laurent series without precision");
+        Self {
+            precision,
+            poly: DensePolynomial::new_symb(symbol, inner),
+        }
+    }
+    /// The most coefficients a value keeps
+    pub fn precision(&self) -> usize {
+        self.precision
+    }
+    pub fn symb(&self) -> El<'_, Self> {
+        self.monomial(1)
+    }
+    /// `x^k` for any integer `k`
+    pub fn monomial(&self, k: isize) -> El<'_, Self> {
+        self.el(LaurentValue {
+            valuation: k,
+            coeffs: vec![self.poly.inner().one().into_owned()],
+            precision: None,
+        })
+    }
+    /// `O(x^p)`, the zero known up to `x^p`, which adds the precision to exact series
+    pub fn big_o(&self, p: isize) -> El<'_, Self> {
+        self.el(LaurentValue {
+            valuation: 0,
+            coeffs: Vec::new(),
+            precision: Some(p),
+        })
+    }
+    /// The exponent of the lowest nonzero term; `None` for zero, exact or not
+    pub fn valuation(&self, a: &El<'_, Self>) -> Option<isize> {
+        (!a.v.coeffs.is_empty()).then_some(a.v.valuation)
+    }
+    /// The `p` in `O(x^p)`; `None` for exact series
+    pub fn absolute_precision(&self, a: &El<'_, Self>) -> Option<isize> {
+        a.v.precision
+    }
+    /// The coefficient of `x^k`, for `k` below the absolute precision
+    pub fn coefficient<'a>(&'a self, a: &El<'_, Self>, k: isize) -> El<'a, S> {
+        assert!(
+            a.v.precision.is_none_or(|p| k < p),
+            "coefficient beyond the precision"
+        );
+        let s = self.poly.inner();
+        match usize::try_from(k - a.v.valuation)
+            .ok()
+            .and_then(|i| a.v.coeffs.get(i))
+        {
+            Some(c) => s.el(c.clone()),
+            None => El { v: s.zero(), s },
+        }
+    }
+    /// The power series `f` with its precision
+    pub fn from_series<'a>(&'a self, f: &El<'_, PowerSeries<S>>) -> El<'a, Self> {
+        assert_eq!(self.poly.inner(), f.s.inner().inner());
+        self.el(LaurentValue {
+            valuation: 0,
+            coeffs: (*f.v).clone(),
+            precision: Some(f.s.precision() as isize),
+        })
+    }
+    /// `x^valuation coeffs + O(x^precision)` in the normal form of `LaurentValue`, dropping
+    /// coefficients beyond the structure's precision
+    fn normalized(&self, v: LaurentValue<S::V>) -> LaurentValue<S::V> {
+        let LaurentValue {
+            mut valuation,
+            mut coeffs,
+            mut precision,
+        } = v;
+        let s = self.poly.inner();
+        let leading = coeffs.iter().take_while(|c| **c == *s.zero()).count();
+        coeffs.drain(..leading);
+        valuation += leading as isize;
+        if let Some(p) = precision {
+            coeffs.truncate(usize::try_from(p - valuation).unwrap_or(0));
+        }
+        coeffs = self.poly.el(coeffs).v.into_owned();
+        if coeffs.is_empty() {
+            valuation = 0;
+        } else if precision.is_some() || coeffs.len() > self.precision {
+            let cap = valuation + self.precision as isize;
+            coeffs.truncate(self.precision);
+            coeffs = self.poly.el(coeffs).v.into_owned();
+            precision = Some(precision.map_or(cap, |p| isize::min(p, cap)));
+        }
+        LaurentValue {
+            valuation,
+            coeffs,
+            precision,
+        }
+    }
+    /// The coefficients of `a` from `x^valuation` on
+    fn aligned(&self, a: &LaurentValue<S::V>, valuation: isize) -> Vec<S::V> {
+        if a.coeffs.is_empty() {
+            return Vec::new();
+        }
+        let mut ret = vec![S::V::default(); (a.valuation - valuation) as usize];
+        ret.extend(a.coeffs.iter().cloned());
+        ret
+    }
+    /// The lowest valuation of two values and the least precision, for sums
+    fn common(&self, a: &LaurentValue<S::V>, b: &LaurentValue<S::V>) -> (isize, Option<isize>) {
+        let valuation = [a, b]
+            .into_iter()
+            .filter(|v| !v.coeffs.is_empty())
+            .map(|v| v.valuation)
+            .min()
+            .unwrap_or(0);
+        let precision = [a.precision, b.precision].into_iter().flatten().min();
+        (valuation, precision)
+    }
+}
+
+/// Division by any series with a known nonzero coefficient
+impl<S: SFusedMulAdd + SField> LaurentSeries<S> {
+    /// The multiplicative inverse, by Newton iteration on the unit part
+    pub fn inverse<'a>(&'a self, a: &El<'_, Self>) -> El<'a, Self> {
+        assert_eq!(self, a.s);
+        self.el(self.inverse_v(&a.v))
+    }
+    fn inverse_v(&self, a: &LaurentValue<S::V>) -> LaurentValue<S::V> {
+        let s = self.poly.inner();
+        let Some(c) = a.coeffs.first() else {
+            panic!("laurent series division by zero");
+        };
+        let c_inv = s.div(s.one(), Cow::Borrowed(c)).into_owned();
+        if a.precision.is_none() && a.coeffs.len() == 1 {
+            return LaurentValue {
+                valuation: -a.valuation,
+                coeffs: vec![c_inv],
+                precision: None,
+            };
+        }
+        let relative = a.precision.map_or(self.precision, |p| {
+            usize::min((p - a.valuation) as usize, self.precision)
+        });
+        LaurentValue {
+            valuation: -a.valuation,
+            coeffs: self.poly.inverse_series(&a.coeffs, relative, c_inv),
+            precision: Some(relative as isize - a.valuation),
+        }
+    }
+}
+
+impl<S: SFusedMulAdd> SAdd for LaurentSeries<S> {
+    fn zero(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(LaurentValue::default())
+    }
+    fn add<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let (valuation, precision) = self.common(&lhs, &rhs);
+        let coeffs = self.poly.add(
+            Cow::Owned(self.aligned(&lhs, valuation)),
+            Cow::Owned(self.aligned(&rhs, valuation)),
+        );
+        Cow::Owned(self.normalized(LaurentValue {
+            valuation,
+            coeffs: coeffs.into_owned(),
+            precision,
+        }))
+    }
+}
+impl<S: SFusedMulAdd + SSub> SSub for LaurentSeries<S> {
+    fn negate<'a>(&'a self, v: &mut Cow<'a, Self::V>) {
+        let v = v.to_mut();
+        let mut coeffs = Cow::Owned(mem::take(&mut v.coeffs));
+        self.poly.negate(&mut coeffs);
+        v.coeffs = coeffs.into_owned();
+    }
+    fn sub<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let (valuation, precision) = self.common(&lhs, &rhs);
+        let coeffs = self.poly.sub(
+            Cow::Owned(self.aligned(&lhs, valuation)),
+            Cow::Owned(self.aligned(&rhs, valuation)),
+        );
+        Cow::Owned(self.normalized(LaurentValue {
+            valuation,
+            coeffs: coeffs.into_owned(),
+            precision,
+        }))
+    }
+}
+impl<S: SFusedMulAdd> SMul for LaurentSeries<S> {
+    fn one(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(LaurentValue::from(vec![
+            self.poly.inner().one().into_owned(),
+        ]))
+    }
+    /// Known to the lower of `v_a + p_b` and `v_b + p_a`, reading the valuation of an inexact
+    /// zero `O(x^p)` as `p`
+    fn mul<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let exact_zero = |v: &LaurentValue<S::V>| v.coeffs.is_empty() && v.precision.is_none();
+        if exact_zero(&lhs) || exact_zero(&rhs) {
+            return self.zero();
+        }
+        let low = |v: &LaurentValue<S::V>| {
+            if v.coeffs.is_empty() {
+                v.precision.unwrap()
+            } else {
+                v.valuation
+            }
+        };
+        let (va, vb) = (low(&lhs), low(&rhs));
+        let precision = [lhs.precision.map(|p| p + vb), rhs.precision.map(|p| p + va)]
+            .into_iter()
+            .flatten()
+            .min();
+        let coeffs = self.poly.mul(
+            Cow::Owned(lhs.into_owned().coeffs),
+            Cow::Owned(rhs.into_owned().coeffs),
+        );
+        Cow::Owned(self.normalized(LaurentValue {
+            valuation: va + vb,
+            coeffs: coeffs.into_owned(),
+            precision,
+        }))
+    }
+}
+impl<S: SFusedMulAdd> SFusedMulAdd for LaurentSeries<S> {}
+impl<S: SFusedMulAdd + SField> SDiv for LaurentSeries<S> {
+    fn div<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let inv = self.inverse_v(&rhs);
+        self.mul(lhs, Cow::Owned(inv))
+    }
+}
+impl<S: SFusedMulAdd + SField> SField for LaurentSeries<S> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Int, Mod};
+    use rug::Integer;
+
+    const P: u64 = 1_000_003;
+
+    /// `x^valuation (coeffs) + O(x^precision)`
+    fn series<'a>(
+        ls: &'a LaurentSeries<Mod<Int, true>>,
+        valuation: isize,
+        coeffs: &[i64],
+        precision: Option<isize>,
+    ) -> El<'a, LaurentSeries<Mod<Int, true>>> {
+        let p = Integer::from(P);
+        ls.el(LaurentValue {
+            valuation,
+            coeffs: coeffs
+                .iter()
+                .map(|&c| Integer::from(c).div_rem_euc(p.clone()).1)
+                .collect(),
+            precision,
+        })
+    }
+
+    #[test]
+    fn precision_of_sums_and_products() {
+        let field = Mod::new_prime(Int.el(P));
+        let ls = LaurentSeries::new_symb("x", &field, 10);
+        // x^-2 + 1 + O(x^3) and x + O(x^4)
+        let a = series(&ls, -2, &[1, 0, 1], Some(3));
+        let b = series(&ls, 1, &[1], Some(4));
+        assert_eq!(a.copy() + b.copy(), series(&ls, -2, &[1, 0, 1, 1], Some(3)));
+        assert_eq!(a.copy() - a.copy(), ls.big_o(3));
+        // Known to min(3 + 1, 4 - 2)
+        assert_eq!(a.copy() * b.copy(), series(&ls, -1, &[1, 0, 1], Some(2)));
+        assert_eq!(ls.valuation(&(a.copy() * b)), Some(-1));
+        // Exact series stay exact
+        let c = series(&ls, -1, &[1, 1], None);
+        let d = series(&ls, -1, &[1, -1], None);
+        assert_eq!(c * d, series(&ls, -2, &[1, 0, -1], None));
+        // Coefficients beyond the precision of a value and of the structure are dropped
+        assert_eq!(
+            series(&ls, 0, &[1, 2, 3], Some(1)),
+            series(&ls, 0, &[1], Some(1))
+        );
+        let long = series(&ls, -5, &[1; 12], None);
+        assert_eq!(ls.absolute_precision(&long), Some(5));
+        assert_eq!(*ls.coefficient(&long, 4).v, 1);
+        assert_eq!(ls.valuation(&ls.big_o(7)), None);
+    }
+
+    #[test]
+    fn inverses() {
+        let field = Mod::new_prime(Int.el(P));
+        let ls = LaurentSeries::new_symb("x", &field, 8);
+        assert_eq!(ls.inverse(&ls.monomial(-3)), ls.monomial(3));
+        // 1 / (x - x^2) = x^-1 (1 + x + x^2 + ...)
+        let a = series(&ls, 1, &[1, -1], None);
+        assert_eq!(ls.inverse(&a), series(&ls, -1, &[1; 8], Some(7)));
+        let b = series(&ls, -2, &[3, 1, 4, 1, 5], Some(6));
+        let product = b.copy() * ls.inverse(&b);
+        assert_eq!(product, ls.monomial(0) + ls.big_o(8));
+        let quotient = a.copy() / b.copy();
+        assert_eq!(quotient * b.copy(), a.copy() + ls.big_o(9));
+    }
+
+    #[test]
+    fn display() {
+        let field = Mod::new_prime(Int.el(P));
+        let ls = LaurentSeries::new_symb("x", &field, 8);
+        let a = series(&ls, -1, &[1, 2], Some(3));
+        assert!(a.to_string().starts_with("x^-1 + 2 + O(x^3) ("), "{a}");
+        assert!(ls.big_o(2).to_string().starts_with("O(x^2) ("));
+    }
+}
@@ -1,18 +1,60 @@
 #[macro_use]
 mod core;
 
+mod ball;
+mod calculus;
+mod complex;
+mod complex_roots;
+mod conway;
+mod crt;
 mod dense_polynomial;
+mod discrete_log;
+mod euclid;
+mod evaluation;
+mod finite_field;
+mod finite_field_factor;
+mod gf2;
 mod int;
+mod integer_factor;
+mod interpolation;
+mod kronecker;
+mod laurent_series;
+mod lll;
+mod modular_roots;
 mod modulo;
+mod ntt;
+mod padic;
+mod polynomial_gcd;
+mod power_series;
 mod primitive_int;
+mod puiseux;
+mod rat;
+mod real;
+mod real_roots;
+mod resultant;
+mod square_free;
 
+pub use ball::{Ball, BallValue};
+pub use complex::{Complex, ComplexValue};
 pub use core::{
-    El, SAdd, SFusedMulAdd, SMul, SRem, SSub, Structure, Super2Structure, Super3Structure,
-    SuperStructure,
+    El, SAdd, SDiv, SDivRem, SDivSmall, SField, SFusedMulAdd, SGcd, SMul, SRem, SSub, Structure,
+    Super2Structure, Super3Structure, SuperStructure,
 };
-pub use dense_polynomial::DensePolynomial;
-pub use int::Int;
+pub use crt::{Rns, crt};
+pub use dense_polynomial::{DensePolynomial, MulAlgorithm, mul_karatsuba, mul_toom3};
+pub use discrete_log::{discrete_log, discrete_log_bsgs, discrete_log_kangaroo, discrete_log_rho};
+pub use finite_field::GF;
+pub use gf2::{GF2, Gf2Poly};
+pub use int::{Int, SInteger, SIntegerResidues};
+pub use interpolation::NewtonInterpolation;
+pub use laurent_series::{LaurentSeries, LaurentValue};
 pub use modulo::Mod;
+pub use padic::{Padic, PadicValue};
+pub use polynomial_gcd::SPolynomialGcd;
+pub use power_series::PowerSeries;
 pub use primitive_int::{I, U};
+pub use rat::Rat;
+pub use real::{Real, RealValue};
+pub use square_free::Factorization;
 
 pub use rug;
@@ -0,0 +1,145 @@
+use rug::{Complete, Integer};
+use std::mem;
+
+/// LLL reduction with `delta = 99 / 100` of the linearly independent rows of `basis`, in place,
+/// by Cohen's integral algorithm 2.6.7, which keeps the Gram–Schmidt data as the integers
+/// `d_i = |b*_1|^2 ... |b*_i|^2` and `lambda_ij = d_j mu_ij`. Returns the `d_i`, from which the
+/// squared Gram–Schmidt norms are `d_i / d_(i-1)`.
+pub(crate) fn lll(basis: &mut [Vec<Integer>]) -> Vec<Integer> {
+    let n = basis.len();
+    // d[i + 1] is d_i of the first i + 1 rows; d[0] = 1
+    let mut d = vec![Integer::from(1)];
+    let mut lambda = vec![vec![Integer::ZERO; n]; n];
+    if n == 0 {
+        return d;
+    }
+    d.push(dot(&basis[0], &basis[0]));
+    let (mut k, mut k_max) = (1, 0);
+    while k < n {
+        if k > k_max {
+            k_max = k;
+            for j in 0..=k {
+                let mut u = dot(&basis[k], &basis[j]);
+                for i in 0..j {
+                    u = (Integer::from(&d[i + 1] * &u) - &lambda[k][i] * &lambda[j][i]) / &d[i];
+                }
+                if j < k {
+                    lambda[k][j] = u;
+                } else {
+                    assert!(u != 0, "lll of dependent vectors");
+                    d.push(u);
+                }
+            }
+        }
+        reduce(basis, &mut lambda, &d, k, k - 1);
+        // The Lovász condition d_k d_(k-2) >= (delta d_(k-1)^2 - lambda^2), times 100
+        let lhs = Integer::from(&d[k + 1] * &d[k - 1]) * 100u32;
+        let rhs = Integer::from(d[k].square_ref()) * 99u32
+            - Integer::from(lambda[k][k - 1].square_ref()) * 100u32;
+        if lhs < rhs {
+            swap(basis, &mut lambda, &mut d, k, k_max);
+            k = usize::max(1, k - 1);
+        } else {
+            for l in (0..k - 1).rev() {
+                reduce(basis, &mut lambda, &d, k, l);
+            }
+            k += 1;
+        }
+    }
+    d
+}
+
+fn dot(a: &[Integer], b: &[Integer]) -> Integer {
+    Iterator::zip(a.iter(), b)
+        .map(|(x, y)| (x * y).complete())
+        .sum()
+}
+
+/// Size reduction of row `k` by row `l`
+fn reduce(
+    basis: &mut [Vec<Integer>],
+    lambda: &mut [Vec<Integer>],
+    d: &[Integer],
+    k: usize,
+    l: usize,
+) {
+    let dl = &d[l + 1];
+    if Integer::from(lambda[k][l].abs_ref()) * 2u32 <= *dl {
+        return;
+    }
+    // The nearest integer to lambda_kl / d_l
+    let q = (Integer::from(&lambda[k][l] * 2u32) + dl)
+        .div_rem_floor(Integer::from(dl * 2u32))
+        .0;
+    let (lower, upper) = basis.split_at_mut(k);
+    for (x, y) in Iterator::zip(upper[0].iter_mut(), &lower[l]) {
+        *x -= &q * y;
+    }
+    lambda[k][l] -= &q * dl;
+    let (lower, upper) = lambda.split_at_mut(k);
+    for (x, y) in Iterator::zip(upper[0].iter_mut(), &lower[l][..l]) {
+        *x -= &q * y;
+    }
+}
+
+/// Exchange of rows `k - 1` and `k`
+fn swap(
+    basis: &mut [Vec<Integer>],
+    lambda: &mut [Vec<Integer>],
+    d: &mut [Integer],
+    k: usize,
+    k_max: usize,
+) {
+    basis.swap(k - 1, k);
+    let (lower, upper) = lambda.split_at_mut(k);
+    lower[k - 1][..k - 1].swap_with_slice(&mut upper[0][..k - 1]);
+    let l = lambda[k][k - 1].clone();
+    let b = (&d[k - 1] * &d[k + 1]).complete() + l.square_ref();
+    let b = b / &d[k];
+    for row in &mut lambda[k + 1..=k_max] {
+        let t = mem::take(&mut row[k]);
+        row[k] = (&d[k + 1] * &row[k - 1]).complete() - &l * &t;
+        row[k] /= &d[k];
+        row[k - 1] = (&b * t + Integer::from(&l * &row[k])) / &d[k + 1];
+    }
+    d[k] = b;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(v: &[&[i64]]) -> Vec<Vec<Integer>> {
+        v.iter()
+            .map(|row| row.iter().map(|&c| Integer::from(c)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn known_basis() {
+        let mut basis = rows(&[&[1, 1, 1], &[-1, 0, 2], &[3, 5, 6]]);
+        let d = lll(&mut basis);
+        assert_eq!(basis, rows(&[&[0, 1, 0], &[1, 0, 1], &[-1, 0, 2]]));
+        // The squared volume, |det|^2 = 9, is unchanged
+        assert_eq!(d, [1, 1, 2, 9].map(Integer::from));
+    }
+
+    #[test]
+    fn finds_small_relation() {
+        // The shortest relation among these is w_0 + 2 w_1 - w_2 = 0
+        let weights = [314_159, 271_828, 857_815];
+        let mut basis: Vec<Vec<Integer>> = (0..3)
+            .map(|i| {
+                let mut row = vec![Integer::ZERO; 4];
+                row[i] = Integer::from(1);
+                row[3] = Integer::from(weights[i]) * 1000;
+                row
+            })
+            .collect();
+        lll(&mut basis);
+        let first = &basis[0];
+        let sign = Integer::from(first[0].signum_ref());
+        let relation: Vec<Integer> = first.iter().map(|c| Integer::from(c * &sign)).collect();
+        assert_eq!(relation, [1, 2, -1, 0].map(Integer::from));
+    }
+}
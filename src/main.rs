@@ -8,7 +8,7 @@
 // NOTE: Pain points
 // - mutating functions, such as negate, interacting with Cow. Maybe enum(&T, &mut T) instead?
 
-use mathlib::{DensePolynomial, I, Int, Mod, Structure, SuperStructure, Super2Structure};
+use mathlib::{DensePolynomial, I, Int, Mod, Structure, Super2Structure, SuperStructure};
 
 fn main() {
     let fixed_int: I<32> = I;
@@ -0,0 +1,493 @@
+use crate::{El, Mod, SInteger, Structure, SuperStructure, int::is_prime};
+use rug::{Complete, Integer, ops::Pow};
+use std::iter;
+
+/// Square roots and k-th roots in `Mod` structures over integers. Moduli other than primes and
+/// prime powers have no roots here, and `is_square` panics for them.
+impl<S: SInteger, const PRIME: bool> Mod<S, PRIME> {
+    /// Whether `a` is a square, decided without computing a root
+    pub fn is_square(&self, a: &El<'_, Self>) -> bool {
+        assert_eq!(self, a.s);
+        let (p, e) = self.prime_power().expect("modulus must be a prime power");
+        is_square_mod_prime_power(self.inner().to_integer(&a.v), &p, e)
+    }
+    /// Some square root of `a`, if there is one
+    pub fn sqrt<'a>(&'a self, a: &El<'_, Self>) -> Option<El<'a, Self>> {
+        self.sqrts(a).next()
+    }
+    /// All square roots of `a`
+    pub fn sqrts<'a>(&'a self, a: &El<'_, Self>) -> impl Iterator<Item = El<'a, Self>> + 'a {
+        assert_eq!(self, a.s);
+        let a = self.inner().to_integer(&a.v);
+        let roots: Box<dyn Iterator<Item = Integer>> = match self.prime_power() {
+            Some((p, e)) if is_square_mod_prime_power(a.clone(), &p, e) => {
+                roots_mod_prime_power(a, 2, p, e)
+            }
+            _ => Box::new(iter::empty()),
+        };
+        roots.map(|x| self.el(self.inner().of_integer(x)))
+    }
+    /// Some `k`-th root of `a`, if there is one
+    pub fn nth_root<'a>(&'a self, a: &El<'_, Self>, k: u32) -> Option<El<'a, Self>> {
+        self.nth_roots(a, k).next()
+    }
+    /// All `k`-th roots of `a`
+    pub fn nth_roots<'a>(
+        &'a self,
+        a: &El<'_, Self>,
+        k: u32,
+    ) -> impl Iterator<Item = El<'a, Self>> + 'a {
+        assert!(k > 0, "zeroth roots are undefined");
+        assert_eq!(self, a.s);
+        let a = self.inner().to_integer(&a.v);
+        let roots: Box<dyn Iterator<Item = Integer>> = match self.prime_power() {
+            Some((p, e)) => roots_mod_prime_power(a, k, p, e),
+            None => Box::new(iter::empty()),
+        };
+        roots.map(|x| self.el(self.inner().of_integer(x)))
+    }
+
+    fn prime_power(&self) -> Option<(Integer, u32)> {
+        prime_power_decomposition(&self.inner().to_integer(&self.modulus().v))
+    }
+}
+
+/// Whether `a` is a square modulo `p^e`
+fn is_square_mod_prime_power(a: Integer, p: &Integer, e: u32) -> bool {
+    let a = a.div_rem_euc(p.clone().pow(e)).1;
+    if a == 0 {
+        return true;
+    }
+    let (u, v) = a.remove_factor(p);
+    if !v.is_multiple_of(2) {
+        return false;
+    }
+    let e = e - v;
+    if *p == 2 {
+        match e {
+            1 => true,
+            2 => u.mod_u(4) == 1,
+            _ => u.mod_u(8) == 1,
+        }
+    } else {
+        u.legendre(p) == 1
+    }
+}
+
+/// Writes `m` as `p^e` for a prime `p`, if possible
+pub(crate) fn prime_power_decomposition(m: &Integer) -> Option<(Integer, u32)> {
+    if *m < 2 {
+        return None;
+    }
+    if is_prime(m) {
+        return Some((m.clone(), 1));
+    }
+    (2..m.significant_bits()).find_map(|e| {
+        let r = m.clone().root(e);
+        (r.clone().pow(e) == *m && is_prime(&r)).then_some((r, e))
+    })
+}
+
+/// All solutions of `x^k = a (mod p^e)`
+fn roots_mod_prime_power(
+    a: Integer,
+    k: u32,
+    p: Integer,
+    e: u32,
+) -> Box<dyn Iterator<Item = Integer>> {
+    let pe = p.clone().pow(e);
+    let a = a.div_rem_euc(pe.clone()).1;
+    if a == 0 {
+        // x^k = 0 exactly when p^ceil(e/k) divides x
+        let step = p.clone().pow(e.div_ceil(k));
+        let count = (&pe / &step).complete();
+        return Box::new(counting(count).map(move |j| j * &step));
+    }
+    let (u, v) = a.remove_factor(&p);
+    if !v.is_multiple_of(k) {
+        return Box::new(iter::empty());
+    }
+    // x = p^(v/k) y with y a unit and y^k = u (mod p^(e-v)); y is free modulo p^(e - v/k)
+    let scale = p.clone().pow(v / k);
+    let unit_modulus = p.clone().pow(e - v);
+    let free = p.clone().pow(v - v / k);
+    let ys = unit_roots_mod_prime_power(u, k, p, e - v);
+    Box::new(ys.flat_map(move |y| {
+        let scale = scale.clone();
+        let unit_modulus = unit_modulus.clone();
+        let pe = pe.clone();
+        counting(free.clone()).map(move |j| (&scale * (&y + j * &unit_modulus)) % &pe)
+    }))
+}
+
+/// `0, 1, ..., count - 1`
+fn counting(count: Integer) -> impl Iterator<Item = Integer> {
+    iter::successors(Some(Integer::ZERO), move |j| Some((j + 1u32).complete()))
+        .take_while(move |j| *j < count)
+}
+
+/// All solutions of `x^k = u (mod p^e)` for a unit `u`, lifted from the solutions modulo `p` with
+/// Hensel's lemma
+fn unit_roots_mod_prime_power(
+    u: Integer,
+    k: u32,
+    p: Integer,
+    e: u32,
+) -> Box<dyn Iterator<Item = Integer>> {
+    let pe = p.clone().pow(e);
+    let u = u.div_rem_euc(pe.clone()).1;
+    let base = unit_roots_mod_prime((&u % &p).complete(), k, &p);
+    if e == 1 {
+        return base;
+    }
+    if !Integer::from(k).is_divisible(&p) {
+        // Nonsingular: each root modulo p lifts uniquely, with quadratic convergence
+        return Box::new(base.map(move |mut x| {
+            let mut modulus = p.clone();
+            while modulus < pe {
+                modulus.square_mut();
+                if modulus > pe {
+                    modulus = pe.clone();
+                }
+                let f = (x
+                    .pow_mod_ref(&Integer::from(k), &modulus)
+                    .unwrap()
+                    .complete()
+                    - &u)
+                    .div_rem_euc(modulus.clone())
+                    .1;
+                let df = (x
+                    .pow_mod_ref(&Integer::from(k - 1), &modulus)
+                    .unwrap()
+                    .complete()
+                    * k)
+                    .invert(&modulus)
+                    .unwrap();
+                x = (x - f * df).div_rem_euc(modulus.clone()).1;
+            }
+            x
+        }));
+    }
+    // Singular: lift one digit at a time, trying every digit
+    let mut roots: Vec<Integer> = base.collect();
+    let mut modulus = p.clone();
+    for _ in 1..e {
+        let next_modulus = (&modulus * &p).complete();
+        let target = (&u % &next_modulus).complete();
+        let modulus_ref = &modulus;
+        roots = roots
+            .iter()
+            .flat_map(|x| counting(p.clone()).map(move |i| x + i * modulus_ref))
+            .filter(|y| {
+                y.pow_mod_ref(&Integer::from(k), &next_modulus)
+                    .unwrap()
+                    .complete()
+                    == target
+            })
+            .collect();
+        modulus = next_modulus;
+    }
+    Box::new(roots.into_iter())
+}
+
+/// All solutions of `x^k = u (mod p)` for `u` not divisible by the prime `p`
+fn unit_roots_mod_prime(u: Integer, k: u32, p: &Integer) -> Box<dyn Iterator<Item = Integer>> {
+    if *p == 2 {
+        return Box::new(iter::once(Integer::from(1)));
+    }
+    let order = (p - 1u32).complete();
+    let g = gcd(order.mod_u(k), k);
+    if pow_mod(&u, &(&order / g).complete(), p) != 1 {
+        return Box::new(iter::empty());
+    }
+    let x = if k == 2 {
+        sqrt_mod_prime(&u, p)
+    } else {
+        // x^k = u has the same solutions as x^g = w, where w = u^(k/g)^-1
+        let cofactor = (&order / g).complete();
+        let w = match Integer::from(k / g).invert(&cofactor) {
+            Ok(inv) => pow_mod(&u, &inv, p),
+            Err(_) => u,
+        };
+        gth_root(w, g, p)
+    };
+    let zeta = primitive_root_of_unity(g, p);
+    let p = p.clone();
+    Box::new(iter::successors(Some(x), move |x| Some((x * &zeta).complete() % &p)).take(g as usize))
+}
+
+fn pow_mod(base: &Integer, exp: &Integer, m: &Integer) -> Integer {
+    base.pow_mod_ref(exp, m).unwrap().complete()
+}
+
+/// Some square root of the quadratic residue `a` modulo the odd prime `p`, using Tonelli–Shanks
+/// when the 2-adic part of `p - 1` is small and Cipolla's algorithm otherwise
+fn sqrt_mod_prime(a: &Integer, p: &Integer) -> Integer {
+    let s = (p - 1u32).complete().find_one(0).unwrap();
+    if s * s <= 2 * p.significant_bits() {
+        tonelli_shanks(a, p, s)
+    } else {
+        cipolla(a, p)
+    }
+}
+
+fn tonelli_shanks(a: &Integer, p: &Integer, s: u32) -> Integer {
+    let q = (p - 1u32).complete() >> s;
+    let z = (2u32..)
+        .map(Integer::from)
+        .find(|z| z.legendre(p) == -1)
+        .unwrap();
+    let mut m = s;
+    let mut c = pow_mod(&z, &q, p);
+    let mut t = pow_mod(a, &q, p);
+    let mut r = pow_mod(a, &((&q + 1u32).complete() >> 1), p);
+    while t != 1 {
+        let mut i = 0;
+        let mut t2i = t.clone();
+        while t2i != 1 {
+            t2i = t2i.square() % p;
+            i += 1;
+        }
+        let b = pow_mod(&c, &(Integer::from(1) << (m - i - 1)), p);
+        m = i;
+        c = b.clone().square() % p;
+        t = t * &c % p;
+        r = r * b % p;
+    }
+    r
+}
+
+fn cipolla(a: &Integer, p: &Integer) -> Integer {
+    // Find t with t^2 - a a nonresidue, then (t + w)^((p + 1) / 2) = sqrt(a) in F_p[w]/(w^2 - (t^2 - a))
+    let (t, d) = (1u32..)
+        .map(|t| {
+            let t = Integer::from(t);
+            let d = (t.clone().square() - a).div_rem_euc(p.clone()).1;
+            (t, d)
+        })
+        .find(|(_, d)| d.legendre(p) == -1)
+        .unwrap();
+    let mul = |(x0, x1): &(Integer, Integer), (y0, y1): &(Integer, Integer)| {
+        (
+            ((x0 * y0).complete() + (x1 * y1).complete() * &d) % p,
+            ((x0 * y1).complete() + x1 * y0) % p,
+        )
+    };
+    let exp: Integer = (p + 1u32).complete() >> 1;
+    let mut acc = (Integer::from(1), Integer::ZERO);
+    let base = (t, Integer::from(1));
+    for i in (0..exp.significant_bits()).rev() {
+        acc = mul(&acc, &acc);
+        if exp.get_bit(i) {
+            acc = mul(&acc, &base);
+        }
+    }
+    acc.0
+}
+
+/// Some `g`-th root of the `g`-th power residue `w` modulo `p`, where `g` divides `p - 1`, taking
+/// one prime-degree root at a time with Adleman–Manders–Miller
+fn gth_root(mut w: Integer, mut g: u32, p: &Integer) -> Integer {
+    let order = (p - 1u32).complete();
+    for q in prime_factors(g) {
+        while g.is_multiple_of(q) {
+            g /= q;
+            let y = adleman_manders_miller(&w, q, p);
+            // Of the q candidate roots, keep one that still has a (g / q)-th root
+            let zeta = primitive_root_of_unity(q, p);
+            let residue_exp = (&order / g).complete();
+            w = iter::successors(Some(y), |y| Some((y * &zeta).complete() % p))
+                .take(q as usize)
+                .find(|y| pow_mod(y, &residue_exp, p) == 1)
+                .unwrap();
+        }
+    }
+    w
+}
+
+/// Some `q`-th root of the `q`-th power residue `delta` modulo `p`, for a prime `q` dividing
+/// `p - 1`
+fn adleman_manders_miller(delta: &Integer, q: u32, p: &Integer) -> Integer {
+    let order = (p - 1u32).complete();
+    let (s, t) = order.clone().remove_factor(&Integer::from(q));
+    let q_int = Integer::from(q);
+    let alpha = q_int.clone().invert(&s).unwrap_or_default();
+    let rho = (2u32..)
+        .map(Integer::from)
+        .find(|rho| pow_mod(rho, &(&order / q).complete(), p) != 1)
+        .unwrap();
+    let a = pow_mod(&rho, &(q_int.clone().pow(t - 1) * &s), p);
+    let mut b = pow_mod(delta, &((&q_int * &alpha).complete() - 1u32), p);
+    let mut c = pow_mod(&rho, &s, p);
+    let mut h = Integer::from(1);
+    for i in 1..t {
+        let d = pow_mod(&b, &q_int.clone().pow(t - 1 - i), p);
+        // j = -log_a(d), found by search in the subgroup of order q
+        let log = iter::successors(Some(Integer::from(1)), |x| Some((x * &a).complete() % p))
+            .take(q as usize)
+            .position(|x| x == d)
+            .unwrap() as u32;
+        let j = Integer::from((q - log) % q);
+        let cq = pow_mod(&c, &q_int, p);
+        b = b * pow_mod(&cq, &j, p) % p;
+        h = h * pow_mod(&c, &j, p) % p;
+        c = cq;
+    }
+    pow_mod(delta, &alpha, p) * h % p
+}
+
+/// An element of multiplicative order exactly `g` modulo `p`, where `g` divides `p - 1`
+fn primitive_root_of_unity(g: u32, p: &Integer) -> Integer {
+    let cofactor = ((p - 1u32).complete()) / g;
+    let factors = prime_factors(g);
+    (2u32..)
+        .map(|h| pow_mod(&Integer::from(h), &cofactor, p))
+        .find(|zeta| {
+            factors
+                .iter()
+                .all(|q| pow_mod(zeta, &Integer::from(g / q), p) != 1)
+        })
+        .unwrap()
+}
+
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn prime_factors(mut n: u32) -> Vec<u32> {
+    let mut ret = Vec::new();
+    let mut d = 2;
+    while u64::from(d) * u64::from(d) <= u64::from(n) {
+        if n.is_multiple_of(d) {
+            ret.push(d);
+            while n.is_multiple_of(d) {
+                n /= d;
+            }
+        }
+        d += 1;
+    }
+    if n > 1 {
+        ret.push(n);
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Int;
+
+    /// The roots of `x^k = a` modulo `m`, by trying every `x`
+    fn brute_force(a: u64, k: u32, m: u64) -> Vec<Integer> {
+        (0..m)
+            .filter(|&x| {
+                Integer::from(x)
+                    .pow_mod(&Integer::from(k), &Integer::from(m))
+                    .unwrap()
+                    == a
+            })
+            .map(Integer::from)
+            .collect()
+    }
+
+    #[test]
+    fn roots_modulo_prime_powers_agree_with_brute_force() {
+        // Nonsingular and singular Hensel lifting, 2-adic moduli, and cube and higher roots by
+        // Adleman–Manders–Miller
+        for m in [
+            2, 4, 8, 16, 32, 64, 3, 9, 27, 81, 5, 25, 125, 7, 49, 19, 37, 97,
+        ] {
+            let zm = Mod::new(Int.el(m));
+            for a in 0..m {
+                let el = zm.el(Integer::from(a));
+                for k in 1..=6 {
+                    let mut roots: Vec<Integer> =
+                        zm.nth_roots(&el, k).map(|x| x.v.into_owned()).collect();
+                    roots.sort();
+                    assert_eq!(roots, brute_force(a, k, m), "x^{k} = {a} mod {m}");
+                }
+                let mut roots: Vec<Integer> = zm.sqrts(&el).map(|x| x.v.into_owned()).collect();
+                roots.sort();
+                assert_eq!(roots, brute_force(a, 2, m), "x^2 = {a} mod {m}");
+                assert_eq!(zm.is_square(&el), !roots.is_empty(), "{a} mod {m}");
+                assert_eq!(zm.sqrt(&el).is_some(), !roots.is_empty(), "{a} mod {m}");
+            }
+        }
+    }
+
+    #[test]
+    fn square_roots_modulo_large_primes() {
+        // 2-adic parts of p - 1 from 1 to 33, for both Tonelli–Shanks and Cipolla
+        for (p, s) in [
+            (1_000_003u64, 1),
+            (65_537, 16),
+            (998_244_353, 23),
+            (469_762_049, 26),
+            (0x3fff_ffee_0000_0001, 33),
+        ] {
+            let p = Integer::from(p);
+            assert_eq!((&p - 1u32).complete().find_one(0), Some(s));
+            for x in [2u32, 3, 12_345, 99_991] {
+                let a = Integer::from(x).square() % &p;
+                for root in [
+                    tonelli_shanks(&a, &p, s),
+                    cipolla(&a, &p),
+                    sqrt_mod_prime(&a, &p),
+                ] {
+                    assert_eq!(root.square() % &p, a, "sqrt {a} mod {p}");
+                }
+            }
+            let zp = Mod::new_prime(Int.el(p.clone()));
+            let a = zp.el(Integer::from(5).square());
+            assert_eq!(zp.sqrts(&a).count(), 2);
+        }
+    }
+
+    #[test]
+    fn higher_roots_modulo_large_primes() {
+        // p - 1 = 2 * 3 * 166667 and 2^23 * 7 * 17
+        for (p, k) in [
+            (1_000_003u64, 3u32),
+            (998_244_353, 7),
+            (998_244_353, 17 * 7),
+        ] {
+            let p = Integer::from(p);
+            let zp = Mod::new_prime(Int.el(p.clone()));
+            for x in [2u32, 3, 12_345] {
+                let a = Integer::from(x).pow_mod(&Integer::from(k), &p).unwrap();
+                let delta = Integer::from(x).pow_mod(&Integer::from(7), &p).unwrap();
+                if k == 7 {
+                    let root = adleman_manders_miller(&delta, 7, &p);
+                    assert_eq!(root.pow_mod(&Integer::from(7), &p).unwrap(), delta);
+                }
+                let roots: Vec<_> = zp.nth_roots(&zp.el(a.clone()), k).collect();
+                assert_eq!(roots.len(), k as usize);
+                for r in roots {
+                    assert_eq!(
+                        r.v.pow_mod_ref(&Integer::from(k), &p).unwrap().complete(),
+                        a
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn moduli_without_roots() {
+        for m in [6u32, 15, 36, 100] {
+            let zm = Mod::new(Int.el(m));
+            let four = zm.el(Integer::from(4));
+            assert!(zm.sqrt(&four).is_none(), "{m}");
+            assert_eq!(zm.sqrts(&four).count(), 0);
+            assert!(zm.nth_root(&four, 3).is_none());
+        }
+        assert_eq!(
+            prime_power_decomposition(&Integer::from(243)),
+            Some((Integer::from(3), 5))
+        );
+        assert_eq!(prime_power_decomposition(&Integer::from(1)), None);
+    }
+}
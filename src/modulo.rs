@@ -1,18 +1,24 @@
-use crate::{El, SAdd, SFusedMulAdd, SMul, SRem, SSub, Structure, SuperStructure};
+use crate::{
+    DensePolynomial, El, Int, SAdd, SDiv, SDivRem, SDivSmall, SField, SFusedMulAdd, SGcd,
+    SIntegerResidues, SMul, SPolynomialGcd, SRem, SSub, Structure, SuperStructure, euclid::xgcd,
+    finite_field::is_irreducible, int::is_prime,
+};
 use std::{borrow::Cow, fmt, mem};
 
+/// The quotient by the ideal generated by `mod_`. `PRIME` records a prime or irreducible modulus,
+/// checked at construction, which makes the quotient a field.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Mod<S: Structure + SRem> {
+pub struct Mod<S: Structure + SRem, const PRIME: bool = false> {
     mod_: S::V,
     inner: S,
 }
-impl<S: SRem> Structure for Mod<S> {
+impl<S: SRem, const PRIME: bool> Structure for Mod<S, PRIME> {
     type V = S::V;
     fn fmt_v(&self, v: &Self::V, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         self.inner.fmt_v(v, f)
     }
 }
-impl<S: SRem> std::fmt::Display for Mod<S> {
+impl<S: SRem, const PRIME: bool> std::fmt::Display for Mod<S, PRIME> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         if f.alternate() {
             write!(f, ", ")?;
@@ -22,7 +28,7 @@ impl<S: SRem> std::fmt::Display for Mod<S> {
         write!(f, "{:#}", self.inner)
     }
 }
-impl<S: SRem> SuperStructure for Mod<S> {
+impl<S: SRem, const PRIME: bool> SuperStructure for Mod<S, PRIME> {
     type Inner = S;
     fn inner(&self) -> &Self::Inner {
         &self.inner
@@ -42,7 +48,35 @@ impl<S: SRem> Mod<S> {
         }
     }
 }
-impl<T: SAdd + SRem> SAdd for Mod<T> {
+impl Mod<Int, true> {
+    /// The prime field `Z/p`; panics unless `p` is prime
+    pub fn new_prime(p: El<'_, Int>) -> Self {
+        assert!(is_prime(&p.v), "modulus must be prime");
+        Self {
+            mod_: p.v.into_owned(),
+            inner: Int,
+        }
+    }
+}
+impl Mod<DensePolynomial<Mod<Int, true>>, true> {
+    /// The field `F_p[x]/(f)`, with `f` made monic; panics unless `f` is irreducible
+    pub fn new_irreducible(f: El<'_, DensePolynomial<Mod<Int, true>>>) -> Self {
+        assert!(is_irreducible(&f), "modulus must be irreducible");
+        Self {
+            mod_: f.s.normalized(f.copy()).v.into_owned(),
+            inner: f.s.clone(),
+        }
+    }
+}
+impl<S: SRem, const PRIME: bool> Mod<S, PRIME> {
+    pub fn modulus(&self) -> El<'_, S> {
+        El {
+            v: Cow::Borrowed(&self.mod_),
+            s: &self.inner,
+        }
+    }
+}
+impl<T: SAdd + SRem, const PRIME: bool> SAdd for Mod<T, PRIME> {
     fn zero(&self) -> Cow<'_, Self::V> {
         self.inner.zero()
     }
@@ -51,16 +85,19 @@ impl<T: SAdd + SRem> SAdd for Mod<T> {
             .rem(self.inner.add(lhs, rhs), Cow::Borrowed(&self.mod_))
     }
 }
-impl<T: SSub + SRem> SSub for Mod<T> {
+impl<T: SSub + SRem, const PRIME: bool> SSub for Mod<T, PRIME> {
     fn negate<'a>(&'a self, x: &mut Cow<'a, Self::V>) {
-        *x = self.inner.sub(Cow::Borrowed(&self.mod_), mem::take(x));
+        *x = self.inner.rem(
+            self.inner.sub(Cow::Borrowed(&self.mod_), mem::take(x)),
+            Cow::Borrowed(&self.mod_),
+        );
     }
     fn sub<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
         self.inner
             .rem(self.inner.sub(lhs, rhs), Cow::Borrowed(&self.mod_))
     }
 }
-impl<T: SMul + SRem> SMul for Mod<T> {
+impl<T: SMul + SRem, const PRIME: bool> SMul for Mod<T, PRIME> {
     fn one(&self) -> Cow<'_, Self::V> {
         let ret = self.inner.one();
         assert_ne!(&self.mod_, &*ret);
@@ -71,7 +108,7 @@ impl<T: SMul + SRem> SMul for Mod<T> {
             .rem(self.inner.mul(lhs, rhs), Cow::Borrowed(&self.mod_))
     }
 }
-impl<T: SFusedMulAdd + SRem> SFusedMulAdd for Mod<T> {
+impl<T: SFusedMulAdd + SRem, const PRIME: bool> SFusedMulAdd for Mod<T, PRIME> {
     fn fused_mul_add_ref(&self, acc: &mut Self::V, lhs: &Self::V, rhs: &Self::V) {
         self.inner.fused_mul_add_ref(acc, lhs, rhs);
 
@@ -79,4 +116,173 @@ impl<T: SFusedMulAdd + SRem> SFusedMulAdd for Mod<T> {
         slot = self.inner.rem(slot, Cow::Borrowed(&self.mod_));
         *acc = slot.into_owned();
     }
+    /// Through `SRem::mul_coefficients_rem` of the inner structure
+    fn mul_coefficients(&self, lhs: &[Self::V], rhs: &[Self::V]) -> Vec<Self::V> {
+        if lhs.is_empty() || rhs.is_empty() {
+            return Vec::new();
+        }
+        let len = lhs.len() + rhs.len() - 1;
+        self.inner.mul_coefficients_rem(lhs, rhs, len, &self.mod_)
+    }
+    fn mul_coefficients_low(&self, lhs: &[Self::V], rhs: &[Self::V], len: usize) -> Vec<Self::V> {
+        self.inner.mul_coefficients_rem(lhs, rhs, len, &self.mod_)
+    }
+}
+/// The integers modulo `n` modulo the residue `r` are the integers modulo `gcd(n, r)`
+impl<T: SIntegerResidues + SRem, const PRIME: bool> SIntegerResidues for Mod<T, PRIME> {
+    fn integer_modulus(&self) -> rug::Integer {
+        let m = self.inner.to_residue(&self.mod_);
+        self.inner.integer_modulus().gcd(&m)
+    }
+    fn to_residue(&self, v: &Self::V) -> rug::Integer {
+        self.inner.to_residue(v)
+    }
+    fn of_residue(&self, v: rug::Integer) -> Self::V {
+        self.inner
+            .of_residue(v.div_rem_euc(self.integer_modulus()).1)
+    }
+}
+impl<T: SFusedMulAdd + SDivSmall + SRem, const PRIME: bool> SDivSmall for Mod<T, PRIME> {
+    fn div_small_exact(&self, v: &Self::V, k: u32) -> Option<Self::V> {
+        // `k` is invertible exactly when some `1 + t mod_` with `0 <= t < k` is a multiple of `k`
+        let mut candidate = self.inner.one().into_owned();
+        for _ in 0..k {
+            if let Some(inv) = self.inner.div_small_exact(&candidate, k) {
+                return Some(self.mul(Cow::Borrowed(v), Cow::Owned(inv)).into_owned());
+            }
+            candidate = self
+                .inner
+                .add(Cow::Owned(candidate), Cow::Borrowed(&self.mod_))
+                .into_owned();
+        }
+        None
+    }
+}
+impl<T: SDivRem, const PRIME: bool> Mod<T, PRIME> {
+    /// The multiplicative inverse, if `v` is coprime to the modulus
+    pub fn inv<'a>(&'a self, v: &El<'_, Self>) -> Option<El<'a, Self>> {
+        assert_eq!(self, v.s);
+        self.inv_v(&v.v).map(|v| self.el(v))
+    }
+    fn inv_v(&self, v: &T::V) -> Option<T::V> {
+        let (g, x, _) = xgcd(&self.inner, v.clone(), self.mod_.clone());
+        // The gcd is only determined up to a unit, so divide it out
+        let (u, r) = self.inner.div_rem(self.inner.one(), Cow::Owned(g));
+        if *r != *self.inner.zero() {
+            return None;
+        }
+        let inv = self.inner.mul(Cow::Owned(x), u);
+        Some(self.inner.rem(inv, Cow::Borrowed(&self.mod_)).into_owned())
+    }
+}
+/// Division by elements coprime to the modulus, which is all nonzero elements when the modulus is
+/// prime or irreducible
+impl<T: SDivRem, const PRIME: bool> SDiv for Mod<T, PRIME> {
+    fn div<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let inv = self.inv_v(&rhs).expect("divisor not invertible");
+        self.mul(lhs, Cow::Owned(inv))
+    }
+}
+/// A field, as the modulus is prime or irreducible
+impl<T: SDivRem> SField for Mod<T, true> {}
+/// Greatest common divisors in a field: one unless both are zero
+impl<T: SDivRem + SGcd> SGcd for Mod<T, true> {
+    fn gcd<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        if *lhs == *self.zero() && *rhs == *self.zero() {
+            self.zero()
+        } else {
+            self.one()
+        }
+    }
+    fn unit_part(&self, v: &Self::V) -> Self::V {
+        if *v == *self.zero() {
+            self.one().into_owned()
+        } else {
+            v.clone()
+        }
+    }
+    fn quotient_characteristic(&self, m: &Self::V) -> rug::Integer {
+        if *m == *self.zero() {
+            self.inner.quotient_characteristic(&self.mod_)
+        } else {
+            rug::Integer::from(1)
+        }
+    }
+    /// The fields `new_prime` and `new_irreducible` construct are finite, so `v -> v^p` permutes
+    /// them and the root is the element before `v` in its orbit
+    fn pth_root(&self, v: &Self::V) -> Option<Self::V> {
+        let p = self.characteristic();
+        let v = self.inner.rem(Cow::Borrowed(v), Cow::Borrowed(&self.mod_));
+        let mut root = v.clone().into_owned();
+        loop {
+            let next = self.el(root.clone()).pow(&p).v.into_owned();
+            if next == *v {
+                return Some(root);
+            }
+            root = next;
+        }
+    }
+}
+impl<T: SDivRem + SGcd + SFusedMulAdd> SPolynomialGcd for Mod<T, true> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{I, U};
+    use rug::Integer;
+
+    /// The schoolbook product of `lhs` and `rhs` over the integers, reduced modulo `m`
+    fn schoolbook(lhs: &[Integer], rhs: &[Integer], m: &Integer) -> Vec<Integer> {
+        let mut ret = vec![Integer::ZERO; lhs.len() + rhs.len() - 1];
+        for (i, a) in lhs.iter().enumerate() {
+            for (j, b) in rhs.iter().enumerate() {
+                ret[i + j] += Integer::from(a * b);
+            }
+        }
+        ret.into_iter().map(|c| c.modulo(m)).collect()
+    }
+
+    #[test]
+    fn bounded_coefficients_do_not_overflow() {
+        let zx = Mod::new(U::<8>.el(2));
+        let ones = vec![1; 300];
+        let expected = schoolbook(
+            &vec![Integer::from(1); 300],
+            &vec![Integer::from(1); 300],
+            &Integer::from(2),
+        );
+        let product = zx.mul_coefficients(&ones, &ones);
+        assert_eq!(
+            product
+                .iter()
+                .map(|&c| Integer::from(c))
+                .collect::<Vec<_>>(),
+            expected
+        );
+
+        let m = (1 << 31) - 1;
+        let zx = Mod::new(I::<64>.el(m));
+        let mut state = 1i64;
+        let mut next = || {
+            state = (state * 48271) % m;
+            state
+        };
+        let lhs: Vec<i64> = (0..300).map(|_| next()).collect();
+        let rhs: Vec<i64> = (0..300).map(|_| next()).collect();
+        let expected = schoolbook(
+            &lhs.iter().map(|&c| Integer::from(c)).collect::<Vec<_>>(),
+            &rhs.iter().map(|&c| Integer::from(c)).collect::<Vec<_>>(),
+            &Integer::from(m),
+        );
+        let product = zx.mul_coefficients(&lhs, &rhs);
+        assert_eq!(
+            product
+                .iter()
+                .map(|&c| Integer::from(c))
+                .collect::<Vec<_>>(),
+            expected
+        );
+        let low = zx.mul_coefficients_low(&lhs, &rhs, 200);
+        assert_eq!(low, product[..200]);
+    }
 }
@@ -0,0 +1,365 @@
+use crate::int::{factorize, is_prime};
+use rug::{Complete, Integer};
+use std::sync::Mutex;
+
+/// The primes `c 2^32 + 1` below `2^62` in decreasing order, with generators of their
+/// multiplicative groups
+const MULTI_PRIMES: [(u64, u64); 64] = [
+    (0x3fffffee00000001, 3),
+    (0x3fffffb400000001, 19),
+    (0x3fffffa000000001, 3),
+    (0x3fffff5d00000001, 5),
+    (0x3fffff4900000001, 3),
+    (0x3fffff4600000001, 3),
+    (0x3fffff3000000001, 5),
+    (0x3fffff2800000001, 3),
+    (0x3fffff1c00000001, 3),
+    (0x3fffff1800000001, 5),
+    (0x3ffffed600000001, 7),
+    (0x3ffffecb00000001, 3),
+    (0x3ffffec700000001, 5),
+    (0x3ffffeb800000001, 5),
+    (0x3ffffeb300000001, 3),
+    (0x3ffffe6a00000001, 7),
+    (0x3ffffe4100000001, 3),
+    (0x3ffffdf900000001, 3),
+    (0x3ffffdd800000001, 3),
+    (0x3ffffdd700000001, 7),
+    (0x3ffffdc800000001, 5),
+    (0x3ffffdc300000001, 5),
+    (0x3ffffda700000001, 11),
+    (0x3ffffd8300000001, 5),
+    (0x3ffffd6600000001, 3),
+    (0x3ffffd2d00000001, 3),
+    (0x3ffffd2000000001, 13),
+    (0x3ffffcfc00000001, 15),
+    (0x3ffffcf700000001, 3),
+    (0x3ffffce200000001, 3),
+    (0x3ffffcc900000001, 5),
+    (0x3ffffc7f00000001, 3),
+    (0x3ffffc6c00000001, 31),
+    (0x3ffffc4e00000001, 13),
+    (0x3ffffbf700000001, 23),
+    (0x3ffffbe200000001, 7),
+    (0x3ffffbbf00000001, 3),
+    (0x3ffffbb600000001, 3),
+    (0x3ffffb9200000001, 3),
+    (0x3ffffb6100000001, 7),
+    (0x3ffffb5900000001, 3),
+    (0x3ffffb5300000001, 3),
+    (0x3ffffb3100000001, 17),
+    (0x3ffffb0e00000001, 3),
+    (0x3ffffaed00000001, 3),
+    (0x3ffffade00000001, 3),
+    (0x3ffffa9900000001, 3),
+    (0x3ffffa9800000001, 11),
+    (0x3ffffa8600000001, 10),
+    (0x3ffffa7200000001, 3),
+    (0x3ffffa6e00000001, 7),
+    (0x3ffffa5a00000001, 3),
+    (0x3ffffa5900000001, 7),
+    (0x3ffffa3000000001, 3),
+    (0x3ffffa1e00000001, 3),
+    (0x3ffffa1400000001, 7),
+    (0x3ffff9e500000001, 3),
+    (0x3ffff9db00000001, 5),
+    (0x3ffff9d800000001, 29),
+    (0x3ffff9c400000001, 3),
+    (0x3ffff99100000001, 3),
+    (0x3ffff97600000001, 3),
+    (0x3ffff96700000001, 3),
+    (0x3ffff96000000001, 11),
+];
+const MULTI_PRIME_TWO_ADICITY: u32 = 32;
+/// The single-transform primes met so far, so that each generator is found once
+static SINGLE_PRIMES: Mutex<Vec<NttPrime>> = Mutex::new(Vec::new());
+
+#[derive(Clone, Copy, Debug)]
+struct NttPrime {
+    p: u64,
+    /// Generator of the multiplicative group
+    g: u64,
+    /// The largest `k` with `2^k` dividing `p - 1`
+    two_adicity: u32,
+}
+impl NttPrime {
+    /// With `g` a generator of the multiplicative group modulo the prime `p`
+    const fn with_generator(p: u64, g: u64) -> Self {
+        Self {
+            p,
+            g,
+            two_adicity: (p - 1).trailing_zeros(),
+        }
+    }
+    /// Panics unless `p` is a prime below `2^62`
+    fn new(p: u64) -> Self {
+        let order = Integer::from(p - 1);
+        let factors = factorize(&order);
+        let g = (2..)
+            .find(|&g| {
+                factors
+                    .iter()
+                    .all(|(q, _)| pow_mod(g, (&order / q).complete().to_u64().unwrap(), p) != 1)
+            })
+            .unwrap();
+        Self {
+            p,
+            g,
+            two_adicity: (p - 1).trailing_zeros(),
+        }
+    }
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        (u128::from(a) * u128::from(b) % u128::from(self.p)) as u64
+    }
+    fn add(&self, a: u64, b: u64) -> u64 {
+        let s = a + b;
+        if s >= self.p { s - self.p } else { s }
+    }
+    fn sub(&self, a: u64, b: u64) -> u64 {
+        if a >= b { a - b } else { a + self.p - b }
+    }
+    fn inv(&self, a: u64) -> u64 {
+        pow_mod(a, self.p - 2, self.p)
+    }
+
+    /// In-place transform of a power-of-two length slice, evaluating at the powers of a
+    /// primitive root of unity (or its inverse, for the unnormalized inverse transform)
+    fn transform(&self, a: &mut [u64], inverse: bool) {
+        let n = a.len();
+        let mut j = 0;
+        for i in 1..n {
+            let mut bit = n >> 1;
+            while j & bit != 0 {
+                j ^= bit;
+                bit >>= 1;
+            }
+            j |= bit;
+            if i < j {
+                a.swap(i, j);
+            }
+        }
+        let mut len = 2;
+        while len <= n {
+            let mut w = pow_mod(self.g, (self.p - 1) / len as u64, self.p);
+            if inverse {
+                w = self.inv(w);
+            }
+            let mut twiddles = Vec::with_capacity(len / 2);
+            let mut wk = 1;
+            for _ in 0..len / 2 {
+                twiddles.push(wk);
+                wk = self.mul(wk, w);
+            }
+            for chunk in a.chunks_mut(len) {
+                let (lo, hi) = chunk.split_at_mut(len / 2);
+                for ((u, v), &t) in lo.iter_mut().zip(hi).zip(&twiddles) {
+                    let x = *u;
+                    let y = self.mul(*v, t);
+                    *u = self.add(x, y);
+                    *v = self.sub(x, y);
+                }
+            }
+            len <<= 1;
+        }
+    }
+
+    /// Cyclic convolution of residues, of length `a.len() + b.len() - 1`
+    fn convolve(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let len = a.len() + b.len() - 1;
+        let size = len.next_power_of_two();
+        assert!(
+            size.trailing_zeros() <= self.two_adicity,
+            "product too long for the ntt prime"
+        );
+        let mut fa = a.to_vec();
+        fa.resize(size, 0);
+        let mut fb = b.to_vec();
+        fb.resize(size, 0);
+        self.transform(&mut fa, false);
+        self.transform(&mut fb, false);
+        for (x, y) in fa.iter_mut().zip(&fb) {
+            *x = self.mul(*x, *y);
+        }
+        self.transform(&mut fa, true);
+        let size_inv = self.inv(size as u64 % self.p);
+        fa.truncate(len);
+        for x in &mut fa {
+            *x = self.mul(*x, size_inv);
+        }
+        fa
+    }
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, p: u64) -> u64 {
+    let mut ret = 1u64;
+    while exp != 0 {
+        if exp & 1 == 1 {
+            ret = (u128::from(ret) * u128::from(base) % u128::from(p)) as u64;
+        }
+        base = (u128::from(base) * u128::from(base) % u128::from(p)) as u64;
+        exp >>= 1;
+    }
+    ret
+}
+
+/// The first `count` primes for multi-prime transforms
+fn multi_primes(count: usize) -> Vec<NttPrime> {
+    assert!(
+        count <= MULTI_PRIMES.len(),
+        "coefficients too large for ntt"
+    );
+    MULTI_PRIMES[..count]
+        .iter()
+        .map(|&(p, g)| NttPrime::with_generator(p, g))
+        .collect()
+}
+
+/// The prime `p` for a single transform, with its generator looked up or found once
+fn single_ntt_prime(p: u64) -> NttPrime {
+    if let Some(&(_, g)) = MULTI_PRIMES.iter().find(|&&(q, _)| q == p) {
+        return NttPrime::with_generator(p, g);
+    }
+    let mut primes = SINGLE_PRIMES.lock().unwrap();
+    if let Some(q) = primes.iter().find(|q| q.p == p) {
+        return *q;
+    }
+    let q = NttPrime::new(p);
+    primes.push(q);
+    q
+}
+
+/// `modulus` as a machine word, if it is a prime admitting a single transform of length `len`
+pub(crate) fn single_prime(modulus: &Integer, len: usize) -> Option<u64> {
+    let p = modulus.to_u64().filter(|p| (2..1 << 62).contains(p))?;
+    let log_len = len.next_power_of_two().trailing_zeros();
+    ((p - 1).trailing_zeros() >= log_len && is_prime(modulus)).then_some(p)
+}
+
+/// The product of polynomials with integer coefficients, reduced modulo `modulus`, or exactly
+/// when `modulus` is zero. Uses a single transform when the modulus is itself a suitable prime,
+/// and otherwise transforms modulo enough primes to recover the integer product by CRT.
+pub(crate) fn mul_residues(a: &[Integer], b: &[Integer], modulus: &Integer) -> Vec<Integer> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let len = a.len() + b.len() - 1;
+    let log_len = len.next_power_of_two().trailing_zeros();
+    if let Some(p) = single_prime(modulus, len) {
+        let q = single_ntt_prime(p);
+        let residues = |v: &[Integer]| -> Vec<u64> {
+            v.iter()
+                .map(|x| x.div_rem_euc_ref(modulus).complete().1.to_u64().unwrap())
+                .collect()
+        };
+        return q
+            .convolve(&residues(a), &residues(b))
+            .into_iter()
+            .map(Integer::from)
+            .collect();
+    }
+    let lifted = |v: &[Integer]| -> Vec<Integer> {
+        if *modulus == 0 {
+            v.to_vec()
+        } else {
+            v.iter()
+                .map(|x| x.div_rem_euc_ref(modulus).complete().1)
+                .collect()
+        }
+    };
+    let (a, b) = (lifted(a), lifted(b));
+    let max_abs = |v: &[Integer]| v.iter().map(|x| x.clone().abs()).max().unwrap();
+    // Every coefficient of the product is at most this in absolute value
+    let bound = max_abs(&a) * max_abs(&b) * usize::min(a.len(), b.len());
+    let signed = *modulus == 0;
+    let needed_bits = bound.significant_bits() + u32::from(signed) + 1;
+    assert!(
+        log_len <= MULTI_PRIME_TWO_ADICITY,
+        "product too long for ntt"
+    );
+    let primes = multi_primes(needed_bits.div_ceil(61) as usize);
+    let per_prime: Vec<Vec<u64>> = primes
+        .iter()
+        .map(|q| {
+            let qi = Integer::from(q.p);
+            let residues = |v: &[Integer]| -> Vec<u64> {
+                v.iter()
+                    .map(|x| x.div_rem_euc_ref(&qi).complete().1.to_u64().unwrap())
+                    .collect()
+            };
+            q.convolve(&residues(&a), &residues(&b))
+        })
+        .collect();
+    let product = reconstruct(&primes, &per_prime, len, signed);
+    if signed {
+        product
+    } else {
+        product
+            .into_iter()
+            .map(|x| x.div_rem_euc_ref(modulus).complete().1)
+            .collect()
+    }
+}
+
+/// Garner's algorithm: mixed-radix digits in machine words, then one big-integer Horner pass per
+/// coefficient. Signed results are taken in `(-M/2, M/2]` for `M` the product of the primes.
+fn reconstruct(
+    primes: &[NttPrime],
+    per_prime: &[Vec<u64>],
+    len: usize,
+    signed: bool,
+) -> Vec<Integer> {
+    // inverses[i] is (q_0 ... q_{i-1})^-1 modulo q_i
+    let inverses: Vec<u64> = (0..primes.len())
+        .map(|i| {
+            let q = &primes[i];
+            let prefix = primes[..i].iter().fold(1, |acc, r| q.mul(acc, r.p % q.p));
+            q.inv(prefix)
+        })
+        .collect();
+    let total: Integer = primes.iter().map(|q| Integer::from(q.p)).product();
+    let half = (&total >> 1u32).complete();
+    (0..len)
+        .map(|k| {
+            let mut digits = Vec::with_capacity(primes.len());
+            for (i, q) in primes.iter().enumerate() {
+                // The value so far, modulo q_i
+                let mut acc = 0;
+                for j in (0..i).rev() {
+                    acc = q.add(q.mul(acc, primes[j].p % q.p), digits[j] % q.p);
+                }
+                digits.push(q.mul(q.sub(per_prime[i][k], acc), inverses[i]));
+            }
+            let mut x = Integer::ZERO;
+            for (j, &d) in digits.iter().enumerate().rev() {
+                x *= primes[j].p;
+                x += d;
+            }
+            if signed && x > half {
+                x -= &total;
+            }
+            x
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multi_primes_table() {
+        let mut c = 1 << (62 - MULTI_PRIME_TWO_ADICITY);
+        for (p, g) in MULTI_PRIMES {
+            // The next prime of the form below the previous one
+            loop {
+                c -= 1;
+                let q = (c << MULTI_PRIME_TWO_ADICITY) + 1;
+                if is_prime(&Integer::from(q)) {
+                    assert_eq!(q, p);
+                    break;
+                }
+            }
+            assert_eq!(NttPrime::new(p).g, g);
+        }
+    }
+}
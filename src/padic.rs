@@ -0,0 +1,505 @@
+use crate::{
+    El, Int, Mod, SAdd, SDiv, SField, SFusedMulAdd, SMul, SSub, Structure, SuperStructure,
+    int::is_prime,
+};
+use rug::{Integer, Rational, ops::Pow};
+use std::{borrow::Cow, fmt};
+
+/// The `p`-adic numbers `p^v u` for a unit `u`, either exact or known up to an absolute
+/// precision `O(p^k)` carried by each value. The structure caps the relative precision, the
+/// number of known digits of `u`, so inverses and long products keep at most that many.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Padic {
+    p: Integer,
+    precision: u32,
+}
+/// `p^valuation unit + O(p^precision)` with `unit` not divisible by `p`, and in
+/// `[0, p^(precision - valuation))` when inexact. Zero has unit and valuation zero; `precision`
+/// is `None` for exact values.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PadicValue {
+    valuation: isize,
+    unit: Integer,
+    precision: Option<isize>,
+}
+/// The exact value of an integer
+impl From<Integer> for PadicValue {
+    fn from(unit: Integer) -> Self {
+        Self {
+            valuation: 0,
+            unit,
+            precision: None,
+        }
+    }
+}
+impl Structure for Padic {
+    type V = PadicValue;
+    fn fmt_v(&self, v: &Self::V, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let p = &self.p;
+        if v.unit != 0 {
+            write!(f, "{}", v.unit)?;
+            if v.valuation != 0 {
+                write!(f, "·{p}^{}", v.valuation)?;
+            }
+        }
+        match v.precision {
+            Some(k) if v.unit != 0 => write!(f, " + O({p}^{k})"),
+            Some(k) => write!(f, "O({p}^{k})"),
+            None if v.unit == 0 => write!(f, "0"),
+            None => Ok(()),
+        }
+    }
+    fn el<'a>(&'a self, v: impl Into<Self::V>) -> El<'a, Self> {
+        El {
+            v: Cow::Owned(self.normalized(v.into())),
+            s: self,
+        }
+    }
+}
+impl std::fmt::Display for Padic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if f.alternate() {
+            write!(f, ", ")?;
+        }
+        write!(f, "padic {} prec {}", self.p, self.precision)
+    }
+}
+/// Lifts integers as exact values
+impl SuperStructure for Padic {
+    type Inner = Int;
+    fn inner(&self) -> &Self::Inner {
+        &Int
+    }
+    fn lifted_from<'a>(&'a self, inner: El<'a, Self::Inner>) -> El<'a, Self> {
+        self.el(inner.v.into_owned())
+    }
+}
+
+impl Padic {
+    /// The `p`-adic numbers keeping at most `precision` digits
+    pub fn new(p: El<'_, Int>, precision: u32) -> Self {
+        assert!(is_prime(&p.v), "p-adic numbers need a prime");
+        assert!(precision > 0, "p-adic numbers without precision");
+        Self {
+            p: p.v.into_owned(),
+            precision,
+        }
+    }
+    pub fn prime(&self) -> El<'_, Int> {
+        El {
+            v: Cow::Borrowed(&self.p),
+            s: &Int,
+        }
+    }
+    /// The most digits a value keeps
+    pub fn precision(&self) -> u32 {
+        self.precision
+    }
+    /// `O(p^k)`, the zero known up to `p^k`, which adds the precision to exact values
+    pub fn big_o(&self, k: isize) -> El<'_, Self> {
+        self.el(PadicValue {
+            valuation: 0,
+            unit: Integer::ZERO,
+            precision: Some(k),
+        })
+    }
+    /// `q` to the precision of the structure, exact when it is an integer times a power of `p`
+    pub fn from_rational(&self, q: &Rational) -> El<'_, Self> {
+        if *q == 0 {
+            return self.el(PadicValue::default());
+        }
+        let (num, num_valuation) = q.numer().clone().remove_factor(&self.p);
+        let (den, den_valuation) = q.denom().clone().remove_factor(&self.p);
+        let valuation = num_valuation as isize - den_valuation as isize;
+        if den == 1 {
+            return self.el(PadicValue {
+                valuation,
+                unit: num,
+                precision: None,
+            });
+        }
+        let modulus = self.p.clone().pow(self.precision);
+        let den_inv = den.invert(&modulus).unwrap();
+        self.el(PadicValue {
+            valuation,
+            unit: num * den_inv,
+            precision: Some(valuation + self.precision as isize),
+        })
+    }
+    /// The exponent of `p` in `a`; `None` for zero, exact or not
+    pub fn valuation(&self, a: &El<'_, Self>) -> Option<isize> {
+        (a.v.unit != 0).then_some(a.v.valuation)
+    }
+    /// The `k` in `O(p^k)`; `None` for exact values
+    pub fn absolute_precision(&self, a: &El<'_, Self>) -> Option<isize> {
+        a.v.precision
+    }
+    /// The `u` in `a = p^v u`, zero for zero
+    pub fn unit<'a>(&self, a: &'a El<'_, Self>) -> El<'a, Int> {
+        El {
+            v: Cow::Borrowed(&a.v.unit),
+            s: &Int,
+        }
+    }
+    /// Whether `a` is in the `p`-adic integers, as far as is known
+    pub fn is_integral(&self, a: &El<'_, Self>) -> bool {
+        self.valuation(a).is_none_or(|v| v >= 0)
+    }
+    /// `p^valuation unit + O(p^precision)` in the normal form of `PadicValue`, dropping digits
+    /// beyond the structure's precision
+    fn normalized(&self, v: PadicValue) -> PadicValue {
+        let PadicValue {
+            mut valuation,
+            mut unit,
+            mut precision,
+        } = v;
+        if let Some(k) = precision {
+            unit = self.reduced(unit, k - valuation);
+        }
+        if unit == 0 {
+            return PadicValue {
+                valuation: 0,
+                unit,
+                precision,
+            };
+        }
+        valuation += unit.remove_factor_mut(&self.p) as isize;
+        let cap = valuation + self.precision as isize;
+        if precision.is_some() || *unit.as_abs() >= self.p.clone().pow(self.precision) {
+            let k = precision.map_or(cap, |k| isize::min(k, cap));
+            precision = Some(k);
+            unit = self.reduced(unit, k - valuation);
+        }
+        PadicValue {
+            valuation,
+            unit,
+            precision,
+        }
+    }
+    /// `unit mod p^digits` in `[0, p^digits)`, zero when there are no digits
+    fn reduced(&self, unit: Integer, digits: isize) -> Integer {
+        match u32::try_from(digits) {
+            Ok(digits) if digits > 0 => unit.div_rem_euc(self.p.clone().pow(digits)).1,
+            _ => Integer::ZERO,
+        }
+    }
+    /// `a` times `p^(valuation(a) - valuation)`, for `valuation` at most that of `a`
+    fn aligned(&self, a: &PadicValue, valuation: isize) -> Integer {
+        if a.unit == 0 {
+            return Integer::ZERO;
+        }
+        let shift = u32::try_from(a.valuation - valuation).unwrap();
+        a.unit.clone() * self.p.clone().pow(shift)
+    }
+    /// The lowest valuation of two values and the least precision, for sums
+    fn common(&self, a: &PadicValue, b: &PadicValue) -> (isize, Option<isize>) {
+        let valuation = [a, b]
+            .into_iter()
+            .filter(|v| v.unit != 0)
+            .map(|v| v.valuation)
+            .min()
+            .unwrap_or(0);
+        let precision = [a.precision, b.precision].into_iter().flatten().min();
+        (valuation, precision)
+    }
+    /// The multiplicative inverse, modulo the power of `p` of the relative precision
+    pub fn inverse<'a>(&'a self, a: &El<'_, Self>) -> El<'a, Self> {
+        assert_eq!(self, a.s);
+        self.el(self.inverse_v(&a.v))
+    }
+    /// A square root by Hensel lifting a root modulo `p`, if `a` is a square to its precision;
+    /// the other root is its negative. For `p = 2` the root has one digit less than `a`.
+    pub fn sqrt<'a>(&'a self, a: &El<'_, Self>) -> Option<El<'a, Self>> {
+        assert_eq!(self, a.s);
+        let PadicValue {
+            valuation,
+            unit,
+            precision,
+        } = &*a.v;
+        if *unit == 0 {
+            return Some(self.el(PadicValue {
+                precision: precision.map(|k| (k + 1).div_euclid(2)),
+                ..PadicValue::default()
+            }));
+        }
+        if valuation % 2 != 0 {
+            return None;
+        }
+        if precision.is_none() && unit.is_perfect_square() {
+            return Some(self.el(PadicValue {
+                valuation: valuation / 2,
+                unit: unit.clone().sqrt(),
+                precision: None,
+            }));
+        }
+        let digits = precision.map_or(self.precision, |k| (k - valuation) as u32);
+        let ring = Mod::new(Int.el(self.p.clone().pow(digits)));
+        let root = ring.sqrt(&ring.el(self.reduced(unit.clone(), digits as isize)))?;
+        let digits = if self.p == 2 { digits - 1 } else { digits };
+        Some(self.el(PadicValue {
+            valuation: valuation / 2,
+            unit: root.v.into_owned(),
+            precision: Some(valuation / 2 + digits as isize),
+        }))
+    }
+    /// The logarithm of the unit `a`, from the series of `log(1 + x)` for `x = a^(p-1) - 1`, or
+    /// `a^2 - 1` for `p = 2`, which is divisible by `p`, divided by `p - 1` or `2`
+    pub fn log<'a>(&'a self, a: &El<'_, Self>) -> El<'a, Self> {
+        assert_eq!(self, a.s);
+        assert!(
+            self.valuation(a) == Some(0),
+            "p-adic logarithm of a non-unit"
+        );
+        let d = if self.p == 2 {
+            Integer::from(2)
+        } else {
+            Integer::from(&self.p - 1u32)
+        };
+        let x = self.el((*a.v).clone()).pow(&d) - self.el(Integer::from(1));
+        let divisor = self.el(d);
+        let Some(w) = self.valuation(&x) else {
+            return x / divisor;
+        };
+        let target = x.v.precision.unwrap_or(w + self.precision as isize);
+        let mut sum = self.el(PadicValue::default());
+        let mut term = self.el((*x.v).clone());
+        for k in 1u32.. {
+            // v(x^k / k) >= k w - log_p k
+            if k as isize * w - self.log_floor(k) >= target {
+                break;
+            }
+            let summand = self.el((*term.v).clone()) / self.el(Integer::from(k));
+            if k % 2 == 1 {
+                sum += summand;
+            } else {
+                sum -= summand;
+            }
+            term *= self.el((*x.v).clone());
+        }
+        (sum + self.big_o(target)) / divisor
+    }
+    /// The exponential of `x` divisible by `p`, or by `4` for `p = 2`, from its series
+    pub fn exp<'a>(&'a self, x: &El<'_, Self>) -> El<'a, Self> {
+        assert_eq!(self, x.s);
+        let one = || self.el(Integer::from(1));
+        let Some(w) = self.valuation(x) else {
+            return one() + self.el((*x.v).clone());
+        };
+        assert!(
+            w >= 1 && (self.p != 2 || w >= 2),
+            "p-adic exponential outside its disc of convergence"
+        );
+        let cap = self.precision as isize;
+        let target = x.v.precision.map_or(cap, |k| isize::min(k, cap));
+        let p1 = Integer::from(&self.p - 1u32);
+        let mut sum = one();
+        let mut term = one();
+        for k in 1u32.. {
+            // v(x^k / k!) >= k w - (k - 1) / (p - 1)
+            let bound = Integer::from(k - 1) / &p1;
+            if Integer::from(k as isize * w) - bound >= target {
+                break;
+            }
+            term = term * self.el((*x.v).clone()) / self.el(Integer::from(k));
+            sum += self.el((*term.v).clone());
+        }
+        sum + self.big_o(target)
+    }
+    /// `floor(log_p k)` for positive `k`
+    fn log_floor(&self, k: u32) -> isize {
+        let mut power = self.p.clone();
+        let mut ret = 0;
+        while power <= k {
+            power *= &self.p;
+            ret += 1;
+        }
+        ret
+    }
+    fn inverse_v(&self, a: &PadicValue) -> PadicValue {
+        assert!(a.unit != 0, "p-adic division by zero");
+        if a.precision.is_none() && *a.unit.as_abs() == 1 {
+            return PadicValue {
+                valuation: -a.valuation,
+                ..a.clone()
+            };
+        }
+        let digits = a.precision.map_or(self.precision, |k| {
+            u32::min((k - a.valuation) as u32, self.precision)
+        });
+        let modulus = self.p.clone().pow(digits);
+        PadicValue {
+            valuation: -a.valuation,
+            unit: a.unit.clone().invert(&modulus).unwrap(),
+            precision: Some(digits as isize - a.valuation),
+        }
+    }
+}
+
+impl SAdd for Padic {
+    fn zero(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(PadicValue::default())
+    }
+    fn add<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let (valuation, precision) = self.common(&lhs, &rhs);
+        Cow::Owned(self.normalized(PadicValue {
+            valuation,
+            unit: self.aligned(&lhs, valuation) + self.aligned(&rhs, valuation),
+            precision,
+        }))
+    }
+}
+impl SSub for Padic {
+    fn negate<'a>(&'a self, v: &mut Cow<'a, Self::V>) {
+        let mut neg = v.as_ref().clone();
+        neg.unit = -neg.unit;
+        *v = Cow::Owned(self.normalized(neg));
+    }
+    fn sub<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let (valuation, precision) = self.common(&lhs, &rhs);
+        Cow::Owned(self.normalized(PadicValue {
+            valuation,
+            unit: self.aligned(&lhs, valuation) - self.aligned(&rhs, valuation),
+            precision,
+        }))
+    }
+}
+impl SMul for Padic {
+    fn one(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(PadicValue::from(Integer::from(1)))
+    }
+    /// Known to the lower of `v_a + k_b` and `v_b + k_a`, reading the valuation of an inexact
+    /// zero `O(p^k)` as `k`
+    fn mul<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let exact_zero = |v: &PadicValue| v.unit == 0 && v.precision.is_none();
+        if exact_zero(&lhs) || exact_zero(&rhs) {
+            return self.zero();
+        }
+        let low = |v: &PadicValue| {
+            if v.unit == 0 {
+                v.precision.unwrap()
+            } else {
+                v.valuation
+            }
+        };
+        let (va, vb) = (low(&lhs), low(&rhs));
+        let precision = [lhs.precision.map(|k| k + vb), rhs.precision.map(|k| k + va)]
+            .into_iter()
+            .flatten()
+            .min();
+        Cow::Owned(self.normalized(PadicValue {
+            valuation: va + vb,
+            unit: lhs.into_owned().unit * &rhs.unit,
+            precision,
+        }))
+    }
+}
+impl SFusedMulAdd for Padic {}
+impl SDiv for Padic {
+    fn div<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let inv = self.inverse_v(&rhs);
+        self.mul(lhs, Cow::Owned(inv))
+    }
+}
+impl SField for Padic {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pseudorandom integers below `bound`
+    fn integers(count: usize, bound: u64, seed: u64) -> Vec<Integer> {
+        let mut state = seed;
+        (0..count)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                Integer::from((state >> 11) % bound)
+            })
+            .collect()
+    }
+
+    /// Checks that `a` and `b` agree up to `p^k` or beyond
+    fn assert_agree(qp: &Padic, a: &El<'_, Padic>, b: &El<'_, Padic>, k: isize) {
+        let d = a.copy() - b.copy();
+        assert!(qp.valuation(&d).is_none(), "{a} and {b} differ");
+        assert!(
+            qp.absolute_precision(&d).is_none_or(|j| j >= k),
+            "{a} and {b} agree only up to {d}"
+        );
+    }
+
+    #[test]
+    fn square_roots() {
+        for p in [2u32, 3, 5, 7, 1_000_003] {
+            let qp = Padic::new(Int.el(p), 12);
+            for (i, b) in integers(40, 1 << 40, u64::from(p)).into_iter().enumerate() {
+                // Exact squares, squares of rationals with and without powers of p, and inexact
+                // squares
+                let b = match i % 4 {
+                    0 => qp.el(b),
+                    1 => qp.from_rational(&Rational::from((b, 2 * p + 1))),
+                    2 => qp.from_rational(&Rational::from((b, Integer::from(p).pow(3u32)))),
+                    _ => qp.el(b) + qp.big_o(12),
+                };
+                if qp.valuation(&b).is_none() {
+                    continue;
+                }
+                let a = b.copy() * b.copy();
+                let r = qp.sqrt(&a).unwrap();
+                assert_eq!(qp.valuation(&r), qp.valuation(&b));
+                // One digit is lost for p = 2
+                let k = qp
+                    .absolute_precision(&a)
+                    .map_or(isize::MAX, |k| k - isize::from(p == 2));
+                assert_agree(&qp, &(r.copy() * r.copy()), &a, k);
+            }
+            assert!(qp.sqrt(&qp.el(Integer::from(p))).is_none());
+            let zero = qp.big_o(7);
+            assert_eq!(qp.sqrt(&zero).unwrap(), qp.big_o(4));
+        }
+        // Nonsquares modulo p, and units that are squares modulo 8 or not
+        let q7 = Padic::new(Int.el(7), 10);
+        assert!(q7.sqrt(&q7.el(Integer::from(3))).is_none());
+        let q2 = Padic::new(Int.el(2), 10);
+        for (a, square) in [(3, false), (5, false), (7, false), (17, true), (-7, true)] {
+            let a = q2.el(Integer::from(a)) + q2.big_o(10);
+            assert_eq!(q2.sqrt(&a).is_some(), square, "{a}");
+        }
+    }
+
+    #[test]
+    fn exponentials_and_logarithms() {
+        for p in [2u32, 3, 5, 7, 101] {
+            let qp = Padic::new(Int.el(p), 16);
+            // The disc of convergence of exp, and its image 1 + p Z_p or 1 + 4 Z_2
+            let q = if p == 2 { 4 } else { p };
+            let cs = integers(20, 1 << 30, u64::from(p));
+            for (i, c) in cs.iter().enumerate() {
+                let x = match i % 3 {
+                    0 => qp.el(Integer::from(c * q)),
+                    1 => qp.from_rational(&Rational::from((Integer::from(c * q), 2 * p + 1))),
+                    _ => qp.el(Integer::from(c * q)) + qp.big_o(12),
+                };
+                let a = qp.el(Integer::from(1)) + x.copy();
+                let log = qp.log(&a);
+                assert!(
+                    qp.valuation(&log)
+                        .is_none_or(|v| v >= qp.valuation(&x).unwrap())
+                );
+                let k = isize::min(qp.absolute_precision(&a).unwrap_or(16), 16) - 2;
+                assert_agree(&qp, &qp.exp(&log), &a, k);
+                if qp.valuation(&x).is_some() {
+                    assert_agree(&qp, &qp.log(&qp.exp(&x)), &x, k);
+                }
+                // log(a b) = log(a) + log(b)
+                let b = qp.el(Integer::from(1)) + qp.el(Integer::from(&cs[(i + 1) % 20] * q));
+                let lhs = qp.log(&(a.copy() * b.copy()));
+                assert_agree(&qp, &lhs, &(log + qp.log(&b)), k);
+            }
+            // Roots of unity have logarithm zero
+            let minus_one = qp.el(Integer::from(-1));
+            assert!(qp.valuation(&qp.log(&minus_one)).is_none());
+        }
+    }
+}
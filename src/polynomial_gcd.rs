@@ -0,0 +1,450 @@
+use crate::{
+    DensePolynomial, El, Int, Mod, SDiv, SFusedMulAdd, SGcd, SMul, SRem, SSub, Structure,
+    SuperStructure, crt::crt_integers,
+};
+use rug::{Complete, Integer};
+use std::{borrow::Cow, mem};
+
+impl<S: SFusedMulAdd + SSub> DensePolynomial<S> {
+    /// `(q, r)` with `lc(b)^(deg a - deg b + 1) a = q b + r` and `deg r < deg b`, by division
+    /// without leaving the coefficient ring
+    pub fn pseudo_div_rem<'a>(
+        &'a self,
+        a: &El<'_, Self>,
+        b: &El<'_, Self>,
+    ) -> (El<'a, Self>, El<'a, Self>) {
+        assert_eq!(self, a.s);
+        assert_eq!(self, b.s);
+        let s = self.inner();
+        let Some(lc) = b.v.last() else {
+            panic!("polynomial division by zero");
+        };
+        let mut rem = (*a.v).clone();
+        if rem.len() < b.v.len() {
+            return (self.el(Vec::new()), self.el(rem));
+        }
+        let mut quot = vec![s.zero().into_owned(); rem.len() - b.v.len() + 1];
+        for i in (0..quot.len()).rev() {
+            let top = mem::take(&mut rem[i + b.v.len() - 1]);
+            for c in rem.iter_mut().chain(&mut quot) {
+                *c = s
+                    .mul(Cow::Owned(mem::take(c)), Cow::Borrowed(lc))
+                    .into_owned();
+            }
+            for (j, d) in b.v.iter().enumerate().take(b.v.len() - 1) {
+                let prod = s.mul(Cow::Borrowed(&top), Cow::Borrowed(d));
+                rem[i + j] = s
+                    .sub(Cow::Owned(mem::take(&mut rem[i + j])), prod)
+                    .into_owned();
+            }
+            quot[i] = top;
+        }
+        rem.truncate(b.v.len() - 1);
+        (self.el(quot), self.el(rem))
+    }
+}
+
+impl<S: SFusedMulAdd + SDiv + SSub> DensePolynomial<S> {
+    /// The subresultant polynomial remainder sequence of `a` and `b`, `deg a >= deg b`: `a`, `b`,
+    /// then pseudo-remainders divided by the factors Collins' algorithm predicts, so that the
+    /// coefficients grow no faster than subresultant determinants, which the elements equal up
+    /// to sign. Ends at the last nonzero remainder.
+    pub fn subresultant_prs<'a>(&'a self, a: &El<'_, Self>, b: &El<'_, Self>) -> Vec<El<'a, Self>> {
+        assert_eq!(self, a.s);
+        assert_eq!(self, b.s);
+        assert!(a.v.len() >= b.v.len(), "prs needs deg a >= deg b");
+        let s = self.inner();
+        let mut seq = vec![self.el((*a.v).clone())];
+        if b.v.is_empty() {
+            return seq;
+        }
+        seq.push(self.el((*b.v).clone()));
+        let one = s.one().into_owned();
+        let (mut g, mut h) = (one.clone(), one);
+        loop {
+            let [prev, last] = &seq[seq.len() - 2..] else {
+                unreachable!()
+            };
+            let delta = prev.v.len() - last.v.len();
+            let (_, r) = self.pseudo_div_rem(prev, last);
+            if r.v.is_empty() {
+                return seq;
+            }
+            // r / (g h^delta)
+            let divisor = s.mul(Cow::Borrowed(&g), Cow::Owned(pow(s, &h, delta)));
+            let r = self.el(r
+                .v
+                .iter()
+                .map(|c| {
+                    s.div(Cow::Borrowed(c), Cow::Borrowed(&*divisor))
+                        .into_owned()
+                })
+                .collect::<Vec<_>>());
+            g = last.v.last().unwrap().clone();
+            // h^(1 - delta) g^delta
+            h = match delta {
+                0 => h,
+                _ => {
+                    let num = pow(s, &g, delta);
+                    s.div(Cow::Owned(num), Cow::Owned(pow(s, &h, delta - 1)))
+                        .into_owned()
+                }
+            };
+            seq.push(r);
+        }
+    }
+}
+
+/// `v^exp`
+pub(crate) fn pow<S: SMul>(s: &S, v: &S::V, exp: usize) -> S::V {
+    El {
+        v: Cow::Borrowed(v),
+        s,
+    }
+    .pow(&Integer::from(exp))
+    .v
+    .into_owned()
+}
+
+impl<S: SFusedMulAdd + SGcd> DensePolynomial<S> {
+    /// The normalized greatest common divisor of the coefficients; zero for the zero polynomial
+    pub fn content<'a>(&'a self, p: &El<'_, Self>) -> El<'a, S> {
+        assert_eq!(self, p.s);
+        let s = self.inner();
+        let mut g = s.zero().into_owned();
+        for c in p.v.iter() {
+            g = s.gcd(Cow::Owned(g), Cow::Borrowed(c)).into_owned();
+            if g == *s.one() {
+                break;
+            }
+        }
+        El {
+            v: Cow::Owned(g),
+            s,
+        }
+    }
+    /// `p` divided by its content
+    pub fn primitive_part<'a>(&'a self, p: &El<'_, Self>) -> El<'a, Self> {
+        let c = self.content(p);
+        if p.v.is_empty() {
+            return self.el(Vec::new());
+        }
+        self.div_coeffs(&p.v, &c.v)
+    }
+    /// The normalized greatest common divisor, by the primitive subresultant remainder sequence
+    pub fn gcd_subresultant<'a>(&'a self, a: &El<'_, Self>, b: &El<'_, Self>) -> El<'a, Self> {
+        assert_eq!(self, a.s);
+        assert_eq!(self, b.s);
+        let (a, b) = if a.v.len() >= b.v.len() {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        if b.v.is_empty() {
+            return self.normalized(self.el((*a.v).clone()));
+        }
+        let s = self.inner();
+        let (ca, cb) = (self.content(a), self.content(b));
+        let c = s.gcd(ca.v, cb.v).into_owned();
+        let (pa, pb) = (self.primitive_part(a), self.primitive_part(b));
+        let last = self.subresultant_prs(&pa, &pb).pop().unwrap();
+        let g = self.primitive_part(&last);
+        let g = self.mul(Cow::Owned(vec![c]), g.v);
+        self.normalized(self.el(g.into_owned()))
+    }
+    /// `p` divided by the unit part of its leading coefficient
+    pub(crate) fn normalized<'a>(&'a self, p: El<'_, Self>) -> El<'a, Self> {
+        let s = self.inner();
+        let u = match p.v.last() {
+            Some(lc) => s.unit_part(lc),
+            None => s.one().into_owned(),
+        };
+        self.div_coeffs(&p.v, &u)
+    }
+    pub(crate) fn div_coeffs<'a>(&'a self, p: &[S::V], c: &S::V) -> El<'a, Self> {
+        let s = self.inner();
+        self.el(p
+            .iter()
+            .map(|a| s.div(Cow::Borrowed(a), Cow::Borrowed(c)).into_owned())
+            .collect::<Vec<_>>())
+    }
+}
+
+impl DensePolynomial<Int> {
+    /// The normalized greatest common divisor, from monic greatest common divisors modulo
+    /// primes, scaled by the gcd of the leading coefficients and combined by CRT until the
+    /// combination stops changing and divides both operands, as in Brown's algorithm
+    pub fn gcd_modular<'a>(&'a self, a: &El<'_, Self>, b: &El<'_, Self>) -> El<'a, Self> {
+        assert_eq!(self, a.s);
+        assert_eq!(self, b.s);
+        if a.v.is_empty() || b.v.is_empty() {
+            return self.gcd_subresultant(a, b);
+        }
+        let c = Int.gcd(self.content(a).v, self.content(b).v).into_owned();
+        let (a, b) = (self.primitive_part(a), self.primitive_part(b));
+        let lc_gcd = Int
+            .gcd(
+                Cow::Borrowed(a.v.last().unwrap()),
+                Cow::Borrowed(b.v.last().unwrap()),
+            )
+            .into_owned();
+        let constant = || self.el(vec![c.clone()]);
+        let mut p = Integer::from(1) << MODULAR_GCD_PRIME_BITS;
+        // The combined image, its modulus, and its degree
+        let mut image: Option<(Vec<Integer>, Integer, usize)> = None;
+        loop {
+            p.next_prime_mut();
+            if lc_gcd.is_divisible(&p) {
+                continue;
+            }
+            let field = Mod::new_prime(Int.el(p.clone()));
+            let poly = DensePolynomial::new_symb("x", &field);
+            let reduce = |v: &[Integer]| {
+                poly.el(v
+                    .iter()
+                    .map(|x| x.div_rem_euc_ref(&p).complete().1)
+                    .collect::<Vec<_>>())
+            };
+            let g = monic_gcd(&poly, reduce(&a.v), reduce(&b.v));
+            let deg = g.len() - 1;
+            if deg == 0 {
+                return constant();
+            }
+            let scale = lc_gcd.div_rem_euc_ref(&p).complete().1;
+            let g: Vec<Integer> = g
+                .into_iter()
+                .map(|x| (x * &scale).div_rem_euc(p.clone()).1)
+                .collect();
+            let combined = match image.take() {
+                // Earlier primes were unlucky, or this one is
+                Some((h, m, d)) if d < deg => Some((h, m, d)),
+                Some((h, m, d)) if d == deg => {
+                    let half = (&m * &p).complete() >> 1u32;
+                    let next: Vec<Integer> = Iterator::zip(h.iter(), &g)
+                        .map(|(x, y)| {
+                            let (z, mp) =
+                                crt_integers([(x.clone(), m.clone()), (y.clone(), p.clone())])
+                                    .unwrap();
+                            if z > half { z - mp } else { z }
+                        })
+                        .collect();
+                    let stable = next == h;
+                    let m = m * &p;
+                    if stable {
+                        let candidate = self.primitive_part(&self.el(next.clone()));
+                        if [&a, &b]
+                            .iter()
+                            .all(|x| self.pseudo_div_rem(x, &candidate).1.v.is_empty())
+                        {
+                            let g = self.mul(Cow::Owned(vec![c.clone()]), candidate.v);
+                            return self.normalized(self.el(g.into_owned()));
+                        }
+                    }
+                    Some((next, m, d))
+                }
+                _ => {
+                    let half = (&p >> 1u32).complete();
+                    let h = g
+                        .into_iter()
+                        .map(|x| if x > half { x - &p } else { x })
+                        .collect();
+                    Some((h, p.clone(), deg))
+                }
+            };
+            image = combined;
+        }
+    }
+}
+
+/// The primes for modular greatest common divisors are the ones above `2^MODULAR_GCD_PRIME_BITS`
+const MODULAR_GCD_PRIME_BITS: u32 = 60;
+
+/// The monic greatest common divisor over a prime field, by Euclid's algorithm
+fn monic_gcd(
+    poly: &DensePolynomial<Mod<Int, true>>,
+    a: El<'_, DensePolynomial<Mod<Int, true>>>,
+    b: El<'_, DensePolynomial<Mod<Int, true>>>,
+) -> Vec<Integer> {
+    let (mut a, mut b) = (a.v.into_owned(), b.v.into_owned());
+    while !b.is_empty() {
+        let r = poly.rem(Cow::Owned(a), Cow::Borrowed(&b)).into_owned();
+        a = mem::replace(&mut b, r);
+    }
+    let field = poly.inner();
+    let lc = a.last().unwrap().clone();
+    a.into_iter()
+        .map(|x| field.div(Cow::Owned(x), Cow::Borrowed(&lc)).into_owned())
+        .collect()
+}
+
+/// Coefficients of polynomials with greatest common divisors, which `DensePolynomial` computes
+/// through `polynomial_gcd`
+pub trait SPolynomialGcd: SFusedMulAdd + SGcd {
+    /// The normalized greatest common divisor in `poly`: by primitive subresultant sequences,
+    /// unless the coefficients have a faster algorithm
+    fn polynomial_gcd<'a>(
+        poly: &'a DensePolynomial<Self>,
+        a: &El<'_, DensePolynomial<Self>>,
+        b: &El<'_, DensePolynomial<Self>>,
+    ) -> El<'a, DensePolynomial<Self>> {
+        poly.gcd_subresultant(a, b)
+    }
+}
+/// Multivariate polynomials, as polynomials over polynomials
+impl<S: SPolynomialGcd> SPolynomialGcd for DensePolynomial<S> {}
+
+/// Greatest common divisors by `SPolynomialGcd::polynomial_gcd` of the coefficients. The unit
+/// part is that of the leading coefficient.
+impl<S: SPolynomialGcd> SGcd for DensePolynomial<S> {
+    fn gcd<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let (a, b) = (self.el(lhs.into_owned()), self.el(rhs.into_owned()));
+        S::polynomial_gcd(self, &a, &b).v
+    }
+    fn unit_part(&self, v: &Self::V) -> Self::V {
+        let s = self.inner();
+        match v.last() {
+            Some(lc) => vec![s.unit_part(lc)],
+            None => self.one().into_owned(),
+        }
+    }
+    /// Only the constants have constant multiples
+    fn quotient_characteristic(&self, m: &Self::V) -> Integer {
+        match &m[..] {
+            [c] => self.inner().quotient_characteristic(c),
+            _ => self.inner().characteristic(),
+        }
+    }
+    /// `(sum a_i x^i)^p = sum a_i^p x^(i p)`, so a root needs exponents divisible by `p`
+    fn pth_root(&self, v: &Self::V) -> Option<Self::V> {
+        let s = self.inner();
+        let p = s.characteristic();
+        if p == 0 {
+            return None;
+        }
+        let p = p.to_usize().unwrap_or(usize::MAX);
+        let zero = s.zero();
+        if v.iter().enumerate().any(|(i, c)| i % p != 0 && *c != *zero) {
+            return None;
+        }
+        v.iter().step_by(p).map(|c| s.pth_root(c)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rug::{Rational, rand::RandState};
+
+    fn random_poly(rng: &mut RandState<'_>, len: u32) -> Vec<Integer> {
+        let len = Integer::from(Integer::random_below_ref(&Integer::from(len), rng)).to_usize();
+        (0..=len.unwrap())
+            .map(|_| Integer::from(Integer::random_below_ref(&Integer::from(41), rng)) - 20)
+            .collect()
+    }
+
+    /// The gcd by Euclid's algorithm over the rationals, scaled to a primitive integer
+    /// polynomial with positive leading coefficient and multiplied by the gcd of the contents
+    fn euclid_over_q(a: &[Integer], b: &[Integer]) -> Vec<Integer> {
+        let to_q = |v: &[Integer]| {
+            let mut v: Vec<Rational> = v.iter().map(Rational::from).collect();
+            while v.last().is_some_and(|c| *c == 0) {
+                v.pop();
+            }
+            v
+        };
+        let (mut a, mut b) = (to_q(a), to_q(b));
+        while !b.is_empty() {
+            while a.len() >= b.len() {
+                let c = Rational::from(a.last().unwrap() / b.last().unwrap());
+                let shift = a.len() - b.len();
+                for (i, d) in b.iter().enumerate() {
+                    a[shift + i] -= Rational::from(&c * d);
+                }
+                while a.last().is_some_and(|c| *c == 0) {
+                    a.pop();
+                }
+            }
+            mem::swap(&mut a, &mut b);
+        }
+        if a.is_empty() {
+            return Vec::new();
+        }
+        let den = a.iter().fold(Integer::from(1), |l, c| l.lcm(c.denom()));
+        let mut g: Vec<Integer> = a
+            .iter()
+            .map(|c| Rational::from(c * &den).into_numer_denom().0)
+            .collect();
+        let content = g.iter().fold(Integer::ZERO, |acc, c| acc.gcd(c));
+        let sign = if *g.last().unwrap() < 0 { -1 } else { 1 };
+        for c in &mut g {
+            *c = Integer::from(&*c / &content) * sign;
+        }
+        g
+    }
+
+    /// Random pairs, half of them with a planted common factor
+    fn pairs(seed: u32, count: usize) -> Vec<(Vec<Integer>, Vec<Integer>)> {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        let mut rng = RandState::new();
+        rng.seed(&Integer::from(seed));
+        (0..count)
+            .map(|i| {
+                let (a, b) = (random_poly(&mut rng, 7), random_poly(&mut rng, 7));
+                if i % 2 == 0 {
+                    return (a, b);
+                }
+                let g = zx.el(random_poly(&mut rng, 4));
+                let scale = zx.el(vec![Integer::from(i % 6 + 1)]);
+                (
+                    (g.copy() * zx.el(a) * scale.copy()).v.into_owned(),
+                    (g * zx.el(b) * scale).v.into_owned(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn integer_gcds_agree_with_euclid_over_q() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        for (a, b) in pairs(1, 200) {
+            let content = a.iter().chain(&b).fold(Integer::ZERO, |acc, c| acc.gcd(c));
+            let expected = zx.mul(Cow::Owned(vec![content]), Cow::Owned(euclid_over_q(&a, &b)));
+            let (a, b) = (zx.el(a), zx.el(b));
+            assert_eq!(zx.gcd_subresultant(&a, &b).v, expected);
+            assert_eq!(zx.gcd_modular(&a, &b).v, expected);
+            assert_eq!(a.gcd(&b).v, expected);
+        }
+    }
+
+    #[test]
+    fn bivariate_gcds_contain_planted_factors() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        let zxy = DensePolynomial::new_symb("y", &zx);
+        let mut rng = RandState::new();
+        rng.seed(&Integer::from(2));
+        let mut random = |len: u32| {
+            let len = Integer::from(Integer::random_below_ref(&Integer::from(len), &mut rng));
+            (0..=len.to_usize().unwrap())
+                .map(|_| random_poly(&mut rng, 3))
+                .collect::<Vec<_>>()
+        };
+        for _ in 0..30 {
+            let g = zxy.el(random(3));
+            if g.v.is_empty() {
+                continue;
+            }
+            let a = g.copy() * zxy.el(random(3));
+            let b = g.copy() * zxy.el(random(3));
+            let h = a.gcd(&b);
+            assert_eq!(zxy.gcd_subresultant(&a, &b), h);
+            if a.v.is_empty() || b.v.is_empty() {
+                continue;
+            }
+            // h is a multiple of g dividing both, with coprime cofactors
+            let divides = |d: &El<'_, _>, p: &El<'_, _>| zxy.pseudo_div_rem(p, d).1.v.is_empty();
+            assert!(divides(&g, &h) && divides(&h, &a) && divides(&h, &b));
+            let one = zxy.one().into_owned();
+            assert_eq!(*zxy.quo(&a, &h).gcd(&zxy.quo(&b, &h)).v, one);
+        }
+    }
+}
@@ -0,0 +1,392 @@
+use crate::{
+    DensePolynomial, El, SAdd, SDiv, SField, SFusedMulAdd, SMul, SSub, Structure, SuperStructure,
+    mul_karatsuba,
+};
+use std::{borrow::Cow, fmt};
+
+/// Power series `f + O(x^n)` in one variable, with the precision `n` fixed by the structure.
+/// Values are coefficient vectors as in `DensePolynomial`, cut off below `x^n`, so equal series
+/// have equal values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PowerSeries<S: Structure + SFusedMulAdd> {
+    precision: usize,
+    poly: DensePolynomial<S>,
+}
+impl<S: SFusedMulAdd> Structure for PowerSeries<S> {
+    type V = Vec<S::V>;
+    fn fmt_v(&self, coeffs: &Self::V, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let s = self.poly.inner();
+        let symbol = self.poly.symbol();
+        for (deg, coeff) in coeffs.iter().enumerate().filter(|(_, c)| *c != &*s.zero()) {
+            if deg == 0 || coeff != &*s.one() {
+                s.fmt_v(coeff, f)?
+            }
+            match deg {
+                0 => {}
+                1 => write!(f, "{symbol}")?,
+                _ => write!(f, "{symbol}^{deg}")?,
+            }
+            write!(f, " + ")?;
+        }
+        write!(f, "O({symbol}^{})", self.precision)
+    }
+    fn el<'a>(&'a self, v: impl Into<Self::V>) -> El<'a, Self> {
+        let mut v = v.into();
+        v.truncate(self.precision);
+        El {
+            v: self.poly.el(v).v,
+            s: self,
+        }
+    }
+}
+impl<S: SFusedMulAdd> std::fmt::Display for PowerSeries<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if f.alternate() {
+            write!(f, ", ")?;
+        }
+        let symbol = self.poly.symbol();
+        write!(
+            f,
+            "series {symbol} mod {symbol}^{}{:#}",
+            self.precision,
+            self.poly.inner()
+        )
+    }
+}
+/// Lifts polynomials by truncation, and coefficients through them with `lift2` and `el2`
+impl<S: SFusedMulAdd> SuperStructure for PowerSeries<S> {
+    type Inner = DensePolynomial<S>;
+    fn inner(&self) -> &Self::Inner {
+        &self.poly
+    }
+    fn lifted_from<'a>(&'a self, inner: El<'a, Self::Inner>) -> El<'a, Self> {
+        let El { v, s } = inner;
+        assert_eq!(&self.poly, s);
+        self.el(v.into_owned())
+    }
+}
+
+impl<S: SFusedMulAdd> PowerSeries<S> {
+    pub fn new_symb(symbol: impl AsRef<str>, inner: &S, precision: usize) -> Self {
+        assert!(precision > 0, "power series without precision");
+        Self {
+            precision,
+            poly: DensePolynomial::new_symb(symbol, inner),
+        }
+    }
+    /// The `n` in `O(x^n)`
+    pub fn precision(&self) -> usize {
+        self.precision
+    }
+    pub fn symb(&self) -> El<'_, Self> {
+        self.el(vec![
+            self.poly.inner().zero().into_owned(),
+            self.poly.inner().one().into_owned(),
+        ])
+    }
+    /// The coefficient of `x^k`, for `k` below the precision
+    pub fn coefficient<'a>(&'a self, a: &El<'_, Self>, k: usize) -> El<'a, S> {
+        assert!(k < self.precision, "coefficient beyond the precision");
+        match a.v.get(k) {
+            Some(c) => self.poly.inner().el(c.clone()),
+            None => El {
+                v: self.poly.inner().zero(),
+                s: self.poly.inner(),
+            },
+        }
+    }
+    /// The exponent of the lowest nonzero term; `None` when all known coefficients are zero
+    pub fn valuation(&self, a: &El<'_, Self>) -> Option<usize> {
+        a.v.iter().position(|c| *c != *self.poly.inner().zero())
+    }
+    /// The known terms as a polynomial
+    pub fn truncation<'a>(&'a self, a: &El<'_, Self>) -> El<'a, DensePolynomial<S>> {
+        assert_eq!(self, a.s);
+        self.poly.el((*a.v).clone())
+    }
+    /// `f(g)` for `g` without constant term, by splitting `f` in halves around the powers
+    /// `g^(2^k)` as for polynomials, with every product truncated
+    pub fn compose<'a>(&'a self, f: &El<'_, Self>, g: &El<'_, Self>) -> El<'a, Self> {
+        assert_eq!(self, f.s);
+        assert_eq!(self, g.s);
+        assert!(
+            self.valuation(g).is_none_or(|k| k > 0),
+            "composition with a series with constant term"
+        );
+        // powers[k] is g^(2^k)
+        let mut powers = vec![(*g.v).clone()];
+        while 2 << (powers.len() - 1) < f.v.len() {
+            let last = powers.last().unwrap();
+            let square = self.mul(Cow::Borrowed(last), Cow::Borrowed(last));
+            powers.push(square.into_owned());
+        }
+        self.el(self.compose_split(&f.v, &powers))
+    }
+    fn compose_split(&self, f: &[S::V], powers: &[Vec<S::V>]) -> Vec<S::V> {
+        if f.len() <= 1 {
+            return self.el(f.to_vec()).v.into_owned();
+        }
+        let k = (f.len() - 1).ilog2() as usize;
+        let (lo, hi) = f.split_at(1 << k);
+        let hi = self.compose_split(hi, &powers[..k]);
+        let hi = self.mul(Cow::Owned(hi), Cow::Borrowed(&powers[k]));
+        let lo = self.compose_split(lo, &powers[..k]);
+        self.add(Cow::Owned(lo), hi).into_owned()
+    }
+}
+
+/// The analytic functions divide by `1, 2, ..., n - 1` for the precision `n`, so they need
+/// coefficients like the rationals or a field of characteristic at least `n`
+impl<S: SFusedMulAdd + SField> PowerSeries<S> {
+    /// The multiplicative inverse of `f`, whose constant term must be nonzero, by Newton
+    /// iteration
+    pub fn inverse<'a>(&'a self, f: &El<'_, Self>) -> El<'a, Self> {
+        assert_eq!(self, f.s);
+        self.el(self.inverse_to(&f.v, self.precision))
+    }
+    /// `log f = integral(f' / f)` for `f` with constant term one
+    pub fn log<'a>(&'a self, f: &El<'_, Self>) -> El<'a, Self> {
+        assert_eq!(self, f.s);
+        self.assert_constant_one(f, "logarithm");
+        self.el(self.log_to(&f.v, self.precision))
+    }
+    /// `exp f` for `f` without constant term, by Newton iteration `g <- g (1 + f - log g)`,
+    /// which doubles the number of correct terms
+    pub fn exp<'a>(&'a self, f: &El<'_, Self>) -> El<'a, Self> {
+        assert_eq!(self, f.s);
+        assert!(
+            self.valuation(f).is_none_or(|k| k > 0),
+            "exponential of a series with constant term"
+        );
+        let p = &self.poly;
+        let one = p.el(p.one().into_owned());
+        let mut g = p.one().into_owned();
+        let mut k = 1;
+        while k < self.precision {
+            k = usize::min(2 * k, self.precision);
+            let fk = p.el(f.v[..usize::min(k, f.v.len())].to_vec());
+            let log_g = p.el(self.log_to(&g, k));
+            let correction = one.copy() + fk - log_g;
+            g = p.mul_low(&g, &correction.v, k);
+        }
+        self.el(g)
+    }
+    /// The square root with constant term one of `f` with constant term one, by Newton
+    /// iteration `g <- (g + f / g) / 2`
+    pub fn sqrt<'a>(&'a self, f: &El<'_, Self>) -> El<'a, Self> {
+        assert_eq!(self, f.s);
+        self.assert_constant_one(f, "square root");
+        let s = self.poly.inner();
+        let two = s.add(s.one(), s.one());
+        assert!(two != s.zero(), "square root in characteristic two");
+        let half = self.poly.el(vec![s.div(s.one(), two).into_owned()]);
+        let p = &self.poly;
+        let mut g = p.one().into_owned();
+        let mut k = 1;
+        while k < self.precision {
+            k = usize::min(2 * k, self.precision);
+            let quotient = p.el(p.mul_low(&f.v, &self.inverse_to(&g, k), k));
+            let step = (p.el(g) + quotient) * half.copy();
+            g = step.v.into_owned();
+        }
+        self.el(g)
+    }
+    /// `f^a = exp(a log f)` for `f` with constant term one and any coefficient `a`; integer
+    /// powers of any series are `El::pow`
+    pub fn pow<'a>(&'a self, f: &El<'_, Self>, a: &El<'_, S>) -> El<'a, Self> {
+        assert_eq!(self.poly.inner(), a.s);
+        let log = self.log(f);
+        let scaled = self.el(vec![(*a.v).clone()]) * log;
+        self.exp(&scaled)
+    }
+    fn assert_constant_one(&self, f: &El<'_, Self>, what: &str) {
+        assert!(
+            f.v.first() == Some(&*self.poly.inner().one()),
+            "{what} of a series without constant term one"
+        );
+    }
+    /// `1 / f mod x^k`
+    fn inverse_to(&self, f: &[S::V], k: usize) -> Vec<S::V> {
+        let s = self.poly.inner();
+        let c = f
+            .first()
+            .filter(|c| **c != *s.zero())
+            .expect("inverse of a series without constant term");
+        let c_inv = s.div(s.one(), Cow::Borrowed(c)).into_owned();
+        self.poly.inverse_series(f, k, c_inv)
+    }
+    /// `log f mod x^k`
+    fn log_to(&self, f: &[S::V], k: usize) -> Vec<S::V> {
+        let p = &self.poly;
+        let df = p.derivative(&p.el(f[..usize::min(k, f.len())].to_vec()));
+        let quotient = p.el(p.mul_low(&df.v, &self.inverse_to(f, k - 1), k - 1));
+        p.integral(&quotient).v.into_owned()
+    }
+}
+
+impl<S: SFusedMulAdd> SAdd for PowerSeries<S> {
+    fn zero(&self) -> Cow<'_, Self::V> {
+        self.poly.zero()
+    }
+    fn add<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        self.poly.add(lhs, rhs)
+    }
+}
+impl<S: SFusedMulAdd + SSub> SSub for PowerSeries<S> {
+    fn negate<'a>(&'a self, v: &mut Cow<'a, Self::V>) {
+        self.poly.negate(v)
+    }
+    fn sub<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        self.poly.sub(lhs, rhs)
+    }
+}
+impl<S: SFusedMulAdd> SMul for PowerSeries<S> {
+    fn one(&self) -> Cow<'_, Self::V> {
+        self.poly.one()
+    }
+    /// The short product, with only the terms below the precision
+    fn mul<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        Cow::Owned(self.poly.mul_low(&lhs, &rhs, self.precision))
+    }
+}
+impl<S: SFusedMulAdd + SSub> SFusedMulAdd for PowerSeries<S> {
+    /// `mul_karatsuba`, as series have no Kronecker substitution into one product
+    fn mul_coefficients(&self, lhs: &[Self::V], rhs: &[Self::V]) -> Vec<Self::V> {
+        mul_karatsuba(self, lhs, rhs)
+    }
+}
+/// Division by series with nonzero constant term, the units
+impl<S: SFusedMulAdd + SField> SDiv for PowerSeries<S> {
+    fn div<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        let inv = self.inverse_to(&rhs, self.precision);
+        self.mul(lhs, Cow::Owned(inv))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Int, Mod, Rat};
+    use rug::{Integer, Rational};
+
+    const P: u64 = 1_000_003;
+
+    fn series(ps: &PowerSeries<Mod<Int, true>>, v: &[i64]) -> Vec<Integer> {
+        let p = Integer::from(P);
+        let v: Vec<Integer> = v
+            .iter()
+            .map(|&c| Integer::from(c).div_rem_euc(p.clone()).1)
+            .collect();
+        ps.el(v).v.into_owned()
+    }
+
+    #[test]
+    fn products_are_truncated() {
+        let field = Mod::new_prime(Int.el(P));
+        for precision in [1, 5, 40, 100] {
+            let ps = PowerSeries::new_symb("x", &field, precision);
+            let poly = ps.inner();
+            let a: Vec<i64> = (0..80).map(|i| i * i + 1).collect();
+            let b: Vec<i64> = (0..70).map(|i| 3 * i - 2).collect();
+            let (a, b) = (series(&ps, &a), series(&ps, &b));
+            let full = poly.el(a.clone()) * poly.el(b.clone());
+            let product = ps.el(a) * ps.el(b);
+            assert_eq!(product, ps.el(full.v.into_owned()), "{precision}");
+            assert!(product.v.len() <= precision);
+        }
+    }
+
+    #[test]
+    fn inverse_and_division() {
+        let field = Mod::new_prime(Int.el(P));
+        let ps = PowerSeries::new_symb("x", &field, 100);
+        let f = ps.el(series(&ps, &(1..=60).collect::<Vec<_>>()));
+        let one = ps.el(ps.one().into_owned());
+        assert_eq!(f.copy() * ps.inverse(&f), one);
+        // 1 / (1 - x) = 1 + x + x^2 + ...
+        let geometric = ps.inverse(&ps.el(series(&ps, &[1, -1])));
+        assert_eq!(*geometric.v, vec![Integer::from(1); 100]);
+        let g = ps.el(series(&ps, &[5, 0, 7, 1]));
+        let quotient = ps.div(Cow::Borrowed(&*g.v), Cow::Borrowed(&*f.v));
+        assert_eq!(ps.el(quotient.into_owned()) * f, g);
+    }
+
+    #[test]
+    fn exp_log_and_sqrt() {
+        let field = Mod::new_prime(Int.el(P));
+        let ps = PowerSeries::new_symb("x", &field, 70);
+        // exp(x) has coefficients 1 / k!
+        let exp_x = ps.exp(&ps.symb());
+        let mut factorial = Integer::from(1);
+        for k in 0..70 {
+            if k > 0 {
+                factorial *= k;
+            }
+            let expected = factorial.clone().invert(&Integer::from(P)).unwrap();
+            assert_eq!(*ps.coefficient(&exp_x, k).v, expected);
+        }
+        let g = ps.el(series(&ps, &[0, 3, -1, 4, 1, -5, 9]));
+        let f = ps.exp(&g);
+        assert_eq!(ps.log(&f), g);
+        let sqrt = ps.sqrt(&f);
+        assert_eq!(sqrt.copy() * sqrt.copy(), f);
+        let third = ps
+            .inner()
+            .inner()
+            .el(Integer::from(3).invert(&Integer::from(P)).unwrap());
+        let cube_root = ps.pow(&f, &third);
+        assert_eq!(cube_root.pow(&Integer::from(3)), f);
+    }
+
+    #[test]
+    fn exact_coefficients_over_the_rationals() {
+        let ps = PowerSeries::new_symb("x", &Rat, 30);
+        let one_plus_x = ps.el(vec![Rational::from(1), Rational::from(1)]);
+        let (exp_x, log) = (ps.exp(&ps.symb()), ps.log(&one_plus_x));
+        let sqrt = ps.sqrt(&one_plus_x);
+        // exp(x) = sum x^k / k!, log(1 + x) = sum (-1)^(k + 1) x^k / k and the binomial series
+        // sqrt(1 + x) = sum binom(1/2, k) x^k
+        let (mut factorial, mut binomial) = (Integer::from(1), Rational::from(1));
+        for k in 0..30 {
+            if k > 0 {
+                factorial *= k;
+            }
+            assert_eq!(
+                *ps.coefficient(&exp_x, k).v,
+                Rational::from((1, factorial.clone()))
+            );
+            let expected = match k {
+                0 => Rational::new(),
+                _ => Rational::from((if k % 2 == 1 { 1 } else { -1 }, k)),
+            };
+            assert_eq!(*ps.coefficient(&log, k).v, expected, "{k}");
+            assert_eq!(*ps.coefficient(&sqrt, k).v, binomial, "{k}");
+            binomial *= Rational::from((1, 2)) - k;
+            binomial /= k + 1;
+        }
+        let g = ps.el(vec![
+            Rational::new(),
+            Rational::from((2, 3)),
+            Rational::from((-5, 7)),
+            Rational::from(4),
+        ]);
+        let f = ps.exp(&g);
+        assert_eq!(ps.log(&f), g);
+        let third = Rat.el(Rational::from((1, 3)));
+        assert_eq!(ps.pow(&f, &third).pow(&Integer::from(3)), f);
+    }
+
+    #[test]
+    fn composition() {
+        let field = Mod::new_prime(Int.el(P));
+        let ps = PowerSeries::new_symb("x", &field, 30);
+        let poly = ps.inner();
+        let f = series(&ps, &(0..25).map(|i| i % 7 - 3).collect::<Vec<_>>());
+        let g = series(&ps, &[0, 2, -1, 3]);
+        let expected = poly.compose(&poly.el(f.clone()), &poly.el(g.clone()));
+        assert_eq!(
+            ps.compose(&ps.el(f), &ps.el(g)),
+            ps.el(expected.v.into_owned())
+        );
+    }
+}
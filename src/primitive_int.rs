@@ -1,8 +1,11 @@
-use crate::{SAdd, SFusedMulAdd, SMul, SRem, SSub, Structure};
+use crate::{
+    SAdd, SDiv, SDivRem, SDivSmall, SFusedMulAdd, SInteger, SIntegerResidues, SMul, SRem, SSub,
+    Structure, mul_karatsuba, mul_toom3,
+};
 use std::{borrow::Cow, fmt};
 
 macro_rules! impl_for_primitive {
-    ($S:ident<$SW:literal>, $sign:ident, $V:ty) => {
+    ($S:ident<$SW:literal>, $sign:ident, $V:ty, $mul_coefficients:ident) => {
         impl Structure for $S<$SW> {
             type V = $V;
             fn fmt_v(&self, v: &Self::V, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
@@ -48,7 +51,55 @@ macro_rules! impl_for_primitive {
                 Cow::Owned(Self::V::rem_euclid(*lhs, *rhs))
             }
         }
-        impl SFusedMulAdd for $S<$SW> {}
+        impl SDiv for $S<$SW> {
+            fn div(&self, lhs: Cow<'_, Self::V>, rhs: Cow<'_, Self::V>) -> Cow<'_, Self::V> {
+                debug_assert_eq!(*lhs % *rhs, 0, "inexact division");
+                Cow::Owned(*lhs / *rhs)
+            }
+        }
+        impl SDivRem for $S<$SW> {
+            /// Returns nonnegative remainder
+            fn div_rem<'a>(
+                &'a self,
+                lhs: Cow<'a, Self::V>,
+                rhs: Cow<'a, Self::V>,
+            ) -> (Cow<'a, Self::V>, Cow<'a, Self::V>) {
+                (
+                    Cow::Owned(Self::V::div_euclid(*lhs, *rhs)),
+                    Cow::Owned(Self::V::rem_euclid(*lhs, *rhs)),
+                )
+            }
+        }
+        impl SFusedMulAdd for $S<$SW> {
+            fn mul_coefficients(&self, lhs: &[Self::V], rhs: &[Self::V]) -> Vec<Self::V> {
+                $mul_coefficients(self, lhs, rhs)
+            }
+        }
+        impl SIntegerResidues for $S<$SW> {
+            fn integer_modulus(&self) -> rug::Integer {
+                rug::Integer::ZERO
+            }
+            fn to_residue(&self, v: &Self::V) -> rug::Integer {
+                self.to_integer(v)
+            }
+            fn of_residue(&self, v: rug::Integer) -> Self::V {
+                self.of_integer(v)
+            }
+        }
+        impl SDivSmall for $S<$SW> {
+            fn div_small_exact(&self, v: &Self::V, k: u32) -> Option<Self::V> {
+                let k = Self::V::try_from(k).ok()?;
+                (*v % k == 0).then(|| *v / k)
+            }
+        }
+        impl SInteger for $S<$SW> {
+            fn to_integer(&self, v: &Self::V) -> rug::Integer {
+                rug::Integer::from(*v)
+            }
+            fn of_integer(&self, v: rug::Integer) -> Self::V {
+                Self::V::try_from(&v).expect("integer out of range")
+            }
+        }
     };
 }
 
@@ -58,14 +109,15 @@ pub struct I<const W: usize>;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct U<const W: usize>;
 
-impl_for_primitive!(I<8>, i, i8);
-impl_for_primitive!(I<16>, i, i16);
-impl_for_primitive!(I<32>, i, i32);
-impl_for_primitive!(I<64>, i, i64);
-impl_for_primitive!(I<128>, i, i128);
+impl_for_primitive!(I<8>, i, i8, mul_toom3);
+impl_for_primitive!(I<16>, i, i16, mul_toom3);
+impl_for_primitive!(I<32>, i, i32, mul_toom3);
+impl_for_primitive!(I<64>, i, i64, mul_toom3);
+impl_for_primitive!(I<128>, i, i128, mul_toom3);
 
-impl_for_primitive!(U<8>, u, u8);
-impl_for_primitive!(U<16>, u, u16);
-impl_for_primitive!(U<32>, u, u32);
-impl_for_primitive!(U<64>, u, u64);
-impl_for_primitive!(U<128>, u, u128);
+// Unsigned values cannot pass through the negative intermediates of Toom-3
+impl_for_primitive!(U<8>, u, u8, mul_karatsuba);
+impl_for_primitive!(U<16>, u, u16, mul_karatsuba);
+impl_for_primitive!(U<32>, u, u32, mul_karatsuba);
+impl_for_primitive!(U<64>, u, u64, mul_karatsuba);
+impl_for_primitive!(U<128>, u, u128, mul_karatsuba);
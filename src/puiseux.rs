@@ -0,0 +1,296 @@
+use crate::{
+    DensePolynomial, El, LaurentSeries, LaurentValue, SField, SFusedMulAdd, Structure,
+    SuperStructure,
+};
+use rug::Rational;
+
+impl<S: SFusedMulAdd + SField> LaurentSeries<S> {
+    /// The Puiseux expansions of the roots `y(x)` of the nonzero `f(x, y)`, given as a
+    /// polynomial in `y` over polynomials in `x`. Each is `(e, g)` for the cycle of `e` conjugate
+    /// roots `y = g(x^(1/e))`, with `g` in this structure's variable and to its precision, or
+    /// exact when the expansion terminates. Roots of repeated factors appear once, as the
+    /// expansions are those of the square-free part in `y`.
+    ///
+    /// By Newton polygons: each edge of slope `-m/q` of the lower convex hull of the points
+    /// `(j, ord_x a_j)` for `f = sum a_j y^j` gives roots `y = x^(m/q) (c + ...)`, with `c^q` a
+    /// root of the polynomial read off the edge, and substituting `x^q` for `x` and
+    /// `x^m (c + y)` for `y` leaves an equation for the rest. `roots` gives the distinct roots
+    /// in the coefficients of a polynomial; expansions needing other roots are left out.
+    pub fn puiseux_expansions<'a>(
+        &'a self,
+        f: &El<'_, DensePolynomial<DensePolynomial<S>>>,
+        mut roots: impl for<'p> FnMut(&El<'p, DensePolynomial<S>>) -> Vec<El<'p, S>>,
+    ) -> Vec<(u32, El<'a, Self>)> {
+        let bivariate = f.s;
+        assert_eq!(self.inner().inner(), bivariate.inner().inner());
+        assert!(!bivariate.is_zero(f), "puiseux expansions of zero");
+        let s = self.inner().inner();
+        // Without repeated roots every expansion eventually parts from the others
+        let f = match bivariate.derivative(f) {
+            df if bivariate.is_zero(&df) => bivariate.el((*f.v).clone()),
+            df => {
+                let gcd = bivariate.subresultant_prs(f, &df).pop().unwrap();
+                bivariate.pseudo_div_rem(f, &gcd).0
+            }
+        };
+        let mut ret = Vec::new();
+        // (g, [(exponent in x, coefficient)], ramification) with the rest of the root a root of
+        // g of positive valuation in x^(1/ramification), once there are terms
+        let mut stack = vec![(f, Vec::new(), 1u32)];
+        while let Some((g, terms, e)) = stack.pop() {
+            let zero_root = g.v[0].is_empty();
+            let edges: Vec<_> = lower_hull(&g.v)
+                .into_iter()
+                .filter(|((_, i1), (_, i2))| terms.is_empty() || i2 < i1)
+                .collect();
+            // The roots left in this branch, counted with multiplicity
+            let left = usize::from(zero_root)
+                + edges
+                    .iter()
+                    .map(|((j1, _), (j2, _))| (j2 - j1) as usize)
+                    .sum::<usize>();
+            // A single root left is simple, so later terms can neither ramify nor part, and the
+            // expansion can stop at the precision
+            if let Some((first, _)) = terms.first()
+                && left <= 1
+            {
+                let (last, _) = terms.last().unwrap();
+                let known = t_exponent(last, e) + 1 - t_exponent(first, e);
+                if known >= self.precision() as isize {
+                    let precision = (!zero_root).then_some(known);
+                    ret.push((e, self.expansion(&terms, e, precision)));
+                    continue;
+                }
+            }
+            if zero_root {
+                ret.push((e, self.expansion(&terms, e, None)));
+            }
+            for ((j1, i1), (j2, i2)) in edges {
+                let slope = Rational::from((i1 - i2, j2 - j1));
+                let (m, q) = (
+                    slope.numer().to_i64().unwrap(),
+                    slope.denom().to_i64().unwrap(),
+                );
+                let beta = q * i1 + m * j1;
+                // The terms on the edge, as a polynomial in c^q
+                let mut psi = vec![S::V::default(); ((j2 - j1) / q + 1) as usize];
+                for (j, a) in g.v.iter().enumerate() {
+                    let j = j as i64;
+                    if let Some(i) = lowest(a)
+                        && q * i as i64 + m * j == beta
+                    {
+                        psi[((j - j1) / q) as usize] = a[i].clone();
+                    }
+                }
+                for zeta in roots(&self.inner().el(psi)) {
+                    let c = if q == 1 {
+                        zeta.v.into_owned()
+                    } else {
+                        // c^q = zeta
+                        let mut binomial = vec![S::V::default(); q as usize + 1];
+                        let mut neg = zeta.v;
+                        s.negate(&mut neg);
+                        binomial[0] = neg.into_owned();
+                        binomial[q as usize] = s.one().into_owned();
+                        match roots(&self.inner().el(binomial)).into_iter().next() {
+                            Some(c) => c.v.into_owned(),
+                            None => continue,
+                        }
+                    };
+                    // x^(-beta) g(x^q, x^m y), then y -> c + y
+                    let substituted = bivariate.el(substituted(&g.v, q, m, beta));
+                    let shift = bivariate.inner().el(vec![c.clone()]);
+                    let next = bivariate.taylor_shift(&substituted, &shift);
+                    let exponent = match terms.last() {
+                        Some((last, _)) => {
+                            Rational::from(last + &Rational::from((m, e as i64 * q)))
+                        }
+                        None => Rational::from((m, q)),
+                    };
+                    let mut terms = terms.clone();
+                    terms.push((exponent, c));
+                    stack.push((next, terms, e * q as u32));
+                }
+            }
+        }
+        ret
+    }
+    /// `sum c t^(k e) + O(t^precision)` for the terms `(k, c)`
+    fn expansion<'a>(
+        &'a self,
+        terms: &[(Rational, S::V)],
+        e: u32,
+        precision: Option<isize>,
+    ) -> El<'a, Self> {
+        let Some((first, _)) = terms.first() else {
+            return self.el(LaurentValue::default());
+        };
+        let valuation = t_exponent(first, e);
+        let mut coeffs = Vec::new();
+        for (k, c) in terms {
+            coeffs.resize((t_exponent(k, e) - valuation) as usize, S::V::default());
+            coeffs.push(c.clone());
+        }
+        let ret = self.el(coeffs) * self.monomial(valuation);
+        match precision {
+            Some(known) => ret + self.big_o(valuation + known),
+            None => ret,
+        }
+    }
+}
+
+/// The exponent `k e` of `x^k` as a power of `t = x^(1/e)`
+fn t_exponent(k: &Rational, e: u32) -> isize {
+    let scaled = Rational::from(k * e);
+    assert!(*scaled.denom() == 1, "exponent outside the ramification");
+    scaled.numer().to_isize().unwrap()
+}
+
+/// `x^(-beta) g(x^q, x^m y)` for `g = sum a_j y^j`, which has no negative powers of `x` when no
+/// point `(j, ord a_j)` lies below the line `q i + m j = beta`
+fn substituted<V: Default + Clone + PartialEq>(
+    g: &[Vec<V>],
+    q: i64,
+    m: i64,
+    beta: i64,
+) -> Vec<Vec<V>> {
+    let mut ret = Vec::with_capacity(g.len());
+    for (j, a) in g.iter().enumerate() {
+        let mut b = Vec::new();
+        for (i, c) in a.iter().enumerate().filter(|(_, c)| **c != V::default()) {
+            let k = usize::try_from(q * i as i64 + m * j as i64 - beta).unwrap();
+            if b.len() <= k {
+                b.resize(k + 1, V::default());
+            }
+            b[k] = c.clone();
+        }
+        ret.push(b);
+    }
+    ret
+}
+
+/// The index of the lowest nonzero coefficient
+fn lowest<V: Default + PartialEq>(a: &[V]) -> Option<usize> {
+    a.iter().position(|c| *c != V::default())
+}
+
+/// The edges of the lower convex hull of the points `(j, ord a_j)`, from left to right
+fn lower_hull<V: Default + PartialEq>(f: &[Vec<V>]) -> Vec<((i64, i64), (i64, i64))> {
+    let points = f
+        .iter()
+        .enumerate()
+        .filter_map(|(j, a)| Some((j as i64, lowest(a)? as i64)));
+    let mut hull: Vec<(i64, i64)> = Vec::new();
+    for p in points {
+        while let [.., o, a] = hull[..]
+            && (a.0 - o.0) * (p.1 - o.1) - (a.1 - o.1) * (p.0 - o.0) <= 0
+        {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    hull.windows(2).map(|w| (w[0], w[1])).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Int, Mod};
+    use rug::{Integer, rand::RandState};
+
+    const P: u64 = 1_000_003;
+
+    /// `f(x, y)` from the coefficients of `x^i y^j` at `[j][i]`
+    fn bivariate<'a>(
+        xy: &'a DensePolynomial<DensePolynomial<Mod<Int, true>>>,
+        f: &[&[i64]],
+    ) -> El<'a, DensePolynomial<DensePolynomial<Mod<Int, true>>>> {
+        let p = Integer::from(P);
+        let reduced = |a: &[i64]| -> Vec<Integer> {
+            a.iter()
+                .map(|&c| Integer::from(c).div_rem_euc(p.clone()).1)
+                .collect()
+        };
+        xy.el(f
+            .iter()
+            .map(|a| xy.inner().el(reduced(a)).v.into_owned())
+            .collect::<Vec<_>>())
+    }
+
+    /// The expansions of `f` with the roots in `F_P`
+    fn expansions<'a>(
+        ls: &'a LaurentSeries<Mod<Int, true>>,
+        f: &El<'_, DensePolynomial<DensePolynomial<Mod<Int, true>>>>,
+    ) -> Vec<(u32, El<'a, LaurentSeries<Mod<Int, true>>>)> {
+        let mut rng = RandState::new();
+        ls.puiseux_expansions(f, |g| {
+            g.s.roots(g, &mut rng).into_iter().map(|(r, _)| r).collect()
+        })
+    }
+
+    fn setup() -> (
+        DensePolynomial<DensePolynomial<Mod<Int, true>>>,
+        Mod<Int, true>,
+    ) {
+        let field = Mod::new_prime(Int.el(P));
+        let xy = DensePolynomial::new_symb("y", &DensePolynomial::new_symb("x", &field));
+        (xy, field)
+    }
+
+    #[test]
+    fn ramified_cusp() {
+        let (xy, field) = setup();
+        // (y - x)^2 - x^3, with the roots x ± x^(3/2) sharing their first term
+        let f = bivariate(&xy, &[&[0, 0, 1, -1], &[0, -2], &[1]]);
+        for precision in [1, 2, 10] {
+            let ls = LaurentSeries::new_symb("t", &field, precision);
+            let found = expansions(&ls, &f);
+            assert_eq!(found.len(), 1, "{precision}");
+            let (e, g) = &found[0];
+            assert_eq!(*e, 2);
+            // t^2 + t^3 with t = x^(1/2), exactly
+            let expected = ls.monomial(2) + ls.monomial(3);
+            assert_eq!(*g, expected, "{precision}");
+        }
+    }
+
+    #[test]
+    fn smooth_branch() {
+        let (xy, field) = setup();
+        // (1 - x) y - x, with the root x / (1 - x) = x + x^2 + ...
+        let f = bivariate(&xy, &[&[0, -1], &[1, -1]]);
+        for precision in [1, 4, 20] {
+            let ls = LaurentSeries::new_symb("t", &field, precision);
+            let found = expansions(&ls, &f);
+            assert_eq!(found.len(), 1);
+            let (e, g) = &found[0];
+            assert_eq!(*e, 1);
+            assert_eq!(ls.valuation(g), Some(1));
+            let known = ls.absolute_precision(g).unwrap();
+            assert!(known > precision as isize, "{precision}");
+            for k in 1..known {
+                assert_eq!(*ls.coefficient(g, k).v, 1);
+            }
+        }
+    }
+
+    #[test]
+    fn ramification_indices_sum_to_the_degree() {
+        let (xy, field) = setup();
+        let ls = LaurentSeries::new_symb("t", &field, 6);
+        let poly = |f: &[&[i64]]| bivariate(&xy, f);
+        // (y^2 - x^3) (y - 1 - x) (y^2 - x) y
+        let f = poly(&[&[0, 0, 0, -1]]) + poly(&[&[], &[], &[1]]);
+        let f = f * poly(&[&[-1, -1], &[1]]);
+        let f = f * (poly(&[&[0, -1]]) + poly(&[&[], &[], &[1]]));
+        let f = f * poly(&[&[], &[1]]);
+        let found = expansions(&ls, &f);
+        let mut indices: Vec<u32> = found.iter().map(|(e, _)| *e).collect();
+        indices.sort();
+        assert_eq!(indices, [1, 1, 2, 2]);
+        assert_eq!(indices.iter().sum::<u32>() as usize, xy.degree(&f).unwrap());
+        // Repeated factors count once
+        let squared = f.copy() * f.copy();
+        assert_eq!(expansions(&ls, &squared).len(), found.len());
+    }
+}
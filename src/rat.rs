@@ -0,0 +1,201 @@
+use crate::{
+    DensePolynomial, El, Int, SAdd, SDiv, SField, SFusedMulAdd, SGcd, SMul, SPolynomialGcd, SSub,
+    Structure, SuperStructure,
+};
+use rug::{Integer, Rational};
+use std::{borrow::Cow, fmt};
+
+/// The rational numbers, exactly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rat;
+impl std::fmt::Display for Rat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if f.alternate() {
+            write!(f, ", ")?;
+        }
+        write!(f, "rat")
+    }
+}
+
+impl Structure for Rat {
+    type V = Rational;
+    fn fmt_v(&self, v: &Self::V, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{v}")
+    }
+}
+/// Lifts integers as fractions with denominator one
+impl SuperStructure for Rat {
+    type Inner = Int;
+    fn inner(&self) -> &Self::Inner {
+        &Int
+    }
+    fn lifted_from<'a>(&'a self, inner: El<'a, Self::Inner>) -> El<'a, Self> {
+        El {
+            v: Cow::Owned(Rational::from(inner.v.into_owned())),
+            s: self,
+        }
+    }
+}
+
+impl SAdd for Rat {
+    fn zero(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(Rational::new())
+    }
+    fn add(&self, lhs: Cow<'_, Self::V>, rhs: Cow<'_, Self::V>) -> Cow<'_, Self::V> {
+        Cow::Owned(Rational::from(&*lhs + &*rhs))
+    }
+}
+impl SSub for Rat {
+    fn negate(&self, v: &mut Cow<'_, Self::V>) {
+        let v: &mut Self::V = v.to_mut();
+        *v = Rational::from(-&*v);
+    }
+    fn sub(&self, lhs: Cow<'_, Self::V>, rhs: Cow<'_, Self::V>) -> Cow<'_, Self::V> {
+        Cow::Owned(Rational::from(&*lhs - &*rhs))
+    }
+}
+impl SMul for Rat {
+    fn one(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(Rational::from(1))
+    }
+    fn mul(&self, lhs: Cow<'_, Self::V>, rhs: Cow<'_, Self::V>) -> Cow<'_, Self::V> {
+        Cow::Owned(Rational::from(&*lhs * &*rhs))
+    }
+}
+impl SDiv for Rat {
+    fn div(&self, lhs: Cow<'_, Self::V>, rhs: Cow<'_, Self::V>) -> Cow<'_, Self::V> {
+        Cow::Owned(Rational::from(&*lhs / &*rhs))
+    }
+}
+impl SField for Rat {}
+/// Greatest common divisors in a field: one unless both are zero
+impl SGcd for Rat {
+    fn gcd(&self, lhs: Cow<'_, Self::V>, rhs: Cow<'_, Self::V>) -> Cow<'_, Self::V> {
+        if *lhs == 0 && *rhs == 0 {
+            self.zero()
+        } else {
+            self.one()
+        }
+    }
+    fn unit_part(&self, v: &Self::V) -> Self::V {
+        if *v == 0 {
+            self.one().into_owned()
+        } else {
+            v.clone()
+        }
+    }
+    fn quotient_characteristic(&self, m: &Self::V) -> Integer {
+        Integer::from(*m != 0)
+    }
+    fn pth_root(&self, _v: &Self::V) -> Option<Self::V> {
+        None
+    }
+}
+/// By the modular algorithm on the integer multiples of the operands, made monic
+impl SPolynomialGcd for Rat {
+    fn polynomial_gcd<'a>(
+        poly: &'a DensePolynomial<Self>,
+        a: &El<'_, DensePolynomial<Self>>,
+        b: &El<'_, DensePolynomial<Self>>,
+    ) -> El<'a, DensePolynomial<Self>> {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        let (a, b) = (zx.el(integers(&a.v).0), zx.el(integers(&b.v).0));
+        let g = zx.gcd_modular(&a, &b);
+        let Some(lc) = g.v.last() else {
+            return poly.el(Vec::new());
+        };
+        poly.el(g
+            .v
+            .iter()
+            .map(|c| Rational::from((c.clone(), lc.clone())))
+            .collect::<Vec<_>>())
+    }
+}
+impl SFusedMulAdd for Rat {
+    fn fused_mul_add_ref(&self, acc: &mut Self::V, lhs: &Self::V, rhs: &Self::V) {
+        *acc += Rational::from(lhs * rhs);
+    }
+    /// Through `Int` on the numerators over common denominators
+    fn mul_coefficients(&self, lhs: &[Self::V], rhs: &[Self::V]) -> Vec<Self::V> {
+        let (lhs, lhs_den) = integers(lhs);
+        let (rhs, rhs_den) = integers(rhs);
+        let den = lhs_den * rhs_den;
+        Int.mul_coefficients(&lhs, &rhs)
+            .into_iter()
+            .map(|c| Rational::from((c, den.clone())))
+            .collect()
+    }
+}
+
+/// `(n, d)` with `v = n / d` for `d` the least common multiple of the denominators
+fn integers(v: &[Rational]) -> (Vec<Integer>, Integer) {
+    let den = v.iter().fold(Integer::from(1), |acc, c| acc.lcm(c.denom()));
+    let num = v
+        .iter()
+        .map(|c| Integer::from(&den / c.denom()) * c.numer())
+        .collect();
+    (num, den)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SRem;
+
+    /// Pseudorandom fractions with numerators in `[-100, 100]` and denominators in `[1, 30]`
+    fn fractions(len: usize, seed: u64) -> Vec<Rational> {
+        let mut state = seed;
+        let mut next = move |bound: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) % bound) as i64
+        };
+        (0..len)
+            .map(|_| Rational::from((next(201) - 100, next(30) + 1)))
+            .collect()
+    }
+
+    #[test]
+    fn products_agree_with_schoolbook() {
+        let qx = DensePolynomial::new_symb("x", &Rat);
+        for (n, m) in [(1, 1), (3, 7), (40, 40), (150, 90)] {
+            let (a, b) = (fractions(n, n as u64), fractions(m, 1000 + m as u64));
+            let mut expected = vec![Rational::new(); n + m - 1];
+            for (i, x) in a.iter().enumerate() {
+                for (j, y) in b.iter().enumerate() {
+                    expected[i + j] += Rational::from(x * y);
+                }
+            }
+            assert_eq!(qx.el(a) * qx.el(b), qx.el(expected), "{n} {m}");
+        }
+    }
+
+    #[test]
+    fn polynomial_gcds_are_monic_common_divisors() {
+        let qx = DensePolynomial::new_symb("x", &Rat);
+        for seed in 0..20 {
+            let g = qx.el(fractions(1 + seed % 4, seed as u64));
+            let a = g.copy() * qx.el(fractions(5, 100 + seed as u64));
+            let b = g.copy() * qx.el(fractions(1 + seed % 6, 200 + seed as u64));
+            let h = a.gcd(&b);
+            let Some(lc) = h.v.last() else {
+                assert!(a.v.is_empty() && b.v.is_empty());
+                continue;
+            };
+            assert_eq!(*lc, 1);
+            // Euclid's algorithm ends at a multiple of the same gcd
+            let (mut x, mut y) = ((*a.v).clone(), (*b.v).clone());
+            while !y.is_empty() {
+                let r = qx.rem(Cow::Borrowed(&x), Cow::Borrowed(&y)).into_owned();
+                x = std::mem::replace(&mut y, r);
+            }
+            assert_eq!(qx.normalized(qx.el(x)), h);
+            assert!(qx.rem(Cow::Borrowed(&g.v), Cow::Borrowed(&h.v)).is_empty());
+        }
+        let zero = qx.el(Vec::new());
+        assert_eq!(zero.gcd(&zero), zero);
+        let p = qx.el(fractions(4, 7));
+        assert_eq!(p.gcd(&zero), qx.normalized(p.copy()));
+    }
+}
@@ -0,0 +1,242 @@
+use crate::{El, Int, SAdd, SDiv, SField, SFusedMulAdd, SMul, SSub, Structure, SuperStructure};
+use rug::{
+    Float, Rational,
+    float::{Round, prec_min},
+    ops::AssignRound,
+};
+use std::{borrow::Cow, cmp::Ordering, fmt};
+
+/// Binary floating-point numbers with `prec` bits of mantissa, by MPFR. Every operation rounds
+/// its exact result once, in the structure's rounding mode. A field only approximately: sums and
+/// products are not associative.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Real {
+    prec: u32,
+    round: Round,
+}
+/// A `Float` as a structure value, in which NaN equals itself so that equality is reflexive. The
+/// default is a zero of the least precision.
+#[derive(Clone, Debug)]
+pub struct RealValue(pub Float);
+impl Default for RealValue {
+    fn default() -> Self {
+        Self(Float::new(prec_min()))
+    }
+}
+impl PartialEq for RealValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0 || self.0.is_nan() && other.0.is_nan()
+    }
+}
+impl Eq for RealValue {}
+impl From<Float> for RealValue {
+    fn from(v: Float) -> Self {
+        Self(v)
+    }
+}
+impl Structure for Real {
+    type V = RealValue;
+    fn fmt_v(&self, v: &Self::V, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", v.0)
+    }
+    /// Rounds `v` to the structure's precision
+    fn el<'a>(&'a self, v: impl Into<Self::V>) -> El<'a, Self> {
+        El {
+            v: Cow::Owned(self.rounded(v.into().0)),
+            s: self,
+        }
+    }
+}
+impl std::fmt::Display for Real {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if f.alternate() {
+            write!(f, ", ")?;
+        }
+        write!(f, "real {}", self.prec)?;
+        if self.round != Round::Nearest {
+            write!(f, " round {}", format!("{:?}", self.round).to_lowercase())?;
+        }
+        Ok(())
+    }
+}
+/// Rounds integers to the precision
+impl SuperStructure for Real {
+    type Inner = Int;
+    fn inner(&self) -> &Self::Inner {
+        &Int
+    }
+    fn lifted_from<'a>(&'a self, inner: El<'a, Self::Inner>) -> El<'a, Self> {
+        El {
+            v: Cow::Owned(self.value(&*inner.v)),
+            s: self,
+        }
+    }
+}
+
+impl Real {
+    /// Rounding to nearest
+    pub fn new(prec: u32) -> Self {
+        Self::with_round(prec, Round::Nearest)
+    }
+    pub fn with_round(prec: u32, round: Round) -> Self {
+        assert!(prec >= prec_min(), "precision below the mpfr minimum");
+        Self { prec, round }
+    }
+    pub fn prec(&self) -> u32 {
+        self.prec
+    }
+    pub fn round(&self) -> Round {
+        self.round
+    }
+    /// `q` rounded to the precision
+    pub fn from_rational(&self, q: &Rational) -> El<'_, Self> {
+        El {
+            v: Cow::Owned(self.value(q)),
+            s: self,
+        }
+    }
+    /// `v` at the structure's precision, rounded once
+    pub(crate) fn value<T>(&self, v: T) -> RealValue
+    where
+        Float: AssignRound<T, Round = Round, Ordering = Ordering>,
+    {
+        RealValue(Float::with_val_round(self.prec, v, self.round).0)
+    }
+    fn rounded(&self, v: Float) -> RealValue {
+        if v.prec() == self.prec {
+            RealValue(v)
+        } else {
+            self.value(v)
+        }
+    }
+}
+
+impl SAdd for Real {
+    fn zero(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(RealValue(Float::new(self.prec)))
+    }
+    fn add<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        Cow::Owned(self.value(&lhs.0 + &rhs.0))
+    }
+}
+impl SSub for Real {
+    fn negate<'a>(&'a self, v: &mut Cow<'a, Self::V>) {
+        *v = Cow::Owned(self.value(-&v.0));
+    }
+    fn sub<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        Cow::Owned(self.value(&lhs.0 - &rhs.0))
+    }
+}
+impl SMul for Real {
+    fn one(&self) -> Cow<'_, Self::V> {
+        Cow::Owned(self.value(1))
+    }
+    fn mul<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        Cow::Owned(self.value(&lhs.0 * &rhs.0))
+    }
+}
+/// With the default `mul_coefficients`, so that polynomial products stay with the schoolbook
+/// method and its single-rounding fused multiply-adds rather than cancelling in Karatsuba
+impl SFusedMulAdd for Real {
+    fn fused_mul_add_ref(&self, acc: &mut Self::V, lhs: &Self::V, rhs: &Self::V) {
+        *acc = self.value(lhs.0.mul_add_ref(&rhs.0, &acc.0));
+    }
+}
+impl SDiv for Real {
+    fn div<'a>(&'a self, lhs: Cow<'a, Self::V>, rhs: Cow<'a, Self::V>) -> Cow<'a, Self::V> {
+        Cow::Owned(self.value(&lhs.0 / &rhs.0))
+    }
+}
+impl SField for Real {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pseudorandom rationals with numerators and denominators below `2^30`
+    fn rationals(count: usize, seed: u64) -> Vec<Rational> {
+        let mut state = seed;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 34) as i64
+        };
+        (0..count)
+            .map(|_| Rational::from((next() - (1 << 29), next() + 1)))
+            .collect()
+    }
+
+    const MODES: [Round; 4] = [Round::Nearest, Round::Zero, Round::Up, Round::Down];
+
+    #[test]
+    fn fused_multiply_adds_round_once() {
+        // (1 + 2^-23) (1 - 2^-23) - 1 = -2^-46, which separate roundings lose
+        let r = Real::new(24);
+        let eps = Rational::from((1, 1 << 23));
+        let a = r.from_rational(&(Rational::from(1) + &eps));
+        let b = r.from_rational(&(Rational::from(1) - &eps));
+        let mut acc = r.from_rational(&Rational::from(-1)).v.into_owned();
+        assert_eq!(*(a.copy() * b.copy() + r.el(acc.clone())).v, *r.zero());
+        r.fused_mul_add_ref(&mut acc, &a.v, &b.v);
+        assert_eq!(acc.0, Rational::from((-1, 1u64 << 46)));
+        for round in MODES {
+            let r = Real::with_round(30, round);
+            let qs = rationals(60, round as u64);
+            for abc in qs.chunks(3) {
+                let [a, b, c] = abc else { unreachable!() };
+                let (a, b, c) = (r.from_rational(a), r.from_rational(b), r.from_rational(c));
+                let exact = a.v.0.to_rational().unwrap() * b.v.0.to_rational().unwrap()
+                    + c.v.0.to_rational().unwrap();
+                let mut acc = c.v.into_owned();
+                r.fused_mul_add_ref(&mut acc, &a.v, &b.v);
+                assert_eq!(acc, r.from_rational(&exact).v.into_owned(), "{round:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn rounding_modes() {
+        let third = Rational::from((1, 3));
+        let [near, zero, up, down] = MODES.map(|round| {
+            Real::with_round(53, round)
+                .from_rational(&third)
+                .v
+                .0
+                .clone()
+        });
+        assert!(down < third && third < up);
+        assert_eq!(zero, down);
+        assert!(near == down || near == up);
+        let minus = -third.clone();
+        let r = Real::with_round(53, Round::Zero);
+        assert_eq!(r.from_rational(&minus).v.0, -down.clone());
+        // Every operation rounds in the mode of the structure
+        for round in MODES {
+            let r = Real::with_round(40, round);
+            for ab in rationals(40, 7).chunks(2) {
+                let (a, b) = (r.from_rational(&ab[0]), r.from_rational(&ab[1]));
+                let (x, y) = (a.v.0.to_rational().unwrap(), b.v.0.to_rational().unwrap());
+                let cases = [
+                    (a.copy() + b.copy(), Rational::from(&x + &y)),
+                    (a.copy() - b.copy(), Rational::from(&x - &y)),
+                    (a.copy() * b.copy(), Rational::from(&x * &y)),
+                    (a.copy() / b.copy(), Rational::from(&x / &y)),
+                ];
+                for (value, exact) in cases {
+                    let (expected, _) = Float::with_val_round(40, &exact, round);
+                    assert_eq!(value.v.0, expected, "{round:?}");
+                    assert_eq!(value.v.0.prec(), 40);
+                }
+            }
+        }
+        // Values are rounded into the structure
+        let r = Real::with_round(10, Round::Up);
+        assert_eq!(r.el(Float::with_val(100, 1.0001)).v.0, 1.001953125);
+        assert_eq!(r.to_string(), "real 10 round up");
+        assert_eq!(Real::new(10).to_string(), "real 10");
+        // NaN equals itself
+        let nan = r.el(Float::with_val(10, rug::float::Special::Nan));
+        assert_eq!(nan, nan.copy());
+    }
+}
@@ -0,0 +1,304 @@
+use crate::{DensePolynomial, El, Int, SSub, Structure};
+use rug::{Integer, Rational};
+use std::cmp::Ordering;
+
+impl DensePolynomial<Int> {
+    /// Intervals `(a, b)`, one for each distinct real root of the nonzero `p`, in increasing order:
+    /// either `a = b` is the root, or `a < b` and the open interval contains exactly that root.
+    /// By the Vincent–Collins–Akritas bisection of an interval bounding the roots, deciding each
+    /// half by Descartes' rule of signs.
+    pub fn isolate_real_roots(&self, p: &El<'_, Self>) -> Vec<(Rational, Rational)> {
+        let q = self.square_free_part(p);
+        let mut ret = Vec::new();
+        let mut q = q.v.into_owned();
+        if q[0] == 0 {
+            ret.push((Rational::new(), Rational::new()));
+            q.remove(0);
+        }
+        // 2^e bounds the absolute values of the roots, by Cauchy's bound 1 + max |q_i / q_n|
+        let lc = Integer::from(q.last().unwrap().abs_ref());
+        let max = q.iter().map(|c| Integer::from(c.abs_ref())).max().unwrap();
+        let e = (max / lc + 1u32).significant_bits();
+        let negated: Vec<Integer> = q
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                if i % 2 == 1 {
+                    Integer::from(-c)
+                } else {
+                    c.clone()
+                }
+            })
+            .collect();
+        for (sign, poly) in [(-1, negated), (1, q)] {
+            // Roots of poly(2^e x) in (0, 1)
+            let scaled = poly
+                .iter()
+                .enumerate()
+                .map(|(i, c)| Integer::from(c << (e * i as u32)))
+                .collect();
+            for (a, b) in self.roots_in_unit_interval(scaled) {
+                let (a, b) = (a << e, b << e);
+                ret.push(if sign < 0 { (-b, -a) } else { (a, b) });
+            }
+        }
+        ret.sort_by(|(a, _), (b, _)| a.cmp(b));
+        ret
+    }
+    /// The Sturm sequence `p, p', -rem(p, p'), ...` down to the greatest common divisor of `p` and
+    /// `p'`, computed by pseudo-division and scaled to primitive parts without changing signs
+    pub fn sturm_sequence<'a>(&'a self, p: &El<'_, Self>) -> Vec<El<'a, Self>> {
+        assert_eq!(self, p.s);
+        let mut seq = vec![self.el((*p.v).clone())];
+        if p.v.is_empty() {
+            return seq;
+        }
+        let dp = self.derivative(p);
+        if dp.v.is_empty() {
+            return seq;
+        }
+        seq.push(self.primitive_part(&dp));
+        loop {
+            let [a, b] = &seq[seq.len() - 2..] else {
+                unreachable!()
+            };
+            let (_, r) = self.pseudo_div_rem(a, b);
+            if r.v.is_empty() {
+                return seq;
+            }
+            // The pseudo-remainder is lc(b)^(deg a - deg b + 1) times the remainder
+            let lc = b.v.last().unwrap();
+            let flip = *lc < 0 && (a.v.len() - b.v.len()) % 2 == 0;
+            let mut r = self.primitive_part(&r);
+            if !flip {
+                self.negate(&mut r.v);
+            }
+            seq.push(r);
+        }
+    }
+    /// The number of distinct real roots of the nonzero `p` in the half-open interval `(a, b]`,
+    /// by the sign variations at the endpoints of the Sturm sequence of the square-free part
+    pub fn count_real_roots(&self, p: &El<'_, Self>, a: &Rational, b: &Rational) -> usize {
+        assert!(a <= b, "empty interval");
+        let seq = self.sturm_sequence(&self.square_free_part(p));
+        let variations = |x: &Rational| {
+            let signs: Vec<_> = seq.iter().map(|q| sign_at(&q.v, x)).collect();
+            sign_variations(&signs)
+        };
+        variations(a) - variations(b)
+    }
+    /// An interval of width at most `width` containing the root of `p` in `interval`, one from
+    /// `isolate_real_roots`, by bisection with exact signs at the midpoints
+    pub fn refine_real_root(
+        &self,
+        p: &El<'_, Self>,
+        interval: &(Rational, Rational),
+        width: &Rational,
+    ) -> (Rational, Rational) {
+        assert!(*width > 0, "refinement to zero width");
+        let q = self.square_free_part(p);
+        let (mut a, mut b) = interval.clone();
+        if a == b {
+            return (a, b);
+        }
+        // The signs just inside the endpoints, which may be other roots, all simple
+        let dq = self.derivative(&q);
+        let sign_a = match sign_at(&q.v, &a) {
+            Ordering::Equal => sign_at(&dq.v, &a),
+            s => s,
+        };
+        let sign_b = match sign_at(&q.v, &b) {
+            Ordering::Equal => sign_at(&dq.v, &b).reverse(),
+            s => s,
+        };
+        assert!(sign_a != sign_b, "not an isolating interval");
+        while Rational::from(&b - &a) > *width {
+            let mid = Rational::from(&a + &b) / 2u32;
+            match sign_at(&q.v, &mid) {
+                Ordering::Equal => return (mid.clone(), mid),
+                s if s == sign_a => a = mid,
+                _ => b = mid,
+            }
+        }
+        (a, b)
+    }
+    /// `p` divided by its greatest common divisor with `p'`, which has the same roots, each
+    /// simple
+    pub(crate) fn square_free_part<'a>(&'a self, p: &El<'_, Self>) -> El<'a, Self> {
+        assert_eq!(self, p.s);
+        assert!(!p.v.is_empty(), "roots of the zero polynomial");
+        let g = p.gcd(&self.derivative(p));
+        self.quo(p, &g)
+    }
+    /// Isolating intervals of the roots in `(0, 1)` of the square-free `p`, with nodes
+    /// `(q, c, k)` for `q` the polynomial whose roots in `(0, 1)` are those of `p` in
+    /// `(c / 2^k, (c + 1) / 2^k)` scaled to `(0, 1)`
+    fn roots_in_unit_interval(&self, p: Vec<Integer>) -> Vec<(Rational, Rational)> {
+        let mut ret = Vec::new();
+        let mut stack = vec![(p, Integer::ZERO, 0u32)];
+        let one = Int.el(Integer::from(1));
+        while let Some((q, c, k)) = stack.pop() {
+            let q = self.el(q);
+            let n = self.degree(&q).unwrap();
+            let endpoints = |c: &Integer| {
+                let a = Rational::from((c.clone(), Integer::from(1) << k));
+                let b = Rational::from((c.clone() + 1u32, Integer::from(1) << k));
+                (a, b)
+            };
+            // The roots in (0, 1) are the positive roots of (x + 1)^n q(1 / (x + 1))
+            let shifted = self.taylor_shift(&self.reverse(&q, n), &one);
+            match sign_variations(&shifted.v.iter().map(|x| x.cmp0()).collect::<Vec<_>>()) {
+                0 => continue,
+                1 => {
+                    ret.push(endpoints(&c));
+                    continue;
+                }
+                _ => {}
+            }
+            // 2^n q(x / 2) and 2^n q((x + 1) / 2) for the halves
+            let left = self.el(q
+                .v
+                .iter()
+                .enumerate()
+                .map(|(i, a)| Integer::from(a << (n - i) as u32))
+                .collect::<Vec<_>>());
+            let mut right = self.taylor_shift(&left, &one).v.into_owned();
+            let c = c << 1u32;
+            if right[0] == 0 {
+                let mid = Integer::from(&c + 1u32);
+                let root = Rational::from((mid, Integer::from(1) << (k + 1)));
+                ret.push((root.clone(), root));
+                right.remove(0);
+            }
+            stack.push((right, Integer::from(&c + 1u32), k + 1));
+            stack.push((left.v.into_owned(), c, k + 1));
+        }
+        ret
+    }
+}
+
+/// The sign of `p` at `x`, from the homogenized value at its numerator and denominator
+fn sign_at(p: &[Integer], x: &Rational) -> Ordering {
+    let (num, den) = (x.numer(), x.denom());
+    let mut acc = Integer::new();
+    let mut den_power = Integer::from(1);
+    for c in p.iter().rev() {
+        acc = acc * num + Integer::from(c * &den_power);
+        den_power *= den;
+    }
+    acc.cmp0()
+}
+
+/// The number of sign changes in a sequence, skipping zeros
+fn sign_variations(signs: &[Ordering]) -> usize {
+    let nonzero: Vec<_> = signs.iter().filter(|s| **s != Ordering::Equal).collect();
+    nonzero.windows(2).filter(|w| w[0] != w[1]).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ints(v: &[i64]) -> Vec<Integer> {
+        v.iter().map(|&c| Integer::from(c)).collect()
+    }
+
+    /// Checks that the intervals are ordered, disjoint and each counted once by
+    /// `count_real_roots`, which also counts them all together
+    fn check_isolation(zx: &DensePolynomial<Int>, p: &El<'_, DensePolynomial<Int>>) -> usize {
+        let intervals = zx.isolate_real_roots(p);
+        for (i, (a, b)) in intervals.iter().enumerate() {
+            assert!(a <= b);
+            if i > 0 {
+                assert!(intervals[i - 1].1 <= *a);
+            }
+            if a == b {
+                assert_eq!(sign_at(&p.v, a), Ordering::Equal);
+                continue;
+            }
+            // (a, b] holds the root and maybe one at b, another isolated root
+            let at_b = usize::from(sign_at(&p.v, b) == Ordering::Equal);
+            assert_eq!(zx.count_real_roots(p, a, b), 1 + at_b, "{p} ({a}, {b})");
+        }
+        let bound = Rational::from(1 << 20);
+        assert_eq!(
+            zx.count_real_roots(p, &-bound.clone(), &bound),
+            intervals.len()
+        );
+        intervals.len()
+    }
+
+    #[test]
+    fn isolation_of_known_roots() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        // (x - 1)^2 (2x + 3) x (3x - 1)^3 (x^2 + 1), with the rational roots found exactly or
+        // in intervals around them
+        let factors = [[-1, 1], [-1, 1], [3, 2], [0, 1], [-1, 3], [-1, 3], [-1, 3]];
+        let mut p = zx.el(ints(&[1, 0, 1]));
+        for f in factors {
+            p *= zx.el(ints(&f));
+        }
+        assert_eq!(check_isolation(&zx, &p), 4);
+        let roots = [
+            Rational::from((-3, 2)),
+            Rational::new(),
+            Rational::from((1, 3)),
+            Rational::from(1),
+        ];
+        for ((a, b), r) in Iterator::zip(zx.isolate_real_roots(&p).iter(), &roots) {
+            assert!(a == b && a == r || a < r && r < b, "{r} in ({a}, {b})");
+        }
+        // Without real roots, with only irrational ones and with many close ones
+        assert_eq!(check_isolation(&zx, &zx.el(ints(&[1, 0, 1]))), 0);
+        assert_eq!(check_isolation(&zx, &zx.el(ints(&[-2, 0, 1]))), 2);
+        assert_eq!(check_isolation(&zx, &zx.el(ints(&[-2, 0, 0, 1]))), 1);
+        let mut wilkinson = zx.el(ints(&[1]));
+        for k in 1..=12 {
+            wilkinson *= zx.el(ints(&[-k, 1]));
+        }
+        assert_eq!(check_isolation(&zx, &wilkinson), 12);
+        let mut close = zx.el(ints(&[1]));
+        for k in 0..6 {
+            close *= zx.el(ints(&[-(1000 + k), 1000]));
+        }
+        assert_eq!(check_isolation(&zx, &close), 6);
+        // The Chebyshev polynomial T_9, with nine roots in (-1, 1)
+        let t9 = zx.el(ints(&[0, 9, 0, -120, 0, 432, 0, -576, 0, 256]));
+        assert_eq!(check_isolation(&zx, &t9), 9);
+        assert_eq!(
+            zx.count_real_roots(&t9, &Rational::from(-1), &Rational::from(1)),
+            9
+        );
+        assert_eq!(
+            zx.count_real_roots(&t9, &Rational::new(), &Rational::from(1)),
+            4
+        );
+    }
+
+    #[test]
+    fn refinement() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        let p = zx.el(ints(&[-2, 0, 1])) * zx.el(ints(&[-3, 0, 1]));
+        let width = Rational::from((1, 1u64 << 40));
+        let intervals = zx.isolate_real_roots(&p);
+        let squares = [3, 2, 2, 3];
+        for (interval, square) in Iterator::zip(intervals.iter(), squares) {
+            let (a, b) = zx.refine_real_root(&p, interval, &width);
+            assert!(Rational::from(&b - &a) <= width);
+            assert!(interval.0 <= a && b <= interval.1);
+            let (a2, b2) = (a.clone().square(), b.clone().square());
+            let (a2, b2) = if a2 < b2 { (a2, b2) } else { (b2, a2) };
+            assert!(a2 < square && square < b2, "{a} {b}");
+        }
+        // Exact roots stay put, and a dyadic one is found at a midpoint
+        let p = zx.el(ints(&[-3, 8])) * zx.el(ints(&[1, 0, 1]));
+        let [interval] = &zx.isolate_real_roots(&p)[..] else {
+            panic!()
+        };
+        let root = Rational::from((3, 8));
+        let (a, b) = zx.refine_real_root(&p, interval, &width);
+        assert!(a == b && a == root || a < root && root < b);
+        let exact = (root.clone(), root.clone());
+        assert_eq!(zx.refine_real_root(&p, &exact, &width), exact);
+    }
+}
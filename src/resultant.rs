@@ -0,0 +1,287 @@
+use crate::{
+    DensePolynomial, El, SDiv, SField, SFusedMulAdd, SRem, SSub, Structure, SuperStructure,
+    polynomial_gcd::pow,
+};
+use std::{borrow::Cow, mem};
+
+impl<S: SFusedMulAdd> DensePolynomial<S> {
+    /// The Sylvester matrix of `p` of degree `m` and `q` of degree `n`: `n` rows of the
+    /// coefficients of `p` followed by `m` rows of those of `q`, highest degree first, each row
+    /// shifted one column right of the one above. Its determinant is the resultant.
+    pub fn sylvester_matrix<'a>(
+        &'a self,
+        p: &El<'_, Self>,
+        q: &El<'_, Self>,
+    ) -> Vec<Vec<El<'a, S>>> {
+        assert_eq!(self, p.s);
+        assert_eq!(self, q.s);
+        let (m, n) = (self.degree(p), self.degree(q));
+        let (Some(m), Some(n)) = (m, n) else {
+            panic!("sylvester matrix of the zero polynomial");
+        };
+        let s = self.inner();
+        let size = m + n;
+        let mut ret = Vec::with_capacity(size);
+        for (v, count) in [(&p.v, n), (&q.v, m)] {
+            for i in 0..count {
+                let row = (0..size).map(|j| match j.checked_sub(i) {
+                    Some(k) if k < v.len() => s.el(v[v.len() - 1 - k].clone()),
+                    _ => s.el(s.zero().into_owned()),
+                });
+                ret.push(row.collect());
+            }
+        }
+        ret
+    }
+}
+
+impl<S: SFusedMulAdd + SDiv + SSub> DensePolynomial<S> {
+    /// The resultant, the determinant of the Sylvester matrix, by the subresultant algorithm,
+    /// which only divides exactly
+    pub fn resultant<'a>(&'a self, p: &El<'_, Self>, q: &El<'_, Self>) -> El<'a, S> {
+        assert_eq!(self, p.s);
+        assert_eq!(self, q.s);
+        let s = self.inner();
+        let zero = || El { v: s.zero(), s };
+        if p.v.is_empty() || q.v.is_empty() {
+            return zero();
+        }
+        let (mut a, mut b) = (self.el((*p.v).clone()), self.el((*q.v).clone()));
+        let mut negate = false;
+        if a.v.len() < b.v.len() {
+            mem::swap(&mut a, &mut b);
+            negate = self.degree(&a).unwrap() % 2 == 1 && self.degree(&b).unwrap() % 2 == 1;
+        }
+        let one = s.one().into_owned();
+        let (mut g, mut h) = (one.clone(), one);
+        while self.degree(&b).is_some_and(|d| d > 0) {
+            let (da, db) = (self.degree(&a).unwrap(), self.degree(&b).unwrap());
+            let delta = da - db;
+            if da % 2 == 1 && db % 2 == 1 {
+                negate = !negate;
+            }
+            let r = self.pseudo_div_rem(&a, &b).1;
+            a = b;
+            // r / (g h^delta)
+            let divisor = s.mul(Cow::Borrowed(&g), Cow::Owned(pow(s, &h, delta)));
+            b = self.el(r
+                .v
+                .iter()
+                .map(|c| {
+                    s.div(Cow::Borrowed(c), Cow::Borrowed(&*divisor))
+                        .into_owned()
+                })
+                .collect::<Vec<_>>());
+            g = a.v.last().unwrap().clone();
+            // h^(1 - delta) g^delta
+            if delta != 0 {
+                let num = pow(s, &g, delta);
+                h = s
+                    .div(Cow::Owned(num), Cow::Owned(pow(s, &h, delta - 1)))
+                    .into_owned();
+            }
+        }
+        let Some(lc) = b.v.last() else {
+            return zero();
+        };
+        // h^(1 - deg a) lc(b)^(deg a)
+        let da = self.degree(&a).unwrap();
+        let num = pow(s, lc, da);
+        let mut res = match da {
+            0 => Cow::Owned(num),
+            _ => s.div(Cow::Owned(num), Cow::Owned(pow(s, &h, da - 1))),
+        };
+        if negate {
+            s.negate(&mut res);
+        }
+        El {
+            v: Cow::Owned(res.into_owned()),
+            s,
+        }
+    }
+    /// The discriminant `(-1)^(n (n - 1) / 2) res(p, p') / lc(p)` of `p` of degree `n`, zero
+    /// exactly when `p` has a repeated root
+    pub fn discriminant<'a>(&'a self, p: &El<'_, Self>) -> El<'a, S> {
+        let s = self.inner();
+        let Some(n) = self.degree(p) else {
+            panic!("discriminant of the zero polynomial");
+        };
+        if n == 0 {
+            return El { v: s.one(), s };
+        }
+        let res = self.resultant(p, &self.derivative(p));
+        let mut disc = s.div(res.v, Cow::Borrowed(p.v.last().unwrap()));
+        if n % 4 >= 2 {
+            s.negate(&mut disc);
+        }
+        El {
+            v: Cow::Owned(disc.into_owned()),
+            s,
+        }
+    }
+}
+
+impl<S: SFusedMulAdd + SField> DensePolynomial<S> {
+    /// `resultant` by Euclid's algorithm, using `res(a, b) = (-1)^(deg a deg b) lc(b)^(deg a -
+    /// deg r) res(b, r)` for `r` the remainder of `a` by `b`
+    pub fn resultant_euclid<'a>(&'a self, p: &El<'_, Self>, q: &El<'_, Self>) -> El<'a, S> {
+        assert_eq!(self, p.s);
+        assert_eq!(self, q.s);
+        let s = self.inner();
+        let (mut a, mut b) = ((*p.v).clone(), (*q.v).clone());
+        let mut acc = s.one().into_owned();
+        loop {
+            let (Some(da), Some(db)) = (a.len().checked_sub(1), b.len().checked_sub(1)) else {
+                acc = s.zero().into_owned();
+                break;
+            };
+            if db == 0 {
+                acc = s
+                    .mul(Cow::Owned(acc), Cow::Owned(pow(s, &b[0], da)))
+                    .into_owned();
+                break;
+            }
+            let r = self.rem(Cow::Borrowed(&a), Cow::Borrowed(&b)).into_owned();
+            if !r.is_empty() {
+                let factor = pow(s, b.last().unwrap(), da - (r.len() - 1));
+                acc = s.mul(Cow::Owned(acc), Cow::Owned(factor)).into_owned();
+            }
+            if da % 2 == 1 && db % 2 == 1 {
+                let mut neg = Cow::Owned(acc);
+                s.negate(&mut neg);
+                acc = neg.into_owned();
+            }
+            a = mem::replace(&mut b, r);
+        }
+        El {
+            v: Cow::Owned(acc),
+            s,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Int, Mod};
+    use rug::{Integer, Rational, ops::Pow};
+
+    /// `len` pseudorandom coefficients in `[-50, 50]`
+    fn coefficients(len: usize, seed: u64) -> Vec<Integer> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                Integer::from((state >> 33) % 101) - 50
+            })
+            .collect()
+    }
+
+    /// The determinant by Gaussian elimination over the rationals
+    fn determinant(rows: Vec<Vec<Integer>>) -> Integer {
+        let mut m: Vec<Vec<Rational>> = rows
+            .into_iter()
+            .map(|r| r.into_iter().map(Rational::from).collect())
+            .collect();
+        let mut det = Rational::from(1);
+        for i in 0..m.len() {
+            let Some(pivot) = (i..m.len()).find(|&k| m[k][i] != 0) else {
+                return Integer::ZERO;
+            };
+            if pivot != i {
+                m.swap(pivot, i);
+                det = -det;
+            }
+            det *= &m[i][i];
+            let (above, below) = m.split_at_mut(i + 1);
+            let pivot_row = &above[i];
+            for row in below {
+                let c = Rational::from(&row[i] / &pivot_row[i]);
+                for (a, b) in Iterator::zip(row[i..].iter_mut(), &pivot_row[i..]) {
+                    *a -= Rational::from(&c * b);
+                }
+            }
+        }
+        det.into_numer_denom().0
+    }
+
+    #[test]
+    fn resultants_agree_with_sylvester_determinants() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        let fp = Mod::new_prime(Int.el(1_000_003));
+        let fpx = DensePolynomial::new_symb("x", &fp);
+        for (i, (m, n)) in [(1, 1), (2, 1), (1, 4), (3, 3), (5, 2), (4, 6), (7, 7)]
+            .into_iter()
+            .enumerate()
+        {
+            let mut p = coefficients(m + 1, 2 * i as u64);
+            let mut q = coefficients(n + 1, 2 * i as u64 + 1);
+            p[m] = Integer::from(i + 2);
+            q[n] = Integer::from(-3);
+            let (p, q) = (zx.el(p), zx.el(q));
+            let matrix = zx.sylvester_matrix(&p, &q);
+            assert_eq!(matrix.len(), m + n);
+            let det = determinant(
+                matrix
+                    .into_iter()
+                    .map(|r| r.into_iter().map(|c| c.v.into_owned()).collect())
+                    .collect(),
+            );
+            assert_eq!(*zx.resultant(&p, &q).v, det, "{m} {n}");
+            // res(q, p) = (-1)^(m n) res(p, q)
+            let sign = if m * n % 2 == 1 { -1 } else { 1 };
+            assert_eq!(*zx.resultant(&q, &p).v, det.clone() * sign);
+            let (p, q) = (fpx.el(p.v.into_owned()), fpx.el(q.v.into_owned()));
+            let expected = det.modulo(&Integer::from(1_000_003));
+            assert_eq!(*fpx.resultant_euclid(&p, &q).v, expected);
+            assert_eq!(*fpx.resultant(&p, &q).v, expected);
+        }
+        // A common root makes the resultant vanish
+        let g = zx.el(coefficients(3, 20));
+        let p = g.copy() * zx.el(coefficients(4, 21));
+        let q = g.copy() * zx.el(coefficients(3, 22));
+        assert_eq!(*zx.resultant(&p, &q).v, 0);
+        let zero = zx.el(Vec::new());
+        assert_eq!(*zx.resultant(&p, &zero).v, 0);
+    }
+
+    #[test]
+    fn discriminants_of_quadratics_and_cubics() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        for (i, c) in [[1, 2, 3], [-4, 0, 1], [5, -7, 2], [1, 2, 1]]
+            .iter()
+            .enumerate()
+        {
+            let [c0, b, a] = c.map(Integer::from);
+            let disc = zx.discriminant(&zx.el(vec![c0.clone(), b.clone(), a.clone()]));
+            assert_eq!(*disc.v, b.clone() * &b - 4 * a * c0, "{i}");
+        }
+        for (i, c) in [
+            [1, 0, 0, 1],
+            [2, -3, 0, 1],
+            [1, 2, 3, 4],
+            [-5, 7, 11, -2],
+            [0, 0, 1, 1],
+        ]
+        .iter()
+        .enumerate()
+        {
+            let [d, c1, b, a] = c.map(Integer::from);
+            let disc = zx.discriminant(&zx.el(vec![d.clone(), c1.clone(), b.clone(), a.clone()]));
+            let expected = b.clone().square() * c1.clone().square()
+                - 4 * a.clone() * c1.clone().pow(3)
+                - 4 * b.clone().pow(3) * &d
+                - 27 * a.clone().square() * d.clone().square()
+                + 18 * a * b * c1 * d;
+            assert_eq!(*disc.v, expected, "{i}");
+        }
+        // x^3 - 3x + 2 = (x - 1)^2 (x + 2)
+        assert_eq!(
+            *zx.discriminant(&zx.el(vec![2.into(), (-3).into(), 0.into(), 1.into()]))
+                .v,
+            0
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use crate::{
+    DensePolynomial, El, Int, Mod, SDiv, SGcd, SPolynomialGcd, SRem, Structure, SuperStructure,
+};
+use rug::Integer;
+use std::borrow::Cow;
+
+/// `(c, [(f_1, e_1), (f_2, e_2), ...])` for `c f_1^e_1 f_2^e_2 ...`, a constant times powers of
+/// polynomials
+pub type Factorization<'a, S> = (El<'a, S>, Vec<(El<'a, DensePolynomial<S>>, u32)>);
+
+impl<S: SPolynomialGcd> DensePolynomial<S> {
+    /// `(c, [(f_1, e_1), (f_2, e_2), ...])` with `p = c f_1^e_1 f_2^e_2 ...`, the `f_i` square-free,
+    /// pairwise coprime, normalized and of positive degree, with increasing multiplicities `e_i`.
+    /// Uses Yun's algorithm in characteristic zero, and `p`-th roots of the coefficients in
+    /// characteristic `p`, which panics unless the coefficients have them, as in finite fields.
+    pub fn square_free_factorization<'a>(&'a self, p: &El<'_, Self>) -> Factorization<'a, S> {
+        assert_eq!(self, p.s);
+        assert!(!p.v.is_empty(), "square-free factorization of zero");
+        let s = self.inner();
+        let unit = s.unit_part(p.v.last().unwrap());
+        let c = s.mul(self.content(p).v, Cow::Owned(unit)).into_owned();
+        let q = self.div_coeffs(&p.v, &c);
+        let factors = match s.characteristic() {
+            p if p == 0 => self.yun(q),
+            p => self.square_free_char_p(q, &p),
+        };
+        (s.el(c), factors)
+    }
+    /// Yun's algorithm on a primitive normalized `p`: with `b_1 = p / gcd(p, p')` and
+    /// `c_1 = p' / gcd(p, p')`, each `f_i = gcd(b_i, c_i - b_i')` and the next `b`, `c` are the
+    /// quotients of `b_i`, `c_i - b_i'` by it
+    fn yun<'a>(&'a self, p: El<'a, Self>) -> Vec<(El<'a, Self>, u32)> {
+        let dp = self.derivative(&p);
+        let g = p.gcd(&dp);
+        let (mut b, mut c) = (self.quo(&p, &g), self.quo(&dp, &g));
+        let mut ret = Vec::new();
+        let mut i = 1;
+        while self.degree(&b).is_some_and(|d| d > 0) {
+            let d = c - self.derivative(&b);
+            let f = b.gcd(&d);
+            b = self.quo(&b, &f);
+            c = self.quo(&d, &f);
+            if self.degree(&f).is_some_and(|d| d > 0) {
+                ret.push((f, i));
+            }
+            i += 1;
+        }
+        ret
+    }
+    /// In characteristic `p`: the factors whose multiplicities are not multiples of `p` come
+    /// from repeatedly splitting `w = p / gcd(p, p')` against the rest, and what remains is a
+    /// polynomial in `x^p`, the `p`-th power of a polynomial whose coefficients are the `p`-th
+    /// roots of its own
+    fn square_free_char_p<'a>(
+        &'a self,
+        p: El<'a, Self>,
+        char_p: &Integer,
+    ) -> Vec<(El<'a, Self>, u32)> {
+        let mut ret = Vec::new();
+        let mut c = p.gcd(&self.derivative(&p));
+        let mut w = self.quo(&p, &c);
+        let mut i = 1;
+        while self.degree(&w).is_some_and(|d| d > 0) {
+            let y = w.gcd(&c);
+            let f = self.quo(&w, &y);
+            if self.degree(&f).is_some_and(|d| d > 0) {
+                ret.push((f, i));
+            }
+            c = self.quo(&c, &y);
+            w = y;
+            i += 1;
+        }
+        if self.degree(&c).is_some_and(|d| d > 0) {
+            let root = self
+                .pth_root(&c.v)
+                .expect("coefficients without p-th roots");
+            // A nonconstant c has zero derivative, so p is at most its degree
+            let multiplier = char_p.to_u32().unwrap();
+            let root_factors = self.square_free_char_p(self.el(root), char_p);
+            ret.extend(root_factors.into_iter().map(|(g, e)| (g, e * multiplier)));
+            ret.sort_by_key(|(_, e)| *e);
+        }
+        ret
+    }
+    /// The exact quotient
+    pub(crate) fn quo<'a>(&'a self, a: &El<'_, Self>, b: &El<'_, Self>) -> El<'a, Self> {
+        self.el(self
+            .div(Cow::Borrowed(&*a.v), Cow::Borrowed(&*b.v))
+            .into_owned())
+    }
+    /// The factorization with `split` giving the irreducible factors of each square-free part,
+    /// ordered by degree and then by coefficients from the top
+    pub(crate) fn factor_square_free_parts<'a>(
+        &'a self,
+        p: &El<'_, Self>,
+        mut split: impl FnMut(&El<'a, Self>) -> Vec<El<'a, Self>>,
+    ) -> Factorization<'a, S>
+    where
+        S::V: Ord,
+    {
+        let (c, parts) = self.square_free_factorization(p);
+        let mut ret = Vec::new();
+        for (f, e) in parts {
+            ret.extend(split(&f).into_iter().map(|g| (g, e)));
+        }
+        ret.sort_by(|(a, _), (b, _)| {
+            Ord::cmp(&a.v.len(), &b.v.len()).then_with(|| a.v.iter().rev().cmp(b.v.iter().rev()))
+        });
+        (c, ret)
+    }
+}
+
+impl DensePolynomial<Mod<Int, true>> {
+    /// `[(g_1, d_1), (g_2, d_2), ...]` for a square-free `p` over a prime field `Z/q`, with `g_i`
+    /// the monic product of the irreducible factors of `p` of degree `d_i`, in increasing order of
+    /// degree. The factors of degree `d` divide `x^(q^d) - x`, and those of lower degree have
+    /// already been removed.
+    pub fn distinct_degree_factorization<'a>(
+        &'a self,
+        p: &El<'_, Self>,
+    ) -> Vec<(El<'a, Self>, u32)> {
+        assert_eq!(self, p.s);
+        assert!(!p.v.is_empty(), "distinct-degree factorization of zero");
+        let q = self.inner().modulus().v.into_owned();
+        let x = vec![Integer::ZERO, Integer::from(1)];
+        let mut f = self.normalized(self.el((*p.v).clone()));
+        // x^(q^d) modulo f
+        let mut h = x.clone();
+        let mut ret = Vec::new();
+        let mut d = 1;
+        while self.degree(&f).is_some_and(|n| n >= 2 * d as usize) {
+            let quotient = Mod::new(self.el((*f.v).clone()));
+            h = quotient.el(h).pow(&q).v.into_owned();
+            let g = f.gcd(&(self.el(h.clone()) - self.el(x.clone())));
+            if self.degree(&g).is_some_and(|n| n > 0) {
+                f = self.quo(&f, &g);
+                h = self.rem(Cow::Owned(h), Cow::Borrowed(&f.v)).into_owned();
+                ret.push((g, d));
+            }
+            d += 1;
+        }
+        if let Some(n) = self.degree(&f)
+            && n > 0
+        {
+            ret.push((f, u32::try_from(n).unwrap()));
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GF, SMul};
+
+    fn ints(v: &[i64]) -> Vec<Integer> {
+        v.iter().map(|&c| Integer::from(c)).collect()
+    }
+
+    /// Checks that the factorization of `p` multiplies back to it, with square-free, pairwise
+    /// coprime factors of positive degree and increasing multiplicities, and returns the
+    /// factors and multiplicities
+    fn checked_factorization<S: SPolynomialGcd>(
+        poly: &DensePolynomial<S>,
+        p: &El<'_, DensePolynomial<S>>,
+    ) -> Vec<(Vec<S::V>, u32)> {
+        let (c, factors) = poly.square_free_factorization(p);
+        let mut product = poly.el(vec![c.v.into_owned()]);
+        for (i, (f, e)) in factors.iter().enumerate() {
+            assert!(poly.degree(f).is_some_and(|d| d > 0));
+            assert_eq!(f.gcd(&poly.derivative(f)), poly.el(poly.one().into_owned()));
+            assert_eq!(poly.normalized(f.copy()), *f);
+            for (g, d) in &factors[..i] {
+                assert!(d < e);
+                assert_eq!(f.gcd(g), poly.el(poly.one().into_owned()));
+            }
+            product *= f.pow(&Integer::from(*e));
+        }
+        assert_eq!(product, *p);
+        factors
+            .into_iter()
+            .map(|(f, e)| (f.v.into_owned(), e))
+            .collect()
+    }
+
+    #[test]
+    fn characteristic_zero() {
+        let zx = DensePolynomial::new_symb("x", &Int);
+        // -6 (x + 1) (x^2 + 1)^2 (x - 2)^3
+        let (a, b, c) = (
+            zx.el(ints(&[1, 1])),
+            zx.el(ints(&[1, 0, 1])),
+            zx.el(ints(&[-2, 1])),
+        );
+        let p = zx.el(ints(&[-6])) * a.copy() * b.pow(&2.into()) * c.pow(&3.into());
+        let factors = checked_factorization(&zx, &p);
+        assert_eq!(
+            factors,
+            [
+                (a.v.into_owned(), 1),
+                (b.v.into_owned(), 2),
+                (c.v.into_owned(), 3)
+            ]
+        );
+        // Factors sharing a multiplicity are multiplied together, contents are pulled out
+        let p =
+            zx.el(ints(&[4, 2])) * zx.el(ints(&[3, 0, 1])) * zx.el(ints(&[1, 5])).pow(&4.into());
+        let factors = checked_factorization(&zx, &p);
+        assert_eq!(factors, [(ints(&[6, 3, 2, 1]), 1), (ints(&[1, 5]), 4)]);
+        assert_eq!(zx.square_free_factorization(&p).0, Int.el(2));
+        for seed in 1..20 {
+            let mut state: u64 = seed;
+            let mut random = |len: usize| {
+                let v: Vec<Integer> = (0..len)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        Integer::from((state >> 33) % 7) - 3
+                    })
+                    .collect();
+                zx.el(v)
+            };
+            let p = random(3) * random(2).pow(&2.into()) * random(4).pow(&3.into()) * random(3);
+            if !p.v.is_empty() {
+                checked_factorization(&zx, &p);
+            }
+        }
+    }
+
+    #[test]
+    fn positive_characteristic() {
+        // (x + 1) (x^2 + 1)^3 (x + 2)^4 (x^3 + 2x + 1)^6 over F_3, where the cube and its multiples
+        // need p-th roots
+        let f3 = Mod::new_prime(Int.el(3));
+        let f3x = DensePolynomial::new_symb("x", &f3);
+        let parts = [
+            (ints(&[1, 1]), 1),
+            (ints(&[1, 0, 1]), 3),
+            (ints(&[2, 1]), 4),
+            (ints(&[1, 2, 0, 1]), 6),
+        ];
+        let mut p = f3x.el(ints(&[2]));
+        for (f, e) in &parts {
+            p *= f3x.el(f.clone()).pow(&Integer::from(*e));
+        }
+        assert_eq!(checked_factorization(&f3x, &p), parts);
+        // A pure p-th power, x^9 + 1 = (x + 1)^9
+        let p = f3x.el(ints(&[1, 0, 0, 0, 0, 0, 0, 0, 0, 1]));
+        assert_eq!(checked_factorization(&f3x, &p), [(ints(&[1, 1]), 9)]);
+        // Over GF(9), where the p-th roots of the coefficients are not themselves
+        let gf9 = GF::new_symb("a", Int.el(3), 2);
+        let gf9x = DensePolynomial::new_symb("x", &gf9);
+        let a = gf9.symb();
+        let x = gf9x.symb();
+        let linear = x.copy() + a.copy().lift(&gf9x);
+        let quadratic = x.copy() * x.copy()
+            + (a.copy() * a.copy() + gf9.el(gf9.one().into_owned())).lift(&gf9x);
+        let p = linear.copy().pow(&3.into()) * quadratic.copy() * x.pow(&2.into());
+        let factors = checked_factorization(&gf9x, &p);
+        assert_eq!(factors.len(), 3);
+        assert_eq!(factors[2], (linear.v.into_owned(), 3));
+    }
+
+    #[test]
+    fn distinct_degrees() {
+        let f5 = Mod::new_prime(Int.el(5));
+        let f5x = DensePolynomial::new_symb("x", &f5);
+        // x (x + 1) (x^2 + 2) (x^2 + 3) (x^3 + x + 1)
+        let factors = [
+            ints(&[0, 1]),
+            ints(&[1, 1]),
+            ints(&[2, 0, 1]),
+            ints(&[3, 0, 1]),
+            ints(&[1, 1, 0, 1]),
+        ];
+        let p = factors
+            .iter()
+            .fold(f5x.el(f5x.one().into_owned()), |acc, f| {
+                acc * f5x.el(f.clone())
+            });
+        let parts = f5x.distinct_degree_factorization(&(p.copy() * f5x.el(ints(&[3]))));
+        let degree = |i: usize, j: usize| f5x.el(factors[i].clone()) * f5x.el(factors[j].clone());
+        assert_eq!(
+            parts,
+            [
+                (degree(0, 1), 1),
+                (degree(2, 3), 2),
+                (f5x.el(factors[4].clone()), 3)
+            ]
+        );
+    }
+}